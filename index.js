@@ -1,14 +1,34 @@
-import { start, Application } from './pkg';
+import { start, window_size, Application } from './pkg';
 
 start();
 
 const canvas = document.createElement('canvas');
-canvas.width = 1080;
-canvas.height = 720;
+const [width, height] = window_size();
+canvas.width = width;
+canvas.height = height;
+// focusable so that it receives keyboard events
+canvas.tabIndex = 0;
 document.body.appendChild(canvas);
 
 const app = new Application(canvas);
 
+const importCampaign = document.createElement('input');
+importCampaign.type = 'file';
+importCampaign.accept = '.json,application/json';
+importCampaign.title = 'Import a campaign';
+importCampaign.addEventListener('change', async () => {
+	const [file] = importCampaign.files;
+	if (file) {
+		try {
+			app.import_campaign(await file.text());
+		} catch (err) {
+			alert(`Couldn't import ${file.name}: ${err}`);
+		}
+		importCampaign.value = '';
+	}
+});
+document.body.appendChild(importCampaign);
+
 const loop = () => {
 	app.update(performance.now());
 	requestAnimationFrame(loop);
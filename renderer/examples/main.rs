@@ -26,16 +26,24 @@ fn main() {
     let mut ctx = solstice_2d::solstice::Context::new(ctx);
     let physical_size = window_ctx.window().inner_size();
     ctx.set_viewport(0, 0, physical_size.width as _, physical_size.height as _);
-    let mut renderer = renderer::Renderer {
-        d2: renderer::Graphics::new(
+    let mut renderer = renderer::Renderer::new(
+        renderer::Graphics::new(
             &mut ctx,
             physical_size.width as _,
             physical_size.height as _,
         )
         .unwrap(),
-    };
+    );
+    renderer.set_constellation(
+        &(0..15)
+            .map(|index| renderer::SpiritNode {
+                group: index / 5,
+                strength: (index % 5) as f32 / 4.,
+            })
+            .collect::<Vec<_>>(),
+    );
 
-    let epoch = std::time::Instant::now();
+    let mut last_frame = std::time::Instant::now();
 
     event_loop.run(move |event, _target, cf| {
         use glutin::event::*;
@@ -59,25 +67,12 @@ fn main() {
                 window_ctx.window().request_redraw();
             }
             Event::RedrawRequested(_) => {
-                solstice_2d::solstice::Renderer::clear(
-                    &mut ctx,
-                    solstice_2d::solstice::ClearSettings {
-                        color: Some(
-                            solstice_2d::solstice::Color {
-                                red: 1f32,
-                                blue: 1.,
-                                green: 1.,
-                                alpha: 1.,
-                            }
-                            .into(),
-                        ),
-                        ..Default::default()
-                    },
-                );
+                renderer.clear(&mut ctx);
 
-                const DURATION: f32 = 10.;
-                let t = epoch.elapsed().as_secs_f32() % DURATION / DURATION;
-                renderer.draw(&mut ctx, t);
+                let now = std::time::Instant::now();
+                renderer.update(now.duration_since(last_frame).as_secs_f32());
+                last_frame = now;
+                renderer.draw(&mut ctx);
                 window_ctx
                     .swap_buffers()
                     .expect("terrible, terrible damage");
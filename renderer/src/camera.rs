@@ -0,0 +1,49 @@
+use crate::{Rad, Transform3D};
+
+/// A camera that orbits the origin of the scene.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    /// Rotation about the vertical axis, in radians.
+    pub yaw: f32,
+    /// Rotation about the horizontal axis, in radians.
+    pub pitch: f32,
+    /// Distance from the origin.
+    pub distance: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            yaw: 0.,
+            pitch: 0.,
+            distance: 1.5,
+        }
+    }
+}
+
+impl Camera {
+    pub const MIN_DISTANCE: f32 = 0.75;
+    pub const MAX_DISTANCE: f32 = 6.;
+    /// Pitch is kept just short of straight up or down so the view never flips.
+    pub const MAX_PITCH: f32 = std::f32::consts::FRAC_PI_2 - 0.01;
+
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = (self.yaw + yaw) % std::f32::consts::TAU;
+        self.pitch = (self.pitch + pitch).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+    }
+
+    /// Multiplies the distance from the origin by `factor`.
+    pub fn zoom(&mut self, factor: f32) {
+        self.distance = (self.distance * factor).clamp(Self::MIN_DISTANCE, Self::MAX_DISTANCE);
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn transform(&self) -> Transform3D {
+        Transform3D::translation(0., 0., -self.distance)
+            * Transform3D::rotation(Rad(self.pitch), Rad(0.), Rad(0.))
+            * Transform3D::rotation(Rad(0.), Rad(self.yaw), Rad(0.))
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{LineVertex, Palette};
+
+/// The number of stat groups the spirits are arranged into.
+pub const GROUP_COUNT: usize = 3;
+
+/// A single spirit as seen by the renderer.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct SpiritNode {
+    /// Index of the stat group this spirit belongs to. Must be less than `GROUP_COUNT`.
+    pub group: usize,
+    /// 0 for an unselected spirit, otherwise in `(0, 1]` from weakest to strongest connection.
+    pub strength: f32,
+}
+
+/// Holds the spirits to visualize and eases the displayed state towards them.
+#[derive(Debug, Clone, Default)]
+pub struct Constellation {
+    targets: Vec<SpiritNode>,
+    current: Vec<f32>,
+}
+
+impl Constellation {
+    pub fn set_nodes(&mut self, nodes: &[SpiritNode]) {
+        self.current.resize(nodes.len(), 0.);
+        self.targets.clear();
+        self.targets.extend_from_slice(nodes);
+    }
+
+    /// Moves each displayed node `amount` (0 to 1) of the way towards its target.
+    pub fn step(&mut self, amount: f32) {
+        let amount = amount.clamp(0., 1.);
+        for (current, target) in self.current.iter_mut().zip(self.targets.iter()) {
+            *current += (target.strength - *current) * amount;
+        }
+    }
+
+    /// The displayed position and strength of every node.
+    pub fn nodes(&self) -> Vec<([f32; 3], f32)> {
+        let mut counts = [0usize; GROUP_COUNT];
+        for node in self.targets.iter() {
+            counts[node.group % GROUP_COUNT] += 1;
+        }
+
+        let mut seen = [0usize; GROUP_COUNT];
+        self.targets
+            .iter()
+            .zip(self.current.iter())
+            .map(|(node, strength)| {
+                let group = node.group % GROUP_COUNT;
+                let index = seen[group];
+                seen[group] += 1;
+                (position(group, index, counts[group]), *strength)
+            })
+            .collect()
+    }
+
+    /// Rings joining the spirits of each group, plus spokes out to the group's hub.
+    pub fn lines(&self, nodes: &[([f32; 3], f32)], palette: &Palette) -> Vec<LineVertex> {
+        fn lv(position: [f32; 3], color: [f32; 4]) -> LineVertex {
+            LineVertex {
+                position,
+                width: 2.0,
+                color,
+            }
+        }
+
+        const CONNECTOR: [f32; 4] = [0., 0., 0., 0.];
+
+        let mut points = Vec::new();
+        for group in 0..GROUP_COUNT {
+            let members = self
+                .targets
+                .iter()
+                .zip(nodes.iter())
+                .filter(|(node, _)| node.group % GROUP_COUNT == group)
+                .map(|(_, node)| *node)
+                .collect::<Vec<_>>();
+            let first = match members.first() {
+                Some(first) => *first,
+                None => continue,
+            };
+
+            let hub = hub(group);
+            for (position, strength) in members.iter() {
+                let mut color = node_color(*strength, palette);
+                color[3] = 0.5;
+                points.extend_from_slice(&[
+                    lv(hub, CONNECTOR),
+                    lv(hub, color),
+                    lv(*position, color),
+                    lv(*position, CONNECTOR),
+                ]);
+            }
+
+            points.push(lv(first.0, CONNECTOR));
+            for (position, strength) in members.iter().chain(std::iter::once(&first)) {
+                let mut color = node_color(*strength, palette);
+                color[3] = 0.75;
+                points.push(lv(*position, color));
+            }
+            points.push(lv(first.0, CONNECTOR));
+        }
+        points
+    }
+}
+
+/// The radius of a node's geometry for a given strength.
+pub fn node_radius(strength: f32) -> f32 {
+    0.025 + 0.05 * strength.clamp(0., 1.)
+}
+
+/// Blends from the palette's weak color for the weakest connections to its strong color.
+pub fn node_color(strength: f32, palette: &Palette) -> [f32; 4] {
+    let Palette { weak, strong, .. } = palette;
+    let s = strength.clamp(0., 1.);
+    let alpha = if s > 0. { 0.9 } else { 0.2 };
+    [
+        weak[0] + (strong[0] - weak[0]) * s,
+        weak[1] + (strong[1] - weak[1]) * s,
+        weak[2] + (strong[2] - weak[2]) * s,
+        alpha,
+    ]
+}
+
+fn hub(group: usize) -> [f32; 3] {
+    const HUB_RADIUS: f32 = 0.6;
+    let angle = angle(group);
+    [angle.cos() * HUB_RADIUS, angle.sin() * HUB_RADIUS, 0.]
+}
+
+fn angle(group: usize) -> f32 {
+    std::f32::consts::FRAC_PI_2 + group as f32 * std::f32::consts::TAU / GROUP_COUNT as f32
+}
+
+fn position(group: usize, index: usize, count: usize) -> [f32; 3] {
+    const RING_RADIUS: f32 = 0.3;
+    let [hx, hy, _] = hub(group);
+    let radial = angle(group);
+    let phi = index as f32 * std::f32::consts::TAU / count.max(1) as f32;
+    let outward = phi.cos() * RING_RADIUS;
+    [
+        hx + radial.cos() * outward,
+        hy + radial.sin() * outward,
+        phi.sin() * RING_RADIUS,
+    ]
+}
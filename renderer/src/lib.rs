@@ -1,59 +1,227 @@
+mod camera;
+mod constellation;
+pub mod picking;
+
+pub use camera::Camera;
+pub use constellation::{node_color, node_radius, Constellation, SpiritNode, GROUP_COUNT};
+pub use picking::Pick;
 pub use solstice_2d::*;
 
+/// The colors the scene is drawn with.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Palette {
+    /// The background the scene is drawn over.
+    pub clear: [f32; 4],
+    /// The weakest spirit connection.
+    pub weak: [f32; 3],
+    /// The strongest spirit connection.
+    pub strong: [f32; 3],
+    pub die: [f32; 3],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            clear: [1., 1., 1., 1.],
+            weak: [0.35, 0.4, 0.65],
+            strong: [1., 0.75, 0.1],
+            die: [1., 0., 0.],
+        }
+    }
+}
+
+/// Radius of the die at the center of the scene.
+const DIE_RADIUS: f32 = 0.2;
+/// Each pentagonal face of the dodecahedron is made of three triangles.
+const DIE_TRIANGLES_PER_FACE: usize = 3;
+
 pub struct Renderer {
     pub d2: solstice_2d::Graphics,
+    constellation: Constellation,
+    camera: Camera,
+    /// The projection the scene is drawn with, for picking.
+    perspective: picking::Perspective,
+    viewport: [f32; 2],
+    hovered: Option<Pick>,
+    palette: Palette,
+    rotation: f32,
+    animated: bool,
+    /// The die's triangles, for picking.
+    die_vertices: Vec<picking::Vec3>,
+    die_indices: Vec<u32>,
 }
 
 impl Renderer {
-    pub fn draw(&mut self, ctx: &mut solstice::Context, t: f32) {
-        ctx.enable(solstice_2d::solstice::Feature::CullFace(
-            solstice_2d::solstice::CullFace::Back,
-            solstice_2d::solstice::VertexWinding::CounterClockWise,
-        ));
-        let mut g = self.d2.lock(ctx);
+    pub fn new(d2: solstice_2d::Graphics) -> Self {
+        let die = Polyhedron::dodecahedron(DIE_RADIUS, 0);
+        Self {
+            d2,
+            constellation: Default::default(),
+            camera: Default::default(),
+            perspective: Default::default(),
+            viewport: [1., 1.],
+            hovered: None,
+            palette: Default::default(),
+            rotation: 0.,
+            animated: true,
+            die_vertices: die.vertices.iter().map(|p| [p.x, p.y, p.z]).collect(),
+            die_indices: die.indices.iter().map(|i| *i as u32).collect(),
+        }
+    }
 
-        g.set_camera(Transform3D::translation(0., 0., -1.5));
+    /// Resizes the area drawn to, in physical pixels.
+    pub fn set_viewport(&mut self, width: f32, height: f32) {
+        self.d2.set_width_height(width, height);
+        self.viewport = [width, height];
+    }
 
-        let tx = Transform3D::rotation(Rad(0.), Rad(t * std::f32::consts::TAU), Rad(0.));
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
 
-        let geometry = Polyhedron::dodecahedron(1., 0);
+    /// When not animated the scene holds still and the constellation jumps straight to its
+    /// targets.
+    pub fn set_animated(&mut self, animated: bool) {
+        self.animated = animated;
+    }
 
-        let mut points = Vec::new();
+    /// Clears the whole viewport to the palette's background.
+    pub fn clear(&mut self, ctx: &mut solstice::Context) {
+        let [red, green, blue, alpha] = self.palette.clear;
+        solstice::Renderer::clear(
+            ctx,
+            solstice::ClearSettings {
+                color: Some(
+                    solstice::Color {
+                        red,
+                        blue,
+                        green,
+                        alpha,
+                    }
+                    .into(),
+                ),
+                ..Default::default()
+            },
+        );
+    }
 
-        const CONNECTORS: [[f32; 4]; 3] =
-            [[1., 0., 0., 0.25], [0., 1., 0., 0.25], [0., 0., 1., 0.25]];
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
 
-        for (index, indices) in geometry.indices.chunks(3).enumerate() {
-            let p1 = geometry.vertices[indices[0] as usize].normalize();
-            let p2 = geometry.vertices[indices[1] as usize].normalize();
-            let p3 = geometry.vertices[indices[2] as usize].normalize();
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
 
-            fn lv(p: Point3D, color: [f32; 4]) -> LineVertex {
-                LineVertex {
-                    position: [p.x, p.y, p.z],
-                    width: 2.0,
-                    color,
-                }
-            }
+    /// Advances animations by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        /// Radians per second the scene turns about its vertical axis.
+        const ROTATION_SPEED: f32 = std::f32::consts::TAU / 10.;
+        /// How quickly the constellation closes the gap to its targets, per second.
+        const EASING_RATE: f32 = 6.;
 
-            // const ALPHA: [f32; 4] = [0., 0., 0., 0.25];
-            let alpha = CONNECTORS[index % 3];
-            const COLOR: [f32; 4] = [0.1, 0.1, 0.1, 0.5];
-            points.extend_from_slice(&[
-                lv(p1, alpha),
-                lv(p1, COLOR),
-                lv(p2, COLOR),
-                lv(p3, COLOR),
-                lv(p3, alpha),
-            ]);
+        if !self.animated {
+            self.constellation.step(1.);
+            return;
         }
+        self.rotation = (self.rotation + dt * ROTATION_SPEED) % std::f32::consts::TAU;
+        self.constellation.step(1. - (-dt * EASING_RATE).exp());
+    }
+
+    /// Highlights an object in the scene, typically the one under the cursor.
+    pub fn set_hovered(&mut self, hovered: Option<Pick>) {
+        self.hovered = hovered;
+    }
+
+    /// The nearest object under `cursor`, given in physical pixels from the top left.
+    pub fn pick(&self, cursor: [f32; 2]) -> Option<Pick> {
+        let ray = picking::Ray::from_screen(cursor, self.viewport, &self.camera, &self.perspective)
+            .unrotate_y(self.rotation);
+
+        let die = picking::ray_mesh(&ray, &self.die_vertices, &self.die_indices)
+            .map(|(triangle, t)| (Pick::DieFace(triangle / DIE_TRIANGLES_PER_FACE), t));
+
+        let nodes = self
+            .constellation
+            .nodes()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, (position, strength))| {
+                picking::ray_sphere(&ray, position, node_radius(strength))
+                    .map(|t| (Pick::Spirit(index), t))
+            });
+
+        die.into_iter()
+            .chain(nodes)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(pick, _)| pick)
+    }
+
+    /// Sets the spirits to visualize. Changes are animated over the following draws.
+    pub fn set_constellation(&mut self, nodes: &[SpiritNode]) {
+        self.constellation.set_nodes(nodes);
+    }
+
+    pub fn draw(&mut self, ctx: &mut solstice::Context) {
+        ctx.enable(solstice_2d::solstice::Feature::CullFace(
+            solstice_2d::solstice::CullFace::Back,
+            solstice_2d::solstice::VertexWinding::CounterClockWise,
+        ));
+        let mut g = self.d2.lock(ctx);
+
+        g.set_camera(self.camera.transform());
+
+        let tx = Transform3D::rotation(Rad(0.), Rad(self.rotation), Rad(0.));
+
+        let nodes = self.constellation.nodes();
 
         g.set_transform(tx);
-        g.line_3d(points);
+        g.line_3d(self.constellation.lines(&nodes, &self.palette));
         g.set_transform(Transform3D::default());
 
-        g.draw_with_color_and_transform(geometry, [1., 0., 0., 0.1], tx);
-        // g.stroke_with_color_and_transform(geometry, [0., 0., 0., 1.], tx);
+        for (index, ([x, y, z], strength)) in nodes.into_iter().enumerate() {
+            let hovered = self.hovered == Some(Pick::Spirit(index));
+            let (radius, color) = if hovered {
+                let [red, green, blue, _] = node_color(strength, &self.palette);
+                (node_radius(strength) * 1.3, [red, green, blue, 1.])
+            } else {
+                (node_radius(strength), node_color(strength, &self.palette))
+            };
+            g.draw_with_color_and_transform(
+                Polyhedron::icosahedron(radius, 0),
+                color,
+                tx * Transform3D::translation(x, y, z),
+            );
+        }
+
+        let die = Polyhedron::dodecahedron(DIE_RADIUS, 0);
+        if let Some(Pick::DieFace(face)) = self.hovered {
+            let [red, green, blue] = self.palette.die;
+            let color = [red, green, blue, 1.];
+            let lv = |p: Point3D| LineVertex {
+                position: [p.x, p.y, p.z],
+                width: 3.0,
+                color,
+            };
+            let start = face * DIE_TRIANGLES_PER_FACE * 3;
+            let end = (start + DIE_TRIANGLES_PER_FACE * 3).min(die.indices.len());
+            let outline = die.indices[start..end]
+                .chunks(3)
+                .flat_map(|triangle| {
+                    [triangle[0], triangle[1], triangle[2], triangle[0]]
+                        .map(|i| lv(die.vertices[i as usize]))
+                })
+                .collect::<Vec<_>>();
+            g.set_transform(tx);
+            g.line_3d(outline);
+            g.set_transform(Transform3D::default());
+        }
+        let [red, green, blue] = self.palette.die;
+        let die_color = match self.hovered {
+            Some(Pick::DieFace(_)) => [red, green, blue, 0.35],
+            _ => [red, green, blue, 0.1],
+        };
+        g.draw_with_color_and_transform(die, die_color, tx);
         drop(g);
         ctx.disable(solstice_2d::solstice::Feature::CullFace(
             solstice_2d::solstice::CullFace::Back,
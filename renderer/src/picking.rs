@@ -0,0 +1,276 @@
+//! Ray casting from the screen into the scene.
+//!
+//! Everything here is plain math on `[f32; 3]` so it can be used and tested without a GL context.
+//! Rotations follow the right-handed convention used by `Transform3D::rotation`.
+
+use crate::Camera;
+
+pub type Vec3 = [f32; 3];
+
+/// Something in the scene that can be clicked.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Pick {
+    /// A face of the die, numbered from 0.
+    DieFace(usize),
+    /// A spirit node, by its index in the slice given to `Renderer::set_constellation`.
+    Spirit(usize),
+}
+
+/// The perspective projection the scene is drawn with. The default is the one `solstice_2d`
+/// gives 3D geometry when no projection is set, `Projection::Perspective(None)`, which the
+/// scene leaves in place; its aspect ratio always follows the viewport.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Perspective {
+    /// Vertical field of view, in radians.
+    pub fovy: f32,
+}
+
+impl Default for Perspective {
+    fn default() -> Self {
+        Self {
+            fovy: std::f32::consts::FRAC_PI_2,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// Normalized direction.
+    pub direction: Vec3,
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction: normalize(direction),
+        }
+    }
+
+    /// The ray under the cursor, in world space.
+    ///
+    /// `cursor` and `viewport` are in the same units with the origin at the top left.
+    pub fn from_screen(
+        cursor: [f32; 2],
+        viewport: [f32; 2],
+        camera: &Camera,
+        perspective: &Perspective,
+    ) -> Self {
+        let [width, height] = viewport;
+        let aspect = width / height.max(1.);
+        let x = 2. * cursor[0] / width.max(1.) - 1.;
+        let y = 1. - 2. * cursor[1] / height.max(1.);
+        let tan = (perspective.fovy / 2.).tan();
+        let direction = [x * tan * aspect, y * tan, -1.];
+
+        // undo the camera's view transform: translate back out, then un-pitch and un-yaw
+        let unview = |v: Vec3| rotate_y(rotate_x(v, -camera.pitch), -camera.yaw);
+        Self::new(unview([0., 0., camera.distance]), unview(direction))
+    }
+
+    /// This ray with the inverse of a rotation of `angle` about the vertical axis applied, which
+    /// moves it into the space of geometry drawn with that rotation.
+    pub fn unrotate_y(&self, angle: f32) -> Self {
+        Self {
+            origin: rotate_y(self.origin, -angle),
+            direction: rotate_y(self.direction, -angle),
+        }
+    }
+
+    pub fn at(&self, distance: f32) -> Vec3 {
+        add(self.origin, scale(self.direction, distance))
+    }
+}
+
+/// Distance along `ray` to the triangle `abc`, using the Möller–Trumbore algorithm.
+///
+/// Triangles are hit from either side.
+pub fn ray_triangle(ray: &Ray, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+    const EPSILON: f32 = 1e-6;
+    let edge1 = sub(b, a);
+    let edge2 = sub(c, a);
+    let p = cross(ray.direction, edge2);
+    let determinant = dot(edge1, p);
+    if determinant.abs() < EPSILON {
+        return None;
+    }
+    let inverse = 1. / determinant;
+    let s = sub(ray.origin, a);
+    let u = dot(s, p) * inverse;
+    if !(0. ..=1.).contains(&u) {
+        return None;
+    }
+    let q = cross(s, edge1);
+    let v = dot(ray.direction, q) * inverse;
+    if v < 0. || u + v > 1. {
+        return None;
+    }
+    let t = dot(edge2, q) * inverse;
+    (t > EPSILON).then_some(t)
+}
+
+/// Distance along `ray` to the nearest point of the sphere, if it's in front of the origin.
+pub fn ray_sphere(ray: &Ray, center: Vec3, radius: f32) -> Option<f32> {
+    let to_center = sub(ray.origin, center);
+    let b = dot(to_center, ray.direction);
+    let c = dot(to_center, to_center) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0. {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    [-b - root, -b + root].into_iter().find(|t| *t > 0.)
+}
+
+/// The index of the nearest triangle hit in an indexed triangle list, and the distance to it.
+pub fn ray_mesh(ray: &Ray, vertices: &[Vec3], indices: &[u32]) -> Option<(usize, f32)> {
+    indices
+        .chunks_exact(3)
+        .enumerate()
+        .filter_map(|(index, triangle)| {
+            let [a, b, c] = [0, 1, 2].map(|i| vertices[triangle[i] as usize]);
+            ray_triangle(ray, a, b, c).map(|t| (index, t))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+pub fn rotate_x(v: Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    [v[0], v[1] * cos - v[2] * sin, v[1] * sin + v[2] * cos]
+}
+
+pub fn rotate_y(v: Vec3, angle: f32) -> Vec3 {
+    let (sin, cos) = angle.sin_cos();
+    [v[0] * cos + v[2] * sin, v[1], -v[0] * sin + v[2] * cos]
+}
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: Vec3, s: f32) -> Vec3 {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vec3, b: Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize(a: Vec3) -> Vec3 {
+    let length = dot(a, a).sqrt();
+    if length > 0. {
+        scale(a, 1. / length)
+    } else {
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    const TRIANGLE: [Vec3; 3] = [[-1., -1., 0.], [1., -1., 0.], [0., 1., 0.]];
+
+    #[test]
+    fn ray_hits_triangle_head_on() {
+        let ray = Ray::new([0., 0., 2.], [0., 0., -1.]);
+        let [a, b, c] = TRIANGLE;
+        let t = ray_triangle(&ray, a, b, c).unwrap();
+        assert!((t - 2.).abs() < 1e-5);
+        assert_close(ray.at(t), [0., 0., 0.]);
+    }
+
+    #[test]
+    fn ray_hits_triangle_from_behind() {
+        let ray = Ray::new([0., 0., -3.], [0., 0., 1.]);
+        let [a, b, c] = TRIANGLE;
+        assert_eq!(ray_triangle(&ray, a, b, c), Some(3.));
+    }
+
+    #[test]
+    fn ray_misses_triangle() {
+        let [a, b, c] = TRIANGLE;
+        let beside = Ray::new([2., 0., 2.], [0., 0., -1.]);
+        assert_eq!(ray_triangle(&beside, a, b, c), None);
+        let away = Ray::new([0., 0., 2.], [0., 0., 1.]);
+        assert_eq!(ray_triangle(&away, a, b, c), None);
+        let parallel = Ray::new([0., 0., 2.], [1., 0., 0.]);
+        assert_eq!(ray_triangle(&parallel, a, b, c), None);
+    }
+
+    #[test]
+    fn ray_mesh_returns_nearest_triangle() {
+        let vertices = [
+            [-1., -1., 0.],
+            [1., -1., 0.],
+            [0., 1., 0.],
+            [-1., -1., 1.],
+            [1., -1., 1.],
+            [0., 1., 1.],
+        ];
+        let indices = [0, 1, 2, 3, 4, 5];
+        let ray = Ray::new([0., 0., 5.], [0., 0., -1.]);
+        assert_eq!(ray_mesh(&ray, &vertices, &indices), Some((1, 4.)));
+    }
+
+    #[test]
+    fn ray_sphere_hits_front_surface() {
+        let ray = Ray::new([0., 0., 5.], [0., 0., -1.]);
+        assert_eq!(ray_sphere(&ray, [0., 0., 0.], 1.), Some(4.));
+        let inside = Ray::new([0., 0., 0.], [0., 0., -1.]);
+        assert_eq!(ray_sphere(&inside, [0., 0., 0.], 1.), Some(1.));
+        let miss = Ray::new([2., 0., 5.], [0., 0., -1.]);
+        assert_eq!(ray_sphere(&miss, [0., 0., 0.], 1.), None);
+    }
+
+    #[test]
+    fn screen_center_looks_at_origin() {
+        let camera = Camera {
+            yaw: 0.7,
+            pitch: -0.3,
+            distance: 2.,
+        };
+        let ray = Ray::from_screen([400., 300.], [800., 600.], &camera, &Perspective::default());
+        assert_close(ray.at(2.), [0., 0., 0.]);
+    }
+
+    #[test]
+    fn screen_edges_follow_field_of_view() {
+        let camera = Camera::default();
+        let perspective = Perspective::default();
+        // with a 90 degree field of view the top edge is one unit up for every unit forward
+        let ray = Ray::from_screen([300., 0.], [600., 600.], &camera, &perspective);
+        assert_close(normalize(ray.direction), normalize([0., 1., -1.]));
+        let ray = Ray::from_screen([600., 300.], [600., 600.], &camera, &perspective);
+        assert_close(normalize(ray.direction), normalize([1., 0., -1.]));
+    }
+
+    #[test]
+    fn unrotate_matches_rotated_geometry() {
+        let angle = 1.2;
+        let point = [0.5, 0.25, -0.1];
+        let ray = Ray::new([0., 0., 3.], sub(rotate_y(point, angle), [0., 0., 3.]));
+        let local = ray.unrotate_y(angle);
+        let t = ray_sphere(&local, point, 0.01).unwrap();
+        assert_close(local.at(t + 0.01), point);
+    }
+}
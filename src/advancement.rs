@@ -0,0 +1,443 @@
+//! Advancing campaign members after creation, and looking back over how they got there.
+
+use crate::{
+    campaign::{Campaign, MemberChoice},
+    content::{self, BurdenId, PrerogativeId},
+    progression::{Change, Progression},
+    session_log,
+    settings::labelled,
+    sheet,
+    theme::Theme,
+};
+use iced_winit::{
+    widget::{
+        button, pick_list, scrollable, slider, text_input, Button, Column, PickList, Row,
+        Scrollable, Slider, TextInput,
+    },
+    Element, Length,
+};
+use spirits_within::{Burden, Prerogative, Spirit};
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MemberSelected(MemberChoice),
+    /// Moves along the timeline to just after that many advances.
+    StepChanged(f32),
+    KindSelected(ChangeKind),
+    SpiritSelected(SpiritName),
+    SwapSelected(SpiritName),
+    BurdenSelected(BurdenId),
+    PrerogativeSelected(PrerogativeId),
+    SlotSelected(Slot),
+    NotesChanged(String),
+    Record,
+    Undo,
+}
+
+#[derive(Debug)]
+pub struct Advancement {
+    member: Option<MemberChoice>,
+    /// How many advances in the sheet is shown, or `None` to follow the latest.
+    step: Option<usize>,
+    kind: ChangeKind,
+    spirit: Spirit,
+    swap_with: Spirit,
+    burden: BurdenId,
+    prerogative: PrerogativeId,
+    slot: usize,
+    notes: String,
+    /// Why the last change couldn't be recorded.
+    status: Option<String>,
+
+    member_picker: pick_list::State<MemberChoice>,
+    step_slider: slider::State,
+    history_scroll: scrollable::State,
+    kind_picker: pick_list::State<ChangeKind>,
+    spirit_picker: pick_list::State<SpiritName>,
+    swap_picker: pick_list::State<SpiritName>,
+    burden_picker: pick_list::State<BurdenId>,
+    prerogative_picker: pick_list::State<PrerogativeId>,
+    slot_picker: pick_list::State<Slot>,
+    notes_input: text_input::State,
+    record_button: button::State,
+    undo_button: button::State,
+}
+
+impl Default for Advancement {
+    fn default() -> Self {
+        Self {
+            member: None,
+            step: None,
+            kind: ChangeKind::ShiftConnection,
+            spirit: Spirit::LIST[0],
+            swap_with: Spirit::LIST[1],
+            burden: Burden::LIST[0].into(),
+            prerogative: Prerogative::LIST[0].into(),
+            slot: 0,
+            notes: String::new(),
+            status: None,
+            member_picker: Default::default(),
+            step_slider: Default::default(),
+            history_scroll: Default::default(),
+            kind_picker: Default::default(),
+            spirit_picker: Default::default(),
+            swap_picker: Default::default(),
+            burden_picker: Default::default(),
+            prerogative_picker: Default::default(),
+            slot_picker: Default::default(),
+            notes_input: Default::default(),
+            record_button: Default::default(),
+            undo_button: Default::default(),
+        }
+    }
+}
+
+impl Advancement {
+    /// Whether one of the screen's text inputs has keyboard focus.
+    pub fn is_editing_text(&self) -> bool {
+        self.notes_input.is_focused()
+    }
+
+    /// Applies `message`, returning whether the campaign changed and should be saved.
+    pub fn update(&mut self, message: Message, campaign: &mut Campaign) -> bool {
+        match message {
+            Message::MemberSelected(choice) => {
+                self.member = Some(choice);
+                self.step = None;
+                self.status = None;
+            }
+            Message::StepChanged(step) => self.step = Some(step.round() as usize),
+            Message::KindSelected(kind) => self.kind = kind,
+            Message::SpiritSelected(SpiritName(spirit)) => self.spirit = spirit,
+            Message::SwapSelected(SpiritName(spirit)) => self.swap_with = spirit,
+            Message::BurdenSelected(burden) => self.burden = burden,
+            Message::PrerogativeSelected(prerogative) => self.prerogative = prerogative,
+            Message::SlotSelected(Slot(slot)) => self.slot = slot,
+            Message::NotesChanged(notes) => self.notes = notes,
+            Message::Record => {
+                let change = self.change();
+                let result = match self.progression_mut(campaign) {
+                    Some(progression) => progression.advance(change, &self.notes),
+                    None => Err(eyre::eyre!("pick someone from the campaign first")),
+                };
+                self.step = None;
+                match result {
+                    Ok(()) => {
+                        self.notes.clear();
+                        self.status = None;
+                        return true;
+                    }
+                    Err(err) => self.status = Some(format!("{:#}", err)),
+                }
+            }
+            Message::Undo => {
+                self.step = None;
+                self.status = None;
+                return self
+                    .progression_mut(campaign)
+                    .and_then(Progression::undo)
+                    .is_some();
+            }
+        }
+        false
+    }
+
+    fn progression_mut<'a>(&self, campaign: &'a mut Campaign) -> Option<&'a mut Progression> {
+        let choice = self.member.as_ref()?;
+        campaign
+            .member_mut(choice)
+            .map(|member| &mut member.progression)
+    }
+
+    /// The change the form describes.
+    fn change(&self) -> Change {
+        match self.kind {
+            ChangeKind::ShiftConnection => Change::ShiftConnection {
+                spirit: self.spirit,
+                swap_with: self.swap_with,
+            },
+            ChangeKind::GainBurden => Change::GainBurden {
+                burden: self.burden.clone(),
+                prerogative: self.prerogative.clone(),
+            },
+            ChangeKind::BuyOffBurden => Change::BuyOffBurden {
+                burden: self.burden.clone(),
+            },
+            ChangeKind::ChangePrerogative => Change::ChangePrerogative {
+                slot: self.slot,
+                prerogative: self.prerogative.clone(),
+            },
+        }
+    }
+
+    pub fn view(&mut self, campaign: &Campaign, theme: Theme) -> Element<'_, Message, Renderer> {
+        let typography = theme.typography();
+        let progression = self
+            .member
+            .as_ref()
+            .and_then(|choice| campaign.member(choice))
+            .map(|member| &member.progression);
+
+        let mut timeline = Column::new()
+            .push(
+                PickList::new(
+                    &mut self.member_picker,
+                    campaign.choices(),
+                    self.member.clone(),
+                    Message::MemberSelected,
+                )
+                .placeholder("Pick someone from the campaign")
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            )
+            .spacing(5)
+            .width(Length::FillPortion(1));
+        let mut history = Scrollable::new(&mut self.history_scroll)
+            .height(Length::Fill)
+            .spacing(2);
+
+        if let Some(progression) = progression {
+            let last = progression.advances.len();
+            let step = self.step.unwrap_or(last).min(last);
+            let position = match step {
+                0 => "As created".to_owned(),
+                step => format!(
+                    "After {} of {} advances: {}",
+                    step,
+                    last,
+                    progression.advances[step - 1].change
+                ),
+            };
+            timeline = timeline.push(theme.body(position));
+            // a slider over nothing has no sensible position
+            if last > 0 {
+                timeline = timeline.push(
+                    Slider::new(
+                        &mut self.step_slider,
+                        0.0..=last as f32,
+                        step as f32,
+                        Message::StepChanged,
+                    )
+                    .step(1.0),
+                );
+            }
+            timeline = timeline.push(match progression.at(step) {
+                Ok(character) => sheet::view(&character, theme),
+                Err(err) => Column::new().push(theme.body(format!("{:#}", err))),
+            });
+
+            history = progression.advances.iter().enumerate().rev().fold(
+                history,
+                |history, (index, advance)| {
+                    let mut line = format!(
+                        "{}. {}: {}",
+                        index + 1,
+                        session_log::format_time(advance.time),
+                        advance.change
+                    );
+                    if !advance.notes.is_empty() {
+                        line.push_str(&format!(" ({})", advance.notes));
+                    }
+                    history.push(theme.body(line))
+                },
+            );
+        }
+
+        let spirits: Vec<SpiritName> = Spirit::LIST.into_iter().map(SpiritName).collect();
+        let mut form = Column::new().spacing(5).push(labelled(
+            theme,
+            "Advance",
+            PickList::new(
+                &mut self.kind_picker,
+                &ChangeKind::ALL[..],
+                Some(self.kind),
+                Message::KindSelected,
+            )
+            .font(typography.body.into())
+            .text_size(typography.body_size)
+            .style(theme.pick_list()),
+        ));
+        match self.kind {
+            ChangeKind::ShiftConnection => {
+                form = form
+                    .push(labelled(
+                        theme,
+                        "Spirit",
+                        PickList::new(
+                            &mut self.spirit_picker,
+                            spirits.clone(),
+                            Some(SpiritName(self.spirit)),
+                            Message::SpiritSelected,
+                        )
+                        .font(typography.body.into())
+                        .text_size(typography.body_size)
+                        .style(theme.pick_list()),
+                    ))
+                    .push(labelled(
+                        theme,
+                        "Swap connections with",
+                        PickList::new(
+                            &mut self.swap_picker,
+                            spirits,
+                            Some(SpiritName(self.swap_with)),
+                            Message::SwapSelected,
+                        )
+                        .font(typography.body.into())
+                        .text_size(typography.body_size)
+                        .style(theme.pick_list()),
+                    ));
+            }
+            ChangeKind::GainBurden | ChangeKind::BuyOffBurden => {
+                form = form.push(labelled(
+                    theme,
+                    "Burden",
+                    PickList::new(
+                        &mut self.burden_picker,
+                        content::active().burden_ids(),
+                        Some(self.burden.clone()),
+                        Message::BurdenSelected,
+                    )
+                    .font(typography.body.into())
+                    .text_size(typography.body_size)
+                    .style(theme.pick_list()),
+                ));
+            }
+            ChangeKind::ChangePrerogative => {
+                form = form.push(labelled(
+                    theme,
+                    "Base prerogative",
+                    PickList::new(
+                        &mut self.slot_picker,
+                        (0..crate::rules::BASE_PREROGATIVE_COUNT)
+                            .map(Slot)
+                            .collect::<Vec<_>>(),
+                        Some(Slot(self.slot)),
+                        Message::SlotSelected,
+                    )
+                    .font(typography.body.into())
+                    .text_size(typography.body_size)
+                    .style(theme.pick_list()),
+                ));
+            }
+        }
+        if matches!(
+            self.kind,
+            ChangeKind::GainBurden | ChangeKind::ChangePrerogative
+        ) {
+            form = form.push(labelled(
+                theme,
+                "Prerogative",
+                PickList::new(
+                    &mut self.prerogative_picker,
+                    content::active().prerogative_ids(),
+                    Some(self.prerogative.clone()),
+                    Message::PrerogativeSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            ));
+        }
+
+        let mut record =
+            Button::new(&mut self.record_button, theme.body("Record")).style(theme.button());
+        let mut undo = Button::new(&mut self.undo_button, theme.body("Undo last"))
+            .style(theme.danger_button());
+        if progression.is_some() {
+            record = record.on_press(Message::Record);
+        }
+        if progression.map_or(false, |progression| !progression.advances.is_empty()) {
+            undo = undo.on_press(Message::Undo);
+        }
+        form = form
+            .push(
+                TextInput::new(
+                    &mut self.notes_input,
+                    "Notes",
+                    &self.notes,
+                    Message::NotesChanged,
+                )
+                .on_submit(Message::Record)
+                .font(typography.body.into())
+                .size(typography.body_size)
+                .padding(2),
+            )
+            .push(
+                Row::new()
+                    .push(record)
+                    .push(undo)
+                    .spacing(5)
+                    .align_items(iced_winit::Alignment::Center),
+            );
+        if let Some(status) = &self.status {
+            form = form.push(theme.body(status));
+        }
+
+        Row::new()
+            .push(timeline)
+            .push(
+                Column::new()
+                    .push(theme.heading("History"))
+                    .push(history)
+                    .push(form)
+                    .spacing(5)
+                    .width(Length::FillPortion(1)),
+            )
+            .spacing(10)
+            .padding(5)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+/// The kinds of [`Change`], for picking one before filling in the rest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    ShiftConnection,
+    GainBurden,
+    BuyOffBurden,
+    ChangePrerogative,
+}
+
+impl ChangeKind {
+    const ALL: [ChangeKind; 4] = [
+        ChangeKind::ShiftConnection,
+        ChangeKind::GainBurden,
+        ChangeKind::BuyOffBurden,
+        ChangeKind::ChangePrerogative,
+    ];
+}
+
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ChangeKind::ShiftConnection => "Shift a connection",
+            ChangeKind::GainBurden => "Gain a burden",
+            ChangeKind::BuyOffBurden => "Buy off a burden",
+            ChangeKind::ChangePrerogative => "Change a base prerogative",
+        })
+    }
+}
+
+/// A spirit, displayed by name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SpiritName(Spirit);
+
+impl std::fmt::Display for SpiritName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+/// One of the base prerogative slots, numbered from 0.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Slot(usize);
+
+impl std::fmt::Display for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0 + 1)
+    }
+}
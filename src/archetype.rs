@@ -0,0 +1,214 @@
+//! Archetypes: named starting points for the character creator, filling in the spirits and base
+//! prerogatives of a coherent build for the player to tweak.
+//!
+//! They come from `resources/archetypes.toml`, with any in an `archetypes.toml` saved with the
+//! other user data added, replacing built-in ones of the same name.
+
+use crate::{
+    build::CharacterBuild,
+    character::Named,
+    content::PrerogativeId,
+    rules::{self, BASE_PREROGATIVE_COUNT, CONNECTION_COUNTS},
+    storage,
+};
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use spirits_within::Spirit;
+
+/// The storage key of the user's own archetypes.
+pub const KEY: &str = "archetypes.toml";
+
+const BUILT_IN: &str = include_str!("../resources/archetypes.toml");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Archetype {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Every spirit, closest first. They get the connections strongest first, as many of each as
+    /// a character has.
+    pub spirits: Vec<String>,
+    /// The base prerogatives, in slot order.
+    #[serde(default)]
+    pub prerogatives: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArchetypeFile {
+    #[serde(default)]
+    archetypes: Vec<Archetype>,
+}
+
+impl std::fmt::Display for Archetype {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl Archetype {
+    /// The built-in archetypes with the user's on top.
+    pub fn load() -> Vec<Self> {
+        let mut archetypes = Self::built_in();
+        if let Some(contents) = storage::load(KEY) {
+            match Self::from_toml(&contents) {
+                Ok(overrides) => {
+                    for archetype in overrides {
+                        archetypes.retain(|existing| {
+                            !existing.name.eq_ignore_ascii_case(&archetype.name)
+                        });
+                        archetypes.push(archetype);
+                    }
+                }
+                Err(err) => log::warn!("ignoring {}: {:?}", KEY, err),
+            }
+        }
+        archetypes
+    }
+
+    pub fn built_in() -> Vec<Self> {
+        Self::from_toml(BUILT_IN).expect("the built-in archetypes parse")
+    }
+
+    pub fn from_toml(contents: &str) -> eyre::Result<Vec<Self>> {
+        let file: ArchetypeFile = toml::from_str(contents).wrap_err("parsing archetypes")?;
+        for archetype in &file.archetypes {
+            archetype
+                .validate()
+                .wrap_err_with(|| format!("in archetype {:?}", archetype.name))?;
+        }
+        Ok(file.archetypes)
+    }
+
+    /// Checks that every spirit is listed exactly once and that the prerogatives fit the slots.
+    pub fn validate(&self) -> eyre::Result<()> {
+        if self.name.trim().is_empty() {
+            eyre::bail!("an archetype needs a name");
+        }
+        let spirits = self.spirits()?;
+        if let Some(missing) = Spirit::LIST
+            .into_iter()
+            .find(|spirit| !spirits.contains(spirit))
+        {
+            eyre::bail!("{:?} isn't listed", missing);
+        }
+        if spirits.len() != Spirit::LIST.len() {
+            eyre::bail!("every spirit must be listed exactly once");
+        }
+        if self.prerogatives.len() > BASE_PREROGATIVE_COUNT {
+            eyre::bail!(
+                "at most {} base prerogatives can be listed, found {}",
+                BASE_PREROGATIVE_COUNT,
+                self.prerogatives.len()
+            );
+        }
+        if let Some(empty) = self.prerogatives.iter().position(|p| p.trim().is_empty()) {
+            eyre::bail!("prerogative {} has no name", empty + 1);
+        }
+        Ok(())
+    }
+
+    fn spirits(&self) -> eyre::Result<Vec<Spirit>> {
+        self.spirits
+            .iter()
+            .map(|name| Spirit::from_name(name))
+            .collect()
+    }
+
+    /// The archetype's build in the active content. Each base prerogative is taken if it is still
+    /// allowed and keeps the stats within their maximums, and otherwise the first that does; a
+    /// slot stays empty if none do.
+    pub fn build(&self) -> eyre::Result<CharacterBuild> {
+        self.validate()?;
+        let mut spirits = self.spirits()?.into_iter();
+        let mut build = CharacterBuild::new();
+        for (connection, count) in CONNECTION_COUNTS {
+            for spirit in spirits.by_ref().take(count) {
+                build.set_connection(spirit, Some(connection));
+            }
+        }
+
+        for slot in 0..BASE_PREROGATIVE_COUNT {
+            let wanted = self
+                .prerogatives
+                .get(slot)
+                .map(|name| PrerogativeId::named(name));
+            let options = build.prerogative_options(slot);
+            let chosen = wanted
+                .into_iter()
+                .filter(|prerogative| options.contains(prerogative))
+                .chain(options.iter().cloned())
+                .find(|prerogative| {
+                    let mut trial = build.clone();
+                    trial.set_prerogative(slot, Some(prerogative.clone()));
+                    within_maximums(&trial)
+                });
+            build.set_prerogative(slot, chosen);
+        }
+        Ok(build)
+    }
+}
+
+fn within_maximums(build: &CharacterBuild) -> bool {
+    match build.preview_stats() {
+        Some(mut stats) => rules::STATS
+            .into_iter()
+            .all(|stat| *rules::base_stat(&mut stats, stat) <= rules::max_stat(stat)),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_archetypes_are_complete_and_legal() {
+        let archetypes = Archetype::built_in();
+        assert!(!archetypes.is_empty());
+        for archetype in archetypes {
+            let build = archetype.build().unwrap();
+            if let Err(err) = build.stats() {
+                panic!("{} isn't legal: {:#}", archetype.name, err);
+            }
+        }
+    }
+
+    #[test]
+    fn listed_prerogatives_fill_the_slots_in_order() {
+        let scholar = Archetype::built_in()
+            .into_iter()
+            .find(|archetype| archetype.name == "Scholar")
+            .unwrap();
+        let build = scholar.build().unwrap();
+        let first = PrerogativeId::named(&scholar.prerogatives[0]);
+        assert_eq!(build.prerogatives()[0], Some(first));
+        assert_eq!(
+            build.connection(Spirit::from_name(&scholar.spirits[0]).unwrap()),
+            Some(CONNECTION_COUNTS[0].0)
+        );
+    }
+
+    #[test]
+    fn bad_archetypes_are_rejected() {
+        let mut archetype = Archetype::built_in().remove(0);
+        archetype.spirits.pop();
+        assert!(archetype.validate().is_err());
+
+        let mut archetype = Archetype::built_in().remove(0);
+        let first = archetype.spirits[0].clone();
+        archetype.spirits[1] = first;
+        assert!(archetype.validate().is_err());
+
+        let mut archetype = Archetype::built_in().remove(0);
+        archetype.spirits[0] = "Nobody".into();
+        assert!(archetype.validate().is_err());
+
+        let mut archetype = Archetype::built_in().remove(0);
+        archetype.prerogatives = vec!["Education".into(); BASE_PREROGATIVE_COUNT + 1];
+        assert!(archetype.validate().is_err());
+
+        assert!(Archetype::from_toml("[[archetypes]]\nname = \"X\"\ncolour = 1\n").is_err());
+    }
+}
@@ -1,28 +1,53 @@
+use eyre::WrapErr;
 use glutin::{
     dpi::{PhysicalPosition, PhysicalSize},
     event::{Event, ModifiersState, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
 };
 use iced_glutin::{glutin, program, Clipboard, Debug, Size};
-use iced_solstice::{Backend, Renderer, Settings, Viewport};
+use iced_solstice::{Backend, Renderer, Viewport};
 use iced_winit::conversion;
 
-pub fn main() {
-    // env_logger::init();
+pub fn main() -> eyre::Result<()> {
+    use clap::Parser;
+    let args = spirits_within_app::cli::Args::parse();
+    let mut logger = env_logger::Builder::new();
+    logger
+        .filter_level(log::LevelFilter::Warn)
+        .parse_default_env();
+    if let Some(level) = args.log_level {
+        logger.filter_level(level);
+    }
+    logger.init();
+
+    let config = args.load_config()?;
+    let character = args.load_character()?;
+    let campaign = args.load_campaign()?;
+    let window_size = args
+        .window_size
+        .unwrap_or(spirits_within_app::cli::WindowSize {
+            width: config.window.width,
+            height: config.window.height,
+        });
 
     // Initialize winit
     let event_loop = EventLoop::new();
     let wb = iced_glutin::glutin::window::WindowBuilder::new()
         .with_title("Editor")
-        // .with_fullscreen(Some(glutin::window::Fullscreen::Borderless(None)))
-        .with_inner_size(PhysicalSize::new(1280, 720))
+        .with_fullscreen(
+            (args.fullscreen || config.window.fullscreen)
+                .then_some(glutin::window::Fullscreen::Borderless(None)),
+        )
+        .with_inner_size(PhysicalSize::new(window_size.width, window_size.height))
         .with_resizable(true);
     let window_ctx = iced_glutin::glutin::ContextBuilder::new()
-        .with_vsync(true)
-        .with_multisampling(8)
+        .with_vsync(config.vsync)
+        .with_multisampling(config.msaa)
         .build_windowed(wb, &event_loop)
-        .unwrap();
-    let window_ctx = unsafe { window_ctx.make_current() }.unwrap();
+        .wrap_err("creating the window")?;
+    let window_ctx = unsafe { window_ctx.make_current() }
+        .map_err(|(_, err)| err)
+        .wrap_err("making the GL context current")?;
     let gl = unsafe {
         // use glow::HasContext;
         use solstice::glow;
@@ -38,9 +63,10 @@ pub fn main() {
     let mut gl = solstice::Context::new(gl);
 
     let physical_size = window_ctx.window().inner_size();
+    let mut ui_scale = config.ui_scale;
     let mut viewport = Viewport::with_physical_size(
         Size::new(physical_size.width, physical_size.height),
-        window_ctx.window().scale_factor(),
+        window_ctx.window().scale_factor() * ui_scale as f64,
     );
     gl.set_viewport(
         0,
@@ -48,14 +74,18 @@ pub fn main() {
         viewport.physical_size().width as _,
         viewport.physical_size().height as _,
     );
-    let mut gfx = renderer::Renderer {
-        d2: renderer::Graphics::new(
+    let mut gfx = renderer::Renderer::new(
+        renderer::Graphics::new(
             &mut gl,
             viewport.physical_size().width as _,
             viewport.physical_size().height as _,
         )
         .unwrap(),
-    };
+    );
+    gfx.set_viewport(
+        viewport.physical_size().width as _,
+        viewport.physical_size().height as _,
+    );
 
     let mut cursor_position = PhysicalPosition::new(-1.0, -1.0);
     let mut modifiers = ModifiersState::default();
@@ -64,16 +94,31 @@ pub fn main() {
 
     // Initialize iced
     let mut debug = Debug::new();
-    let mut renderer = Renderer::new(Backend::new(&mut gl, Settings::default()));
+    let mut renderer = Renderer::new(Backend::new(&mut gl, spirits_within_app::fonts::settings()));
     let mut clipboard = Clipboard::connect(window_ctx.window());
 
-    let seed = rand::RngCore::next_u64(&mut rand::thread_rng());
-    let controls = spirits_within_app::Application::new(seed);
+    let seed = args.seed.unwrap_or_else(|| {
+        config
+            .seed
+            .seed(|| rand::RngCore::next_u64(&mut rand::thread_rng()))
+    });
+    let mut controls = spirits_within_app::Application::new(seed, config);
+    if let Some(character) = &character {
+        controls.open(character);
+    }
+    if let Some(campaign) = campaign {
+        controls.open_campaign(campaign);
+    }
+    if let Some(screen) = args.screen {
+        controls.set_state(screen.into());
+    }
 
     let mut state =
         program::State::new(controls, viewport.logical_size(), &mut renderer, &mut debug);
 
     let epoch = std::time::Instant::now();
+    let mut clock = spirits_within_app::clock::Clock::new();
+    let mut camera = spirits_within_app::camera_controller::CameraController::default();
 
     // Run event loop
     event_loop.run(move |event, _, control_flow| {
@@ -97,7 +142,7 @@ pub fn main() {
                     WindowEvent::Resized(new_size) => {
                         viewport = Viewport::with_physical_size(
                             Size::new(new_size.width, new_size.height),
-                            window_ctx.window().scale_factor(),
+                            window_ctx.window().scale_factor() * ui_scale as f64,
                         );
 
                         resized = true;
@@ -118,13 +163,41 @@ pub fn main() {
                 }
 
                 // Map window event to iced event
-                let scale_factor = window_ctx.window().scale_factor();
+                let scale_factor = viewport.scale_factor();
                 let event = conversion::window_event(&event, scale_factor, modifiers);
                 if let Some(event) = event {
-                    state.queue_event(event);
+                    // keys typed into a text input are the UI's alone
+                    let editing_text = state.program().is_editing_text();
+                    if !editing_text {
+                        clock.handle_event(&event);
+                    }
+                    let status = camera.handle_event(
+                        &event,
+                        state.mouse_interaction(),
+                        editing_text,
+                        gfx.camera_mut(),
+                    );
+                    if status == iced_winit::event::Status::Ignored {
+                        state.queue_event(event);
+                    }
                 }
             }
             Event::MainEventsCleared => {
+                // Anything under the cursor that the UI isn't using is hovered in the scene
+                let hovered = if state.mouse_interaction() == iced_winit::mouse::Interaction::Idle {
+                    gfx.pick([cursor_position.x as f32, cursor_position.y as f32])
+                } else {
+                    None
+                };
+                gfx.set_hovered(hovered);
+                if let (true, Some(pick)) = (camera.take_click(), hovered) {
+                    state.queue_message(spirits_within_app::Message::ScenePicked(pick));
+                }
+
+                for message in state.program().session_messages() {
+                    state.queue_message(message);
+                }
+
                 // If there are events pending
                 if !state.is_queue_empty() {
                     // We update iced
@@ -135,6 +208,16 @@ pub fn main() {
                         &mut clipboard,
                         &mut debug,
                     );
+
+                    let config = state.program().config();
+                    if config.ui_scale != ui_scale {
+                        ui_scale = config.ui_scale;
+                        let size = window_ctx.window().inner_size();
+                        viewport = Viewport::with_physical_size(
+                            Size::new(size.width, size.height),
+                            window_ctx.window().scale_factor() * ui_scale as f64,
+                        );
+                    }
                 }
                 // and request a redraw
                 window_ctx.window().request_redraw();
@@ -143,33 +226,21 @@ pub fn main() {
                 if resized {
                     let size = window_ctx.window().inner_size();
                     gl.set_viewport(0, 0, size.width as i32, size.height as i32);
-                    gfx.d2.set_width_height(size.width as _, size.height as _);
+                    gfx.set_viewport(size.width as _, size.height as _);
 
                     resized = false;
                 }
 
-                solstice::Renderer::clear(
-                    &mut gl,
-                    solstice::ClearSettings {
-                        color: Some(
-                            solstice::Color {
-                                red: 1f32,
-                                blue: 1.,
-                                green: 1.,
-                                alpha: 1.,
-                            }
-                            .into(),
-                        ),
-                        ..Default::default()
-                    },
-                );
-
-                {
-                    let now = std::time::Instant::now();
-                    const DURATION: f32 = 10.;
-                    let t = now.duration_since(epoch).as_secs_f32() % DURATION / DURATION;
-                    gfx.draw(&mut gl, t);
+                gfx.set_palette(state.program().theme().scene());
+                gfx.set_animated(state.program().config().animation);
+                gfx.clear(&mut gl);
+
+                clock.tick(epoch.elapsed().as_secs_f64());
+                gfx.set_constellation(&state.program().constellation());
+                for _ in 0..clock.fixed_steps() {
+                    gfx.update(clock.fixed_step());
                 }
+                gfx.draw(&mut gl);
 
                 renderer.with_primitives(|backend, primitives| {
                     backend.present(&mut gl, primitives, &viewport, &debug.overlay());
@@ -0,0 +1,37 @@
+//! Hosts a shared table that the native and web apps can join from the Game screen.
+
+use clap::Parser;
+use spirits_within_app::{protocol, server::Server};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "spirits-server",
+    about = "Host a Spirits Within table on the local network"
+)]
+struct Args {
+    /// Address to listen on; 127.0.0.1 keeps the table to this machine.
+    #[arg(long, default_value_t = format!("0.0.0.0:{}", protocol::DEFAULT_PORT))]
+    bind: String,
+    /// Seed for the dice; random if not given.
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+fn main() -> eyre::Result<()> {
+    env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Info)
+        .parse_default_env()
+        .init();
+    let args = Args::parse();
+
+    let mut server = Server::bind(&args.bind)?;
+    if let Some(seed) = args.seed {
+        server = server.with_seed(seed);
+    }
+    println!(
+        "listening on ws://{} (protocol version {})",
+        server.local_addr()?,
+        protocol::VERSION
+    );
+    server.run()
+}
@@ -0,0 +1,216 @@
+//! Generates, validates and summarizes characters without opening a window.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use eyre::WrapErr;
+use spirits_within_app::{
+    character::Character,
+    content::{self, Content, ContentPack},
+    rules,
+};
+use std::{fmt::Write, path::PathBuf};
+
+/// How many random builds to try for each generated character before giving up.
+const MAX_ATTEMPTS: usize = 1000;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "spirits",
+    about = "Generate and check Spirits Within characters"
+)]
+struct Args {
+    /// A content pack file to load on top of the core rules, for homebrew prerogatives and
+    /// burdens. Can be given more than once; later packs win.
+    #[arg(long = "pack", global = true)]
+    packs: Vec<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print random legal characters.
+    Generate {
+        /// Seed for the random number generator; random if not given.
+        #[arg(long)]
+        seed: Option<u64>,
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+    },
+    /// Check that every character in a file is complete and legal.
+    Validate { file: PathBuf },
+    /// Print the base and final stats of every character in a file.
+    Stats { file: PathBuf },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Json,
+    Md,
+}
+
+fn main() -> eyre::Result<()> {
+    env_logger::init();
+    let args = Args::parse();
+    if !args.packs.is_empty() {
+        let packs = args
+            .packs
+            .iter()
+            .map(|path| read_pack(path))
+            .collect::<eyre::Result<Vec<_>>>()?;
+        content::set_active(Content::from_packs(packs));
+    }
+    match args.command {
+        Command::Generate {
+            seed,
+            count,
+            format,
+        } => {
+            let seed = seed.unwrap_or_else(|| rand::RngCore::next_u64(&mut rand::thread_rng()));
+            let mut rng: rand::rngs::SmallRng = rand::SeedableRng::seed_from_u64(seed);
+            let characters = (0..count)
+                .map(|_| generate(&mut rng))
+                .collect::<eyre::Result<Vec<_>>>()?;
+            match format {
+                Format::Json => println!("{}", Character::list_to_json(&characters)?),
+                Format::Md => {
+                    println!("<!-- seed {} -->", seed);
+                    for (index, character) in characters.iter().enumerate() {
+                        println!("{}", markdown(index, character));
+                    }
+                }
+            }
+        }
+        Command::Validate { file } => {
+            let mut invalid = 0;
+            for (index, character) in read(&file)?.iter().enumerate() {
+                match rules::stats(character) {
+                    Ok(_) => println!("character {}: ok", index + 1),
+                    Err(err) => {
+                        invalid += 1;
+                        println!("character {}: {:#}", index + 1, err);
+                    }
+                }
+            }
+            if invalid > 0 {
+                eyre::bail!("{} invalid character(s) in {}", invalid, file.display());
+            }
+        }
+        Command::Stats { file } => {
+            for (index, character) in read(&file)?.iter().enumerate() {
+                println!("character {}", index + 1);
+                match rules::selection(character) {
+                    Ok(selection) => {
+                        let base = spirits_within::BaseStats::new(&selection);
+                        println!(
+                            "  base:  {}",
+                            stat_line(base.proficiency, base.knowledge, base.discipline)
+                        );
+                    }
+                    Err(err) => println!("  base:  {:#}", err),
+                }
+                match rules::stats(character) {
+                    Ok(stats) => println!(
+                        "  final: {}",
+                        stat_line(stats.proficiency, stats.knowledge, stats.discipline)
+                    ),
+                    Err(err) => println!("  final: {:#}", err),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A random character that passes the rules.
+fn generate<R: rand::Rng>(rng: &mut R) -> eyre::Result<Character> {
+    (0..MAX_ATTEMPTS)
+        .map(|_| rules::random(rng))
+        .find(|character| rules::stats(character).is_ok())
+        .ok_or_else(|| eyre::eyre!("no legal character in {} attempts", MAX_ATTEMPTS))
+}
+
+fn read(path: &std::path::Path) -> eyre::Result<Vec<Character>> {
+    let contents =
+        std::fs::read_to_string(path).wrap_err_with(|| format!("reading {}", path.display()))?;
+    Character::list_from_json(&contents).wrap_err_with(|| format!("loading {}", path.display()))
+}
+
+fn read_pack(path: &std::path::Path) -> eyre::Result<ContentPack> {
+    let contents =
+        std::fs::read_to_string(path).wrap_err_with(|| format!("reading {}", path.display()))?;
+    ContentPack::parse(&path.to_string_lossy(), &contents)
+        .wrap_err_with(|| format!("loading {}", path.display()))
+}
+
+fn stat_line(
+    proficiency: impl std::fmt::Display,
+    knowledge: impl std::fmt::Display,
+    discipline: impl std::fmt::Display,
+) -> String {
+    use spirits_within::Stats;
+    format!(
+        "Proficiency {} / {}, Knowledge {} / {}, Discipline {} / {}",
+        proficiency,
+        Stats::MAX_PROFICIENCY,
+        knowledge,
+        Stats::MAX_KNOWLEDGE,
+        discipline,
+        Stats::MAX_DISCIPLINE
+    )
+}
+
+fn markdown(index: usize, character: &Character) -> String {
+    let mut out = String::new();
+    writeln!(out, "## Character {}\n", index + 1).unwrap();
+    writeln!(out, "| Stat | Spirit | Connection |").unwrap();
+    writeln!(out, "| --- | --- | --- |").unwrap();
+    let mut spirits = character.spirits.clone();
+    // grouped by stat in the same order as the character creator
+    spirits.sort_by_key(|(spirit, _)| {
+        use spirits_within::Stat;
+        match spirit.stat() {
+            Stat::Proficiency => 0,
+            Stat::Knowledge => 1,
+            Stat::Discipline => 2,
+        }
+    });
+    for (spirit, connection) in spirits {
+        writeln!(
+            out,
+            "| {:?} | {:?} | {} |",
+            spirit.stat(),
+            spirit,
+            connection
+        )
+        .unwrap();
+    }
+
+    let prerogatives: Vec<String> = character
+        .prerogatives
+        .iter()
+        .flatten()
+        .map(ToString::to_string)
+        .collect();
+    writeln!(out, "\n**Prerogatives:** {}", prerogatives.join(", ")).unwrap();
+    let burdens: Vec<String> = character
+        .burdens
+        .iter()
+        .map(|(burden, prerogative)| format!("{} for {}", burden, prerogative))
+        .collect();
+    if burdens.is_empty() {
+        writeln!(out, "\n**Burdens:** none").unwrap();
+    } else {
+        writeln!(out, "\n**Burdens:** {}", burdens.join(", ")).unwrap();
+    }
+    if let Ok(stats) = rules::stats(character) {
+        writeln!(
+            out,
+            "\n**Stats:** {}",
+            stat_line(stats.proficiency, stats.knowledge, stats.discipline)
+        )
+        .unwrap();
+    }
+    out
+}
@@ -0,0 +1,381 @@
+//! The character creator in a terminal, for players without a graphics stack.
+//!
+//! Every choice is a slot: up and down move between slots, left and right cycle through the
+//! choices still open for the focused slot.
+
+use clap::Parser;
+use eyre::WrapErr;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Gauge, Paragraph},
+    Frame,
+};
+use spirits_within::{Spirit, Stat, Stats};
+use spirits_within_app::{
+    build::CharacterBuild,
+    character::Character,
+    content::PrerogativeId,
+    rules::{BASE_PREROGATIVE_COUNT, MAX_OPTIONAL_BURDENS},
+};
+use std::path::PathBuf;
+
+const STATS: [Stat; 3] = [Stat::Proficiency, Stat::Knowledge, Stat::Discipline];
+
+const HELP: &str = "↑↓ move  ←→ change  del clear  a/d add/remove burden  \
+                    r randomize  x reset  s save  q quit";
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "spirits-tui",
+    about = "Build a Spirits Within character in the terminal"
+)]
+struct Args {
+    /// Start from this character file.
+    #[arg(long, value_name = "CHARACTER FILE")]
+    open: Option<PathBuf>,
+    /// Where `s` saves to; defaults to the opened file, or character.json.
+    #[arg(long, value_name = "FILE")]
+    out: Option<PathBuf>,
+    /// Seed for randomizing; random if not given.
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+/// Something that can be chosen, in the order the cursor visits them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Slot {
+    Spirit(Spirit),
+    Prerogative(usize),
+    Burden(usize),
+    BurdenPrerogative(usize),
+}
+
+struct App {
+    build: CharacterBuild,
+    rng: rand::rngs::SmallRng,
+    cursor: usize,
+    out: PathBuf,
+    status: String,
+}
+
+impl App {
+    /// Every slot, spirits grouped by stat as in the character creator.
+    fn slots(&self) -> Vec<Slot> {
+        let spirits = STATS.into_iter().flat_map(|stat| {
+            Spirit::LIST
+                .into_iter()
+                .filter(move |spirit| spirit.stat() == stat)
+                .map(Slot::Spirit)
+        });
+        let prerogatives = (0..BASE_PREROGATIVE_COUNT).map(Slot::Prerogative);
+        let burdens = (0..self.build.burdens().len())
+            .flat_map(|index| [Slot::Burden(index), Slot::BurdenPrerogative(index)]);
+        spirits.chain(prerogatives).chain(burdens).collect()
+    }
+
+    fn focused(&self) -> Slot {
+        let slots = self.slots();
+        slots[self.cursor.min(slots.len() - 1)]
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let len = self.slots().len() as isize;
+        self.cursor = (self.cursor as isize + delta).rem_euclid(len) as usize;
+    }
+
+    /// Moves the focused slot's choice `delta` steps through the choices open to it, where the
+    /// first choice is always "none".
+    fn cycle(&mut self, delta: isize) {
+        fn step<T: PartialEq + Clone>(
+            options: Vec<T>,
+            current: Option<T>,
+            delta: isize,
+        ) -> Option<T> {
+            let options: Vec<Option<T>> = std::iter::once(None)
+                .chain(options.into_iter().map(Some))
+                .collect();
+            let index = options.iter().position(|o| *o == current).unwrap_or(0) as isize;
+            options[(index + delta).rem_euclid(options.len() as isize) as usize].clone()
+        }
+
+        let focused = self.focused();
+        let build = &mut self.build;
+        match focused {
+            Slot::Spirit(spirit) => {
+                let next = step(
+                    build.connection_options(spirit),
+                    build.connection(spirit),
+                    delta,
+                );
+                build.set_connection(spirit, next);
+            }
+            Slot::Prerogative(slot) => {
+                let current = build.prerogatives()[slot].clone();
+                let options = with_current(build.prerogative_options(slot), current.clone());
+                let next = step(options, current, delta);
+                build.set_prerogative(slot, next);
+            }
+            Slot::Burden(index) => {
+                let current = build.burdens()[index].burden.clone();
+                let next = step(build.burden_options(), current, delta);
+                build.set_burden(index, next);
+            }
+            Slot::BurdenPrerogative(index) => {
+                let current = build.burdens()[index].prerogative.clone();
+                let options =
+                    with_current(build.burden_prerogative_options(index), current.clone());
+                let next = step(options, current, delta);
+                build.set_burden_prerogative(index, next);
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        let focused = self.focused();
+        let build = &mut self.build;
+        match focused {
+            Slot::Spirit(spirit) => build.set_connection(spirit, None),
+            Slot::Prerogative(slot) => build.set_prerogative(slot, None),
+            Slot::Burden(index) => build.set_burden(index, None),
+            Slot::BurdenPrerogative(index) => build.set_burden_prerogative(index, None),
+        }
+    }
+
+    fn save(&mut self) {
+        self.status = match self.build.character().save(&self.out) {
+            Ok(()) => format!("saved to {}", self.out.display()),
+            Err(err) => format!("{:#}", err),
+        };
+    }
+
+    /// Handles a key press, returning false to quit.
+    fn key(&mut self, key: KeyCode) -> bool {
+        self.status.clear();
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => self.move_cursor(1),
+            KeyCode::Left | KeyCode::Char('h') => self.cycle(-1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => self.cycle(1),
+            KeyCode::Delete | KeyCode::Backspace => self.clear(),
+            KeyCode::Char('a') => self.build.add_burden(),
+            KeyCode::Char('d') => {
+                self.build.remove_burden();
+                self.cursor = self.cursor.min(self.slots().len() - 1);
+            }
+            KeyCode::Char('r') => self.build = CharacterBuild::random(&mut self.rng),
+            KeyCode::Char('x') => {
+                self.build = CharacterBuild::new();
+                self.cursor = self.cursor.min(self.slots().len() - 1);
+            }
+            KeyCode::Char('s') => self.save(),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(frame.area());
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(main);
+
+        let focused = self.focused();
+        let row = |slot: Slot, label: String, value: String| {
+            let style = if slot == focused {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::raw(format!("{:<16}", label)),
+                Span::styled(format!("{:<14}", value), style),
+            ])
+        };
+
+        let spirit_areas = Layout::vertical([Constraint::Length(7); 3]).split(left);
+        for (stat, area) in STATS.into_iter().zip(spirit_areas.iter()) {
+            let lines: Vec<Line> = Spirit::LIST
+                .into_iter()
+                .filter(|spirit| spirit.stat() == stat)
+                .map(|spirit| {
+                    let value = self
+                        .build
+                        .connection(spirit)
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| "-".into());
+                    row(Slot::Spirit(spirit), format!("{:?}", spirit), value)
+                })
+                .collect();
+            frame.render_widget(
+                Paragraph::new(lines).block(Block::bordered().title(format!("{:?}", stat))),
+                *area,
+            );
+        }
+
+        let [prerogatives_area, burdens_area, stats_area] = Layout::vertical([
+            Constraint::Length(BASE_PREROGATIVE_COUNT as u16 + 2),
+            Constraint::Length(MAX_OPTIONAL_BURDENS as u16 * 2 + 2),
+            Constraint::Min(0),
+        ])
+        .areas(right);
+
+        let prerogatives: Vec<Line> = self
+            .build
+            .prerogatives()
+            .iter()
+            .enumerate()
+            .map(|(slot, prerogative)| {
+                row(
+                    Slot::Prerogative(slot),
+                    format!("Prerogative {}", slot + 1),
+                    name(prerogative.as_ref()),
+                )
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(prerogatives).block(Block::bordered().title("Prerogatives")),
+            prerogatives_area,
+        );
+
+        let burdens: Vec<Line> = self
+            .build
+            .burdens()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, row_value)| {
+                [
+                    row(
+                        Slot::Burden(index),
+                        format!("Burden {}", index + 1),
+                        name(row_value.burden.as_ref()),
+                    ),
+                    row(
+                        Slot::BurdenPrerogative(index),
+                        "  for".into(),
+                        name(row_value.prerogative.as_ref()),
+                    ),
+                ]
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(burdens).block(Block::bordered().title(format!(
+                "Optional burdens ({}/{})",
+                self.build.burdens().len(),
+                MAX_OPTIONAL_BURDENS
+            ))),
+            burdens_area,
+        );
+
+        self.draw_stats(frame, stats_area);
+
+        let status = match self.build.stats() {
+            Ok(_) if self.status.is_empty() => "complete".to_owned(),
+            Err(err) if self.status.is_empty() => format!("{:#}", err),
+            _ => self.status.clone(),
+        };
+        frame.render_widget(
+            Paragraph::new(vec![
+                Line::from(status),
+                Line::styled(HELP, Style::default().fg(Color::DarkGray)),
+            ]),
+            footer,
+        );
+    }
+
+    fn draw_stats(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered().title("Stats");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let values = match (self.build.stats(), self.build.preview_stats()) {
+            (Ok(stats), _) => [stats.proficiency, stats.knowledge, stats.discipline],
+            (Err(_), Some(preview)) => [preview.proficiency, preview.knowledge, preview.discipline],
+            (Err(_), None) => {
+                frame.render_widget(Paragraph::new("choose every spirit's connection"), inner);
+                return;
+            }
+        };
+        let maxima = [
+            Stats::MAX_PROFICIENCY,
+            Stats::MAX_KNOWLEDGE,
+            Stats::MAX_DISCIPLINE,
+        ];
+        let rows = Layout::vertical([Constraint::Length(1); 3]).split(inner);
+        for ((stat, (value, max)), area) in STATS
+            .into_iter()
+            .zip(values.into_iter().zip(maxima))
+            .zip(rows.iter())
+        {
+            let ratio = (f64::from(value) / f64::from(max)).min(1.);
+            let color = if value >= max {
+                Color::Red
+            } else {
+                Color::Blue
+            };
+            frame.render_widget(
+                Gauge::default()
+                    .ratio(ratio)
+                    .label(format!("{:?} {} / {}", stat, value, max))
+                    .gauge_style(Style::default().fg(color)),
+                *area,
+            );
+        }
+    }
+}
+
+/// The prerogatives open to a slot currently holding `current`, which stays choosable.
+fn with_current(
+    mut options: Vec<PrerogativeId>,
+    current: Option<PrerogativeId>,
+) -> Vec<PrerogativeId> {
+    if let Some(current) = current {
+        if !options.contains(&current) {
+            options.push(current);
+        }
+    }
+    options
+}
+
+fn name(value: Option<impl std::fmt::Display>) -> String {
+    value.map_or_else(|| "-".into(), |value| value.to_string())
+}
+
+fn main() -> eyre::Result<()> {
+    let args = Args::parse();
+    let build = match &args.open {
+        Some(path) => CharacterBuild::from(&Character::load(path)?),
+        None => CharacterBuild::new(),
+    };
+    let seed = args
+        .seed
+        .unwrap_or_else(|| rand::RngCore::next_u64(&mut rand::thread_rng()));
+    let mut app = App {
+        build,
+        rng: rand::SeedableRng::seed_from_u64(seed),
+        cursor: 0,
+        out: args
+            .out
+            .or(args.open)
+            .unwrap_or_else(|| "character.json".into()),
+        status: String::new(),
+    };
+
+    let mut terminal = ratatui::init();
+    let result = (|| -> eyre::Result<()> {
+        loop {
+            terminal.draw(|frame| app.draw(frame)).wrap_err("drawing")?;
+            if let Event::Key(key) = event::read().wrap_err("reading input")? {
+                if key.kind == KeyEventKind::Press && !app.key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+    ratatui::restore();
+    result
+}
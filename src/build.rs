@@ -0,0 +1,451 @@
+//! A character under construction, with the legality queries a front end needs to offer only
+//! the choices that are still open.
+
+use crate::{
+    character::Character,
+    content::{self, BurdenId, PrerogativeId},
+    rules::{self, BASE_PREROGATIVE_COUNT, CONNECTION_COUNTS, MAX_OPTIONAL_BURDENS},
+};
+use spirits_within::{BaseStats, Connection, Spirit, SpiritSelection, Stats};
+
+const SPIRIT_COUNT: usize = Spirit::LIST.len();
+
+/// An optional burden row, either half of which may still be unchosen.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OptionalBurden {
+    pub burden: Option<BurdenId>,
+    /// The prerogative taken in exchange for the burden.
+    pub prerogative: Option<PrerogativeId>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharacterBuild {
+    /// The connection chosen for each spirit, in `Spirit::LIST` order.
+    connections: [Option<Connection>; SPIRIT_COUNT],
+    prerogatives: [Option<PrerogativeId>; BASE_PREROGATIVE_COUNT],
+    burdens: Vec<OptionalBurden>,
+    /// Set by `finalize` and cleared by any change.
+    finalized: bool,
+}
+
+impl Default for CharacterBuild {
+    fn default() -> Self {
+        Self {
+            connections: [None; SPIRIT_COUNT],
+            prerogatives: Default::default(),
+            burdens: vec![],
+            finalized: false,
+        }
+    }
+}
+
+impl CharacterBuild {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A random build, as `rules::random` makes them.
+    pub fn random<R: rand::Rng>(rng: &mut R) -> Self {
+        Self::from(&rules::random(rng))
+    }
+
+    pub fn connection(&self, spirit: Spirit) -> Option<Connection> {
+        self.connections[index(spirit)]
+    }
+
+    pub fn set_connection(&mut self, spirit: Spirit, connection: Option<Connection>) {
+        self.finalized = false;
+        self.connections[index(spirit)] = connection;
+    }
+
+    /// How many more spirits can be given `connection`.
+    pub fn remaining(&self, connection: Connection) -> usize {
+        let used = self
+            .connections
+            .iter()
+            .filter(|chosen| **chosen == Some(connection))
+            .count();
+        CONNECTION_COUNTS
+            .into_iter()
+            .find(|(c, _)| *c == connection)
+            .map_or(0, |(_, count)| count.saturating_sub(used))
+    }
+
+    /// The connections `spirit` could be given: those with some left, plus its current one.
+    pub fn connection_options(&self, spirit: Spirit) -> Vec<Connection> {
+        let current = self.connection(spirit);
+        CONNECTION_COUNTS
+            .into_iter()
+            .map(|(connection, _)| connection)
+            .filter(|&connection| self.remaining(connection) > 0 || current == Some(connection))
+            .collect()
+    }
+
+    /// The spirit selection, once every spirit has a legal connection.
+    pub fn selection(&self) -> Option<SpiritSelection> {
+        rules::selection(&self.character()).ok()
+    }
+
+    pub fn base_stats(&self) -> Option<BaseStats> {
+        self.selection().map(|selection| BaseStats::new(&selection))
+    }
+
+    pub fn prerogatives(&self) -> &[Option<PrerogativeId>; BASE_PREROGATIVE_COUNT] {
+        &self.prerogatives
+    }
+
+    pub fn set_prerogative(&mut self, slot: usize, prerogative: Option<PrerogativeId>) {
+        if let Some(chosen) = self.prerogatives.get_mut(slot) {
+            self.finalized = false;
+            *chosen = prerogative;
+        }
+    }
+
+    pub fn burdens(&self) -> &[OptionalBurden] {
+        &self.burdens
+    }
+
+    pub fn can_add_burden(&self) -> bool {
+        self.burdens.len() < MAX_OPTIONAL_BURDENS
+    }
+
+    /// Adds an empty optional burden row if there's room for one.
+    pub fn add_burden(&mut self) {
+        if self.can_add_burden() {
+            self.finalized = false;
+            self.burdens.push(Default::default());
+        }
+    }
+
+    /// Removes the last optional burden row.
+    pub fn remove_burden(&mut self) {
+        if self.burdens.pop().is_some() {
+            self.finalized = false;
+        }
+    }
+
+    pub fn set_burden(&mut self, index: usize, burden: Option<BurdenId>) {
+        if let Some(row) = self.burdens.get_mut(index) {
+            self.finalized = false;
+            row.burden = burden;
+        }
+    }
+
+    pub fn set_burden_prerogative(&mut self, index: usize, prerogative: Option<PrerogativeId>) {
+        if let Some(row) = self.burdens.get_mut(index) {
+            self.finalized = false;
+            row.prerogative = prerogative;
+        }
+    }
+
+    /// Every prerogative chosen so far, base and from burdens.
+    pub fn chosen_prerogatives(&self) -> impl Iterator<Item = &PrerogativeId> {
+        self.prerogatives
+            .iter()
+            .chain(self.burdens.iter().map(|row| &row.prerogative))
+            .flatten()
+    }
+
+    /// The base stats with the bonuses of the prerogatives chosen so far, which may be past the
+    /// stats' maximums.
+    pub fn preview_stats(&self) -> Option<BaseStats> {
+        self.base_stats().map(|stats| {
+            rules::with_prerogative_bonuses(stats, self.chosen_prerogatives().cloned())
+        })
+    }
+
+    /// The prerogatives base prerogative `slot` could be given without pushing a stat past its
+    /// maximum or breaking a restriction, core and from the enabled content packs. Whatever the
+    /// slot holds now doesn't count against them. The stats are only checked once the spirits are
+    /// complete.
+    pub fn prerogative_options(&self, slot: usize) -> Vec<PrerogativeId> {
+        self.options(Slot::Base(slot))
+    }
+
+    /// As `prerogative_options`, for the prerogative taken in exchange for optional burden row
+    /// `index`.
+    pub fn burden_prerogative_options(&self, index: usize) -> Vec<PrerogativeId> {
+        self.options(Slot::Burden(index))
+    }
+
+    /// The burdens that can be taken on, core and from the enabled content packs.
+    pub fn burden_options(&self) -> Vec<BurdenId> {
+        content::active().burden_ids()
+    }
+
+    fn options(&self, slot: Slot) -> Vec<PrerogativeId> {
+        let base = self.base_stats();
+        let preview = base.map(|stats| {
+            rules::with_prerogative_bonuses(stats, self.chosen_prerogatives_but(slot).cloned())
+        });
+        content::active()
+            .prerogative_ids()
+            .into_iter()
+            .filter(|prerogative| {
+                let taken = self
+                    .chosen_prerogatives_but(slot)
+                    .filter(|&chosen| chosen == prerogative)
+                    .count();
+                let within_maximums = match preview {
+                    Some(stats) => rules::prerogative_allowed(stats, prerogative),
+                    None => true,
+                };
+                within_maximums
+                    && rules::restrictions_allow(
+                        prerogative,
+                        base,
+                        taken,
+                        matches!(slot, Slot::Burden(_)),
+                    )
+                    .is_ok()
+            })
+            .collect()
+    }
+
+    /// As `chosen_prerogatives`, leaving out whatever `slot` holds.
+    fn chosen_prerogatives_but(&self, slot: Slot) -> impl Iterator<Item = &PrerogativeId> {
+        let base = self
+            .prerogatives
+            .iter()
+            .enumerate()
+            .filter(move |&(index, _)| slot != Slot::Base(index));
+        let burdens = self
+            .burdens
+            .iter()
+            .map(|row| &row.prerogative)
+            .enumerate()
+            .filter(move |&(index, _)| slot != Slot::Burden(index));
+        base.chain(burdens)
+            .filter_map(|(_, chosen)| chosen.as_ref())
+    }
+
+    /// The final stats, or why the build isn't complete and legal yet. Burden rows with an
+    /// unchosen half are ignored.
+    pub fn stats(&self) -> eyre::Result<Stats> {
+        rules::stats(&self.character())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.stats().is_ok()
+    }
+
+    /// Locks in a complete and legal build, or says why it can't be.
+    pub fn finalize(&mut self) -> eyre::Result<()> {
+        self.stats()?;
+        self.finalized = true;
+        Ok(())
+    }
+
+    /// Unlocks the build for editing.
+    pub fn unfinalize(&mut self) {
+        self.finalized = false;
+    }
+
+    pub fn is_finalized(&self) -> bool {
+        self.finalized
+    }
+
+    /// The build as a character, leaving out anything not chosen yet.
+    pub fn character(&self) -> Character {
+        Character {
+            spirits: Spirit::LIST
+                .into_iter()
+                .zip(self.connections)
+                .filter_map(|(spirit, connection)| Some((spirit, connection?)))
+                .collect(),
+            prerogatives: self.prerogatives.clone(),
+            burdens: self
+                .burdens
+                .iter()
+                .filter_map(|row| row.burden.clone().zip(row.prerogative.clone()))
+                .collect(),
+            finalized: self.finalized,
+        }
+    }
+}
+
+impl From<&Character> for CharacterBuild {
+    fn from(character: &Character) -> Self {
+        let mut build = Self::new();
+        for &(spirit, connection) in &character.spirits {
+            build.set_connection(spirit, Some(connection));
+        }
+        build.prerogatives = character.prerogatives.clone();
+        build.burdens = character
+            .burdens
+            .iter()
+            .take(MAX_OPTIONAL_BURDENS)
+            .map(|(burden, prerogative)| OptionalBurden {
+                burden: Some(burden.clone()),
+                prerogative: Some(prerogative.clone()),
+            })
+            .collect();
+        build.finalized = character.finalized;
+        build
+    }
+}
+
+/// A place a prerogative can be chosen.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Slot {
+    /// A base prerogative, by position.
+    Base(usize),
+    /// The prerogative for an optional burden row, by position.
+    Burden(usize),
+}
+
+fn index(spirit: Spirit) -> usize {
+    Spirit::LIST
+        .iter()
+        .position(|s| *s == spirit)
+        .expect("every spirit is in the list")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use spirits_within::{Burden, Prerogative};
+
+    /// A build with every spirit given a legal connection, strongest first in list order.
+    fn complete_spirits() -> CharacterBuild {
+        let mut build = CharacterBuild::new();
+        let mut spirits = Spirit::LIST.into_iter();
+        for (connection, count) in CONNECTION_COUNTS {
+            for spirit in spirits.by_ref().take(count) {
+                build.set_connection(spirit, Some(connection));
+            }
+        }
+        build
+    }
+
+    #[test]
+    fn new_build_offers_everything() {
+        let build = CharacterBuild::new();
+        for (connection, count) in CONNECTION_COUNTS {
+            assert_eq!(build.remaining(connection), count);
+        }
+        assert_eq!(build.connection_options(Spirit::LIST[0]).len(), 4);
+        assert_eq!(build.prerogative_options(0).len(), Prerogative::LIST.len());
+        assert_eq!(build.burden_options().len(), Burden::LIST.len());
+        assert!(build.selection().is_none());
+        assert!(build.stats().is_err());
+    }
+
+    #[test]
+    fn used_up_connections_are_no_longer_offered() {
+        let mut build = CharacterBuild::new();
+        let (mastery, count) = CONNECTION_COUNTS[0];
+        for spirit in Spirit::LIST.into_iter().take(count) {
+            build.set_connection(spirit, Some(mastery));
+        }
+        assert_eq!(build.remaining(mastery), 0);
+        assert!(!build
+            .connection_options(Spirit::LIST[count])
+            .contains(&mastery));
+        // a spirit that already has it can keep it
+        assert!(build.connection_options(Spirit::LIST[0]).contains(&mastery));
+    }
+
+    #[test]
+    fn complete_spirits_give_base_stats() {
+        let build = complete_spirits();
+        assert!(build.selection().is_some());
+        assert!(build.base_stats().is_some());
+        // prerogatives are still missing
+        assert!(build.stats().is_err());
+    }
+
+    #[test]
+    fn prerogatives_raise_the_preview() {
+        let mut build = complete_spirits();
+        let base = build.base_stats().unwrap();
+        build.set_prerogative(0, Some(Prerogative::Education.into()));
+        let preview = build.preview_stats().unwrap();
+        assert_eq!(preview.knowledge, base.knowledge + 3);
+        assert_eq!(preview.proficiency, base.proficiency);
+    }
+
+    #[test]
+    fn a_slot_being_changed_does_not_count_against_itself() {
+        let mut build = complete_spirits();
+        let education = PrerogativeId::from(Prerogative::Education);
+        let mut filled = 0;
+        while filled < BASE_PREROGATIVE_COUNT
+            && build.prerogative_options(filled).contains(&education)
+        {
+            build.set_prerogative(filled, Some(education.clone()));
+            filled += 1;
+        }
+        // Knowledge is full, yet every slot with Education could still keep it
+        assert!(filled < BASE_PREROGATIVE_COUNT);
+        for slot in 0..filled {
+            assert!(build.prerogative_options(slot).contains(&education));
+        }
+        build.add_burden();
+        assert!(!build.burden_prerogative_options(0).contains(&education));
+    }
+
+    #[test]
+    fn burdens_are_limited() {
+        let mut build = CharacterBuild::new();
+        for _ in 0..MAX_OPTIONAL_BURDENS + 2 {
+            build.add_burden();
+        }
+        assert_eq!(build.burdens().len(), MAX_OPTIONAL_BURDENS);
+        assert!(!build.can_add_burden());
+        build.remove_burden();
+        assert!(build.can_add_burden());
+    }
+
+    #[test]
+    fn incomplete_burden_rows_are_left_out_of_the_character() {
+        let mut build = CharacterBuild::new();
+        build.add_burden();
+        build.set_burden(0, Some(Burden::LIST[0].into()));
+        assert!(build.character().burdens.is_empty());
+        build.set_burden_prerogative(0, Some(Prerogative::Vocation.into()));
+        assert_eq!(
+            build.character().burdens,
+            vec![(Burden::LIST[0].into(), Prerogative::Vocation.into())]
+        );
+    }
+
+    #[test]
+    fn only_legal_builds_are_finalized_until_changed() {
+        let mut build = complete_spirits();
+        assert!(build.finalize().is_err());
+        let neutral = Prerogative::LIST
+            .into_iter()
+            .map(PrerogativeId::from)
+            .find(|p| rules::prerogative_stat(p).is_none())
+            .unwrap();
+        for slot in 0..BASE_PREROGATIVE_COUNT {
+            build.set_prerogative(slot, Some(neutral.clone()));
+        }
+        build.finalize().unwrap();
+
+        let json = build.character().to_json().unwrap();
+        let character = Character::from_json(&json).unwrap();
+        assert!(character.finalized);
+        assert!(CharacterBuild::from(&character).is_finalized());
+
+        build.set_connection(Spirit::LIST[0], None);
+        assert!(!build.is_finalized());
+    }
+
+    #[test]
+    fn round_trips_through_character() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(7);
+        let build = CharacterBuild::random(&mut rng);
+        assert_eq!(CharacterBuild::from(&build.character()), build);
+    }
+
+    #[test]
+    fn random_spirits_are_always_legal() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(1);
+        for _ in 0..100 {
+            assert!(CharacterBuild::random(&mut rng).selection().is_some());
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use iced_winit::{event, keyboard, mouse, Event, Point};
+
+/// Turns input that the UI doesn't use into camera movement.
+///
+/// Dragging with the left button orbits, the wheel zooms and the middle button or `Home` resets.
+/// Drags and zooms only start while the cursor is over empty space, which is whenever the UI
+/// reports an idle mouse interaction. A press and release that barely moves is a click instead.
+/// `Home` is left alone while a text input has focus.
+#[derive(Debug, Clone, Default)]
+pub struct CameraController {
+    cursor: Option<Point>,
+    pressed_at: Option<Point>,
+    dragging: bool,
+    clicked: bool,
+}
+
+impl CameraController {
+    /// Radians of orbit per logical pixel dragged.
+    const ORBIT_SPEED: f32 = 0.01;
+    /// Zoom factor applied per line scrolled.
+    const ZOOM_STEP: f32 = 0.9;
+    /// How many pixels of scrolling count as one line.
+    const PIXELS_PER_LINE: f32 = 50.;
+    /// How far, in logical pixels, the cursor can move between press and release of a click.
+    const CLICK_DISTANCE: f32 = 4.;
+
+    /// Whether the scene was clicked since the last call.
+    pub fn take_click(&mut self) -> bool {
+        std::mem::take(&mut self.clicked)
+    }
+
+    /// Applies `event` to `camera`.
+    ///
+    /// Returns `Captured` when the event belongs to the camera alone and shouldn't be passed on
+    /// to the UI. Cursor movement and left button presses and releases are never captured so
+    /// that the UI's view of the mouse stays consistent and a click on the scene still takes
+    /// focus away from a text input.
+    pub fn handle_event(
+        &mut self,
+        event: &Event,
+        interaction: mouse::Interaction,
+        editing_text: bool,
+        camera: &mut renderer::Camera,
+    ) -> event::Status {
+        let idle = interaction == mouse::Interaction::Idle;
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                if let (true, Some(last)) = (self.dragging, self.cursor) {
+                    camera.orbit(
+                        (position.x - last.x) * Self::ORBIT_SPEED,
+                        (position.y - last.y) * Self::ORBIT_SPEED,
+                    );
+                }
+                self.cursor = Some(*position);
+                event::Status::Ignored
+            }
+            Event::Mouse(mouse::Event::CursorLeft) => {
+                self.cursor = None;
+                self.dragging = false;
+                event::Status::Ignored
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) if idle => {
+                self.dragging = true;
+                self.pressed_at = self.cursor;
+                event::Status::Ignored
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                if let (true, Some(pressed), Some(released)) =
+                    (self.dragging, self.pressed_at.take(), self.cursor)
+                {
+                    self.clicked = pressed.distance(released) <= Self::CLICK_DISTANCE;
+                }
+                self.dragging = false;
+                event::Status::Ignored
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Middle)) if idle => {
+                camera.reset();
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) if idle => {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => *y,
+                    mouse::ScrollDelta::Pixels { y, .. } => *y / Self::PIXELS_PER_LINE,
+                };
+                camera.zoom(Self::ZOOM_STEP.powf(lines));
+                event::Status::Captured
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: keyboard::KeyCode::Home,
+                ..
+            }) if !editing_text => {
+                camera.reset();
+                event::Status::Captured
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+}
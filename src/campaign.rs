@@ -0,0 +1,225 @@
+//! Campaigns: a party, the characters the Game Master plays, the session log and the Game
+//! Master's notes, kept together in one JSON file.
+//!
+//! Campaigns are saved by name with the other user data, or to the file they were opened from.
+
+use crate::{character::Character, progression::Progression, session_log::SessionLog, storage};
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+
+/// The campaign everything goes into until another is opened.
+pub const DEFAULT_NAME: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Campaign {
+    pub name: String,
+    pub party: Vec<Member>,
+    pub npcs: Vec<Member>,
+    pub log: SessionLog,
+    /// The Game Master's notes, oldest first.
+    pub notes: Vec<String>,
+    /// The names of the saved content packs enabled in this campaign, applied in this order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub packs: Vec<String>,
+    /// The file this was loaded from with `load_from`, which `save` writes back to.
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+/// A named character in a campaign, as created and advanced since.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    pub name: String,
+    #[serde(flatten)]
+    pub progression: Progression,
+}
+
+/// Which list of a campaign a member is in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Role {
+    Party,
+    Npc,
+}
+
+/// A member of a campaign, as picked from a list of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberChoice {
+    pub role: Role,
+    pub name: String,
+}
+
+impl std::fmt::Display for MemberChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.role {
+            Role::Party => write!(f, "{}", self.name),
+            Role::Npc => write!(f, "{} (NPC)", self.name),
+        }
+    }
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Self::new(DEFAULT_NAME)
+    }
+}
+
+impl Campaign {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            party: vec![],
+            npcs: vec![],
+            log: Default::default(),
+            notes: vec![],
+            packs: vec![],
+            path: None,
+        }
+    }
+
+    /// The campaign saved as `name`, or a new one if there is none.
+    pub fn load(name: &str) -> eyre::Result<Self> {
+        match storage::load(&key(name)) {
+            Some(contents) => {
+                Self::from_json(&contents).wrap_err_with(|| format!("loading campaign {:?}", name))
+            }
+            None => Ok(Self::new(name)),
+        }
+    }
+
+    /// Loads the campaign file at `path`, which later saves go to as well.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from(path: &std::path::Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("reading {}", path.display()))?;
+        let campaign =
+            Self::from_json(&contents).wrap_err_with(|| format!("loading {}", path.display()))?;
+        Ok(Self {
+            path: Some(path.to_owned()),
+            ..campaign
+        })
+    }
+
+    /// Whether this was opened from a file rather than saved by name.
+    pub fn is_file(&self) -> bool {
+        self.path.is_some()
+    }
+
+    pub fn save(&self) -> eyre::Result<()> {
+        let contents = self.to_json()?;
+        match &self.path {
+            Some(path) => std::fs::write(path, contents)
+                .wrap_err_with(|| format!("writing {}", path.display())),
+            None => storage::save(&key(&self.name), &contents)
+                .wrap_err_with(|| format!("saving campaign {:?}", self.name)),
+        }
+    }
+
+    /// Exports the whole campaign with [`storage::export`], returning where it went.
+    pub fn export(&self) -> eyre::Result<String> {
+        storage::export(
+            &format!("{}.campaign.json", storage::file_stem(&self.name)),
+            &self.to_json()?,
+        )
+    }
+
+    pub fn from_json(contents: &str) -> eyre::Result<Self> {
+        serde_json::from_str(contents).wrap_err("parsing campaign")
+    }
+
+    pub fn to_json(&self) -> eyre::Result<String> {
+        serde_json::to_string_pretty(self).wrap_err("serializing campaign")
+    }
+
+    pub fn members(&self, role: Role) -> &[Member] {
+        match role {
+            Role::Party => &self.party,
+            Role::Npc => &self.npcs,
+        }
+    }
+
+    fn members_mut(&mut self, role: Role) -> &mut Vec<Member> {
+        match role {
+            Role::Party => &mut self.party,
+            Role::Npc => &mut self.npcs,
+        }
+    }
+
+    /// Everyone in the campaign, the party first.
+    pub fn choices(&self) -> Vec<MemberChoice> {
+        [Role::Party, Role::Npc]
+            .into_iter()
+            .flat_map(|role| {
+                self.members(role).iter().map(move |member| MemberChoice {
+                    role,
+                    name: member.name.clone(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn member(&self, choice: &MemberChoice) -> Option<&Member> {
+        self.members(choice.role)
+            .iter()
+            .find(|member| member.name == choice.name)
+    }
+
+    pub fn member_mut(&mut self, choice: &MemberChoice) -> Option<&mut Member> {
+        self.members_mut(choice.role)
+            .iter_mut()
+            .find(|member| member.name == choice.name)
+    }
+
+    /// Saves `character` as `name` in `role`, replacing whoever had that name in either list,
+    /// advances and all.
+    pub fn set_member(&mut self, role: Role, name: &str, character: Character) {
+        let name = name.trim();
+        for role in [Role::Party, Role::Npc] {
+            self.members_mut(role).retain(|member| member.name != name);
+        }
+        self.members_mut(role).push(Member {
+            name: name.to_owned(),
+            progression: Progression::new(character),
+        });
+    }
+
+    pub fn remove_member(&mut self, choice: &MemberChoice) {
+        self.members_mut(choice.role)
+            .retain(|member| member.name != choice.name);
+    }
+}
+
+fn key(name: &str) -> String {
+    format!("campaigns/{}.json", storage::file_stem(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn campaigns_round_trip_through_json() {
+        let mut rng: rand::rngs::SmallRng = rand::SeedableRng::seed_from_u64(4);
+        let mut campaign = Campaign::new("Night Market");
+        campaign.set_member(Role::Party, "Ada", crate::rules::random(&mut rng));
+        campaign.set_member(Role::Npc, "The Broker", crate::rules::random(&mut rng));
+        campaign.notes.push("the stalls close at dawn".into());
+        let json = campaign.to_json().unwrap();
+        assert_eq!(Campaign::from_json(&json).unwrap(), campaign);
+    }
+
+    #[test]
+    fn members_are_replaced_by_name() {
+        let mut campaign = Campaign::default();
+        campaign.set_member(Role::Party, "Ada", Character::default());
+        campaign.set_member(Role::Npc, " Ada ", Character::default());
+        assert!(campaign.party.is_empty());
+        assert_eq!(
+            campaign.choices(),
+            [MemberChoice {
+                role: Role::Npc,
+                name: "Ada".into()
+            }]
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! Characters as saved to and loaded from files.
+//!
+//! Spirits, connections, prerogatives and burdens are stored by name rather than by index so that
+//! files stay readable and survive reordering of the rules enums. Prerogative and burden names
+//! that aren't in the core rules are kept as homebrew.
+
+use crate::content::{BurdenId, PrerogativeId};
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use spirits_within::{Connection, Spirit};
+
+const CONNECTIONS: [Connection; 4] = [
+    Connection::Mastery,
+    Connection::Expertise,
+    Connection::Competence,
+    Connection::Ineptitude,
+];
+
+/// A possibly incomplete character build.
+///
+/// Serializes to the same form as character files, so it can be embedded in other messages.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(into = "CharacterFile", try_from = "CharacterFile")]
+pub struct Character {
+    pub spirits: Vec<(Spirit, Connection)>,
+    pub prerogatives: [Option<PrerogativeId>; 4],
+    pub burdens: Vec<(BurdenId, PrerogativeId)>,
+    /// Whether the player has declared the character done, which locks it against changes in
+    /// the character creator until they choose to edit it.
+    pub finalized: bool,
+}
+
+impl Character {
+    pub fn from_json(contents: &str) -> eyre::Result<Self> {
+        let file: CharacterFile = serde_json::from_str(contents).wrap_err("parsing character")?;
+        file.try_into()
+    }
+
+    pub fn to_json(&self) -> eyre::Result<String> {
+        serde_json::to_string_pretty(&CharacterFile::from(self)).wrap_err("serializing character")
+    }
+
+    /// Parses either a single character or a list of them.
+    pub fn list_from_json(contents: &str) -> eyre::Result<Vec<Self>> {
+        let files = match serde_json::from_str(contents).wrap_err("parsing characters")? {
+            OneOrMany::Many(files) => files,
+            OneOrMany::One(file) => vec![file],
+        };
+        files
+            .into_iter()
+            .enumerate()
+            .map(|(index, file)| {
+                Self::try_from(file).wrap_err_with(|| format!("character {}", index + 1))
+            })
+            .collect()
+    }
+
+    pub fn list_to_json(characters: &[Self]) -> eyre::Result<String> {
+        let files: Vec<CharacterFile> = characters.iter().map(Into::into).collect();
+        serde_json::to_string_pretty(&files).wrap_err("serializing characters")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &std::path::Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("reading {}", path.display()))?;
+        Self::from_json(&contents).wrap_err_with(|| format!("loading {}", path.display()))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: &std::path::Path) -> eyre::Result<()> {
+        std::fs::write(path, self.to_json()?)
+            .wrap_err_with(|| format!("writing {}", path.display()))
+    }
+}
+
+/// The on-disk form of a `Character`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CharacterFile {
+    /// Connection names keyed by spirit name.
+    spirits: std::collections::BTreeMap<String, String>,
+    /// Up to four base prerogative names.
+    prerogatives: Vec<String>,
+    burdens: Vec<BurdenEntry>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    finalized: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    Many(Vec<CharacterFile>),
+    One(CharacterFile),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BurdenEntry {
+    burden: String,
+    prerogative: String,
+}
+
+impl From<&Character> for CharacterFile {
+    fn from(character: &Character) -> Self {
+        Self {
+            spirits: character
+                .spirits
+                .iter()
+                .map(|(spirit, connection)| (name(spirit), name(connection)))
+                .collect(),
+            prerogatives: character.prerogatives.iter().flatten().map(name).collect(),
+            burdens: character
+                .burdens
+                .iter()
+                .map(|(burden, prerogative)| BurdenEntry {
+                    burden: name(burden),
+                    prerogative: name(prerogative),
+                })
+                .collect(),
+            finalized: character.finalized,
+        }
+    }
+}
+
+impl From<Character> for CharacterFile {
+    fn from(character: Character) -> Self {
+        Self::from(&character)
+    }
+}
+
+impl TryFrom<CharacterFile> for Character {
+    type Error = eyre::Report;
+
+    fn try_from(file: CharacterFile) -> Result<Self, Self::Error> {
+        if file.prerogatives.len() > 4 {
+            eyre::bail!(
+                "a character has at most 4 base prerogatives, found {}",
+                file.prerogatives.len()
+            );
+        }
+
+        let mut spirits = file
+            .spirits
+            .iter()
+            .map(|(spirit, connection)| {
+                Ok((
+                    lookup("spirit", spirit, Spirit::LIST)?,
+                    lookup("connection", connection, CONNECTIONS)?,
+                ))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        // keep the rules' order rather than the file's alphabetical one
+        spirits.sort_by_key(|(spirit, _)| Spirit::LIST.iter().position(|s| s == spirit));
+
+        let mut prerogatives: [Option<PrerogativeId>; 4] = Default::default();
+        for (slot, prerogative) in prerogatives.iter_mut().zip(&file.prerogatives) {
+            *slot = Some(PrerogativeId::from_name(prerogative)?);
+        }
+
+        let burdens = file
+            .burdens
+            .iter()
+            .map(|entry| {
+                Ok((
+                    BurdenId::from_name(&entry.burden)?,
+                    PrerogativeId::from_name(&entry.prerogative)?,
+                ))
+            })
+            .collect::<eyre::Result<_>>()?;
+
+        Ok(Self {
+            spirits,
+            prerogatives,
+            burdens,
+            finalized: file.finalized,
+        })
+    }
+}
+
+fn name(value: &impl std::fmt::Debug) -> String {
+    format!("{:?}", value)
+}
+
+fn lookup<T: std::fmt::Debug>(
+    kind: &str,
+    value: &str,
+    list: impl IntoIterator<Item = T>,
+) -> eyre::Result<T> {
+    list.into_iter()
+        .find(|item| name(item).eq_ignore_ascii_case(value))
+        .ok_or_else(|| eyre::eyre!("unknown {} {:?}", kind, value))
+}
+
+/// Serializes a stat by name, for `#[serde(with = "...")]`.
+pub(crate) mod stat_name {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use spirits_within::Stat;
+
+    pub fn serialize<S: Serializer>(stat: &Stat, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&super::name(stat))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Stat, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        super::lookup("stat", &name, crate::rules::STATS).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes an optional spirit by name, for `#[serde(default, with = "...")]`.
+pub(crate) mod optional_spirit_name {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use spirits_within::Spirit;
+
+    pub fn serialize<S: Serializer>(
+        spirit: &Option<Spirit>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match spirit {
+            Some(spirit) => serializer.collect_str(&super::name(spirit)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Spirit>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|name| super::lookup("spirit", &name, Spirit::LIST).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// A rules value that can be stored by name.
+pub(crate) trait Named: std::fmt::Debug + Clone + 'static {
+    /// What the values are called in errors.
+    const KIND: &'static str;
+
+    /// The value called `name`, ignoring case.
+    fn from_name(name: &str) -> eyre::Result<Self>;
+}
+
+impl Named for Spirit {
+    const KIND: &'static str = "spirit";
+
+    fn from_name(name: &str) -> eyre::Result<Self> {
+        lookup(Self::KIND, name, Spirit::LIST)
+    }
+}
+
+impl Named for Connection {
+    const KIND: &'static str = "connection";
+
+    fn from_name(name: &str) -> eyre::Result<Self> {
+        lookup(Self::KIND, name, CONNECTIONS)
+    }
+}
+
+/// Serializes any `Named` value by name, for `#[serde(with = "...")]`.
+pub(crate) mod by_name {
+    use super::Named;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: Named, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&super::name(value))
+    }
+
+    pub fn deserialize<'de, T: Named, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        T::from_name(&name).map_err(D::Error::custom)
+    }
+}
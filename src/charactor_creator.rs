@@ -1,8 +1,20 @@
+use crate::{
+    archetype::Archetype,
+    build::CharacterBuild,
+    campaign::{Campaign, MemberChoice, Role},
+    compendium::Compendium,
+    content::{BurdenId, PrerogativeId},
+    stat_chart::StatValues,
+    theme::Theme,
+    wizard::Step,
+};
 use iced_winit::{
-    widget::{pick_list, Button, Column, Row, Text},
+    widget::{pick_list, text_input, Button, Checkbox, Column, Row, TextInput},
     Element, Length,
 };
 
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+
 #[derive(Debug, Clone)]
 pub enum Message {
     SpiritSelected(spirits_within::Spirit, SelectionOption),
@@ -13,211 +25,287 @@ pub enum Message {
     RemoveOptionalBurden,
     Reset,
     Randomize,
+    /// Replaces the build with an archetype's.
+    ArchetypeSelected(Archetype),
+    /// Switches between the grid and the wizard, which share the build.
+    ShowWizard(bool),
+    WizardStep(Step),
     Export,
-}
-
-#[derive(Debug, Clone)]
+    /// Locks a legal build, showing it as a read-only sheet.
+    Finalize,
+    /// Unlocks a finalized build for editing.
+    Edit,
+    MemberNameChanged(String),
+    /// Saves the character to the campaign under the member name.
+    SaveMember(Role),
+    /// Replaces the build with a campaign member's character.
+    OpenMember(MemberChoice),
+}
+
+/// The character creator's widgets, drawn over a `CharacterBuild` that holds the actual choices.
+#[derive(Debug, Clone, Default)]
 pub struct CharacterCreator {
+    build: CharacterBuild,
+    /// The name to save the character to the campaign as.
+    member_name: String,
+    /// The archetype the build was last started from, if it wasn't reset or replaced since.
+    archetype: Option<Archetype>,
+    /// Whether the build is made a step at a time rather than on the grid.
+    show_wizard: bool,
+
     random_button: iced_winit::widget::button::State,
     reset_button: iced_winit::widget::button::State,
-
-    selected: SpiritSelection,
-    selection_options: Vec<SelectionOption>,
-
+    archetype_picker: pick_list::State<Archetype>,
+    mode_button: iced_winit::widget::button::State,
+    finalize_button: iced_winit::widget::button::State,
+    edit_button: iced_winit::widget::button::State,
+    member_name_input: text_input::State,
+    save_party_button: iced_winit::widget::button::State,
+    save_npc_button: iced_winit::widget::button::State,
+    member_picker: pick_list::State<MemberChoice>,
+    spirits: SpiritPickers,
     prerogatives: PrerogativesState,
-}
-
-impl Default for CharacterCreator {
-    fn default() -> Self {
-        Self::new()
-    }
+    wizard: WizardState,
 }
 
 impl CharacterCreator {
     pub fn new() -> Self {
-        Self {
-            random_button: Default::default(),
-            reset_button: Default::default(),
-            selection_options: vec![
-                SelectionOption::None,
-                SelectionOption::Mastery,
-                SelectionOption::Expertise,
-                SelectionOption::Competence,
-                SelectionOption::Ineptitude,
-            ],
-            selected: SpiritSelection::default(),
-            prerogatives: PrerogativesState::new(),
-        }
+        Self::default()
     }
 
-    pub fn update<R: rand::Rng>(&mut self, message: Message, rng: &mut R) {
+    /// Whether one of the screen's text inputs has keyboard focus.
+    pub fn is_editing_text(&self) -> bool {
+        self.member_name_input.is_focused()
+    }
+
+    /// Applies `message`, returning whether `campaign` changed.
+    pub fn update<R: rand::Rng>(
+        &mut self,
+        message: Message,
+        rng: &mut R,
+        campaign: &mut Campaign,
+    ) -> bool {
+        let build = &mut self.build;
         match message {
-            Message::SpiritSelected(index, connection) => {
-                let SelectionState { selection, .. } = &mut self.selected[index];
-                *selection = connection;
-                self.update_selection_options();
+            Message::SpiritSelected(spirit, connection) => {
+                build.set_connection(spirit, connection.into());
             }
-            Message::BasePrerogativeSelected(index, selection) => {
-                self.prerogatives.base_prerogatives[index].0 = selection;
+            Message::BasePrerogativeSelected(index, prerogative) => {
+                build.set_prerogative(index, prerogative.0);
             }
             Message::Reset => {
-                self.selected = Default::default();
-                self.update_selection_options();
-                self.prerogatives.clear()
+                *build = CharacterBuild::new();
+                self.archetype = None;
+                self.wizard.step = Step::default();
             }
             Message::Randomize => {
-                self.selected = Default::default();
-                self.update_selection_options();
-                for spirit in spirits_within::Spirit::LIST {
-                    let index = rng.gen_range(1..self.selection_options.len());
-                    self.selected[spirit].selection = self.selection_options[index];
-                    self.update_selection_options();
-                }
-                for (prerogative, _) in self.prerogatives.base_prerogatives.iter_mut() {
-                    let index = rng.gen_range(0..spirits_within::Prerogative::LIST.len());
-                    *prerogative = spirits_within::Prerogative::LIST[index].into();
-                }
-                self.prerogatives.optional_burdens.clear();
-                for _index in 0..4 {
-                    let burden_index = rng.gen_range(0..spirits_within::Burden::LIST.len());
-                    let prerog_index = rng.gen_range(0..spirits_within::Prerogative::LIST.len());
-                    self.prerogatives.optional_burdens.push(OptionalBurden {
-                        burden: spirits_within::Burden::LIST[burden_index].into(),
-                        burden_state: Default::default(),
-                        prerogative: spirits_within::Prerogative::LIST[prerog_index].into(),
-                        prerogative_state: Default::default(),
-                    })
-                }
+                *build = CharacterBuild::random(rng);
+                self.archetype = None;
             }
-            Message::AddOptionalBurden => {
-                self.prerogatives.optional_burdens.push(Default::default());
+            Message::ArchetypeSelected(archetype) => match archetype.build() {
+                Ok(archetype_build) => {
+                    *build = archetype_build;
+                    self.archetype = Some(archetype);
+                }
+                Err(err) => log::warn!("can't use archetype {:?}: {:#}", archetype.name, err),
+            },
+            Message::ShowWizard(show) => self.show_wizard = show,
+            Message::WizardStep(step) => self.wizard.step = step,
+            Message::AddOptionalBurden => build.add_burden(),
+            Message::RemoveOptionalBurden => build.remove_burden(),
+            Message::OptionalBurdenSelected(index, burden) => build.set_burden(index, burden.0),
+            Message::OptionalPrerogativeSelected(index, prerogative) => {
+                build.set_burden_prerogative(index, prerogative.0);
             }
-            Message::RemoveOptionalBurden => {
-                self.prerogatives.optional_burdens.pop();
+            Message::Export => {}
+            Message::Finalize => {
+                if let Err(err) = build.finalize() {
+                    log::warn!("can't finalize the character: {:#}", err);
+                }
             }
-            Message::OptionalBurdenSelected(index, burden) => {
-                self.prerogatives
-                    .optional_burdens
-                    .get_mut(index)
-                    .map(|state| {
-                        state.burden = burden;
-                    });
+            Message::Edit => build.unfinalize(),
+            Message::MemberNameChanged(name) => self.member_name = name,
+            Message::SaveMember(role) => {
+                if !self.member_name.trim().is_empty() {
+                    campaign.set_member(role, &self.member_name, build.character());
+                    return true;
+                }
             }
-            Message::OptionalPrerogativeSelected(index, prerogative) => {
-                self.prerogatives
-                    .optional_burdens
-                    .get_mut(index)
-                    .map(|state| {
-                        state.prerogative = prerogative;
-                    });
+            Message::OpenMember(choice) => {
+                if let Some(member) = campaign.member(&choice) {
+                    if let Ok(character) = member.progression.current() {
+                        *build = (&character).into();
+                        self.member_name = member.name.clone();
+                        self.archetype = None;
+                    }
+                }
             }
-            Message::Export => {}
         }
+        false
     }
 
+    /// The pickers' tooltips come from `compendium`.
     pub fn view(
         &mut self,
-    ) -> Element<'_, Message, <crate::Application as iced_winit::Program>::Renderer> {
+        campaign: &Campaign,
+        compendium: &Compendium,
+        archetypes: &[Archetype],
+        theme: Theme,
+    ) -> Element<'_, Message, Renderer> {
+        let typography = theme.typography();
+        let finalized = self.build.is_finalized();
+        // a finalized character only offers to be edited, so that a stray click can't change it
+        let editing = if finalized {
+            Row::new().push(
+                Button::new(&mut self.edit_button, theme.body("Edit"))
+                    .on_press(Message::Edit)
+                    .style(theme.button()),
+            )
+        } else {
+            let mut finalize = Button::new(&mut self.finalize_button, theme.body("Finalize"))
+                .style(theme.button());
+            if self.build.is_complete() {
+                finalize = finalize.on_press(Message::Finalize);
+            }
+            let (mode, show_wizard) = if self.show_wizard {
+                ("Grid", false)
+            } else {
+                ("Wizard", true)
+            };
+            Row::new()
+                .push(finalize)
+                .push(
+                    Button::new(&mut self.mode_button, theme.body(mode))
+                        .on_press(Message::ShowWizard(show_wizard))
+                        .style(theme.button()),
+                )
+                .push(with_tooltip(
+                    pick_list::PickList::new(
+                        &mut self.archetype_picker,
+                        archetypes.to_vec(),
+                        self.archetype.clone(),
+                        Message::ArchetypeSelected,
+                    )
+                    .placeholder("Start from archetype")
+                    .font(typography.body.into())
+                    .text_size(typography.body_size)
+                    .style(theme.pick_list()),
+                    self.archetype
+                        .as_ref()
+                        .map(|archetype| archetype.description.clone()),
+                    theme,
+                ))
+                .push(
+                    Button::new(&mut self.random_button, theme.body("Randomize"))
+                        .on_press(Message::Randomize)
+                        .style(theme.button()),
+                )
+                .push(
+                    Button::new(&mut self.reset_button, theme.body("Reset"))
+                        .on_press(Message::Reset)
+                        .style(theme.danger_button()),
+                )
+                .spacing(2)
+        };
         let mut root = Column::new()
             .push(
                 iced_winit::widget::Container::new(
                     Row::new()
                         .push(
-                            Button::new(&mut self.random_button, Text::new("Randomize"))
-                                .on_press(Message::Randomize),
+                            pick_list::PickList::new(
+                                &mut self.member_picker,
+                                campaign.choices(),
+                                None,
+                                Message::OpenMember,
+                            )
+                            .placeholder("Open from campaign")
+                            .font(typography.body.into())
+                            .text_size(typography.body_size)
+                            .style(theme.pick_list()),
+                        )
+                        .push(
+                            TextInput::new(
+                                &mut self.member_name_input,
+                                "Name",
+                                &self.member_name,
+                                Message::MemberNameChanged,
+                            )
+                            .font(typography.body.into())
+                            .size(typography.body_size)
+                            .padding(2)
+                            .width(Length::Units(160)),
+                        )
+                        .push(
+                            Button::new(&mut self.save_party_button, theme.body("Save to party"))
+                                .on_press(Message::SaveMember(Role::Party))
+                                .style(theme.button()),
                         )
                         .push(
-                            Button::new(&mut self.reset_button, Text::new("Reset"))
-                                .on_press(Message::Reset)
-                                .style(CustomStyle::with_bg(iced_winit::Color::from([
-                                    0.9, 0.2, 0.3,
-                                ]))),
-                        ),
+                            Button::new(&mut self.save_npc_button, theme.body("Save as NPC"))
+                                .on_press(Message::SaveMember(Role::Npc))
+                                .style(theme.button()),
+                        )
+                        .push(iced_winit::widget::Space::with_width(Length::Fill))
+                        .push(editing)
+                        .spacing(2)
+                        .align_items(iced_winit::Alignment::Center),
                 )
                 .align_x(iced_winit::alignment::Horizontal::Right)
                 .width(Length::Fill)
-                .style(CustomStyle::with_bg(iced_winit::Color::from([
-                    0.7, 0.7, 0.7,
-                ]))),
+                .style(theme.toolbar()),
             )
             .width(Length::Fill)
             .height(Length::Fill)
             .spacing(2);
 
-        let selection = (&self.selected)
-            .into_iter()
-            .all(|state| state.selection.is_some())
-            .then(|| {
-                spirits_within::SpiritSelection::try_from_iter(
-                    spirits_within::Spirit::LIST.into_iter().map(
-                        |spirit: spirits_within::Spirit| {
-                            (
-                                spirit,
-                                Into::<Option<spirits_within::Connection>>::into(
-                                    self.selected[spirit].selection,
-                                )
-                                .unwrap(),
-                            )
-                        },
-                    ),
+        if finalized {
+            return root
+                .push(
+                    crate::sheet::view(&self.build.character(), theme)
+                        .padding(5)
+                        .width(Length::Fill),
                 )
-            });
-
-        struct Rows<T> {
-            proficiency: Vec<T>,
-            knowledge: Vec<T>,
-            discipline: Vec<T>,
-        }
-
-        impl<T> Default for Rows<T> {
-            fn default() -> Self {
-                Self {
-                    proficiency: Vec::with_capacity(5),
-                    knowledge: Vec::with_capacity(5),
-                    discipline: Vec::with_capacity(5),
-                }
-            }
+                .into();
         }
 
-        impl<T> IntoIterator for Rows<T> {
-            type Item = (spirits_within::Stat, Vec<T>);
-            type IntoIter = std::array::IntoIter<Self::Item, 3>;
-
-            fn into_iter(self) -> Self::IntoIter {
-                use spirits_within::Stat::*;
-                [
-                    (Proficiency, self.proficiency),
-                    (Knowledge, self.knowledge),
-                    (Discipline, self.discipline),
-                ]
-                .into_iter()
-            }
+        if self.show_wizard {
+            return root
+                .push(
+                    self.wizard
+                        .view(&self.build, &mut self.prerogatives, compendium, theme),
+                )
+                .into();
         }
 
-        let mut rows = Rows::default();
-        for state in &mut self.selected {
-            let SelectionState {
-                spirit,
-                selection,
-                ref mut state,
-            } = *state;
-            use spirits_within::Stat;
-            let row = match spirit.stat() {
-                Stat::Knowledge => &mut rows.knowledge,
-                Stat::Discipline => &mut rows.discipline,
-                Stat::Proficiency => &mut rows.proficiency,
-            };
+        let build = &self.build;
+        let mut rows = StatRows::default();
+        for SpiritPicker { spirit, state } in &mut self.spirits {
+            let spirit = *spirit;
+            let row = rows.row(spirit.stat());
+            let options: Vec<SelectionOption> = std::iter::once(SelectionOption::None)
+                .chain(build.connection_options(spirit).into_iter().map(Into::into))
+                .collect();
+            let picker = pick_list::PickList::new(
+                state,
+                options,
+                Some(build.connection(spirit).into()),
+                move |connection| Message::SpiritSelected(spirit, connection),
+            )
+            .style(theme.pick_list());
+            let picker = with_tooltip(
+                picker,
+                build.connection(spirit).map(|c| compendium.connection(c)),
+                theme,
+            );
             row.push(
                 Column::new()
                     .align_items(iced_winit::Alignment::Center)
                     .width(Length::Fill)
-                    .push(Text::new(format!("{:?}", spirit)))
-                    .push(pick_list::PickList::new(
-                        state,
-                        &self.selection_options,
-                        Some(selection),
-                        move |connection| Message::SpiritSelected(spirit, connection),
+                    .push(theme.tooltip(
+                        theme.body(format!("{:?}", spirit)),
+                        compendium.spirit(spirit),
                     ))
+                    .push(picker)
                     .into(),
             )
         }
@@ -226,57 +314,57 @@ impl CharacterCreator {
             root = root
                 .push(
                     Row::new()
-                        .push(Text::new(format!("{:?}", stat)).size(32))
+                        .push(theme.heading(format!("{:?}", stat)))
                         .padding(5),
                 )
                 .push(Row::with_children(row).spacing(2));
         }
 
-        if let Some(selected) = selection {
-            match selected {
-                Ok(selected) => {
-                    let stats = spirits_within::BaseStats::new(&selected);
-                    let text = format!("{:#?}", stats);
-                    root = root.push(Text::new(text));
-                    root = root.push(self.prerogatives.view(stats)).width(Length::Fill);
-                }
-                Err(err) => {
-                    log::error!("OH GOD OH NO: {:?}", err);
-                }
-            }
+        if build.selection().is_some() {
+            root = root
+                .push(self.prerogatives.view(build, Part::All, compendium, theme))
+                .width(Length::Fill);
         }
 
         root.into()
     }
 
-    fn update_selection_options(&mut self) {
-        self.selection_options.clear();
-        self.selection_options.push(SelectionOption::None);
-        let mut mastery = 0;
-        let mut expertise = 0;
-        let mut competence = 0;
-        let mut inept = 0;
-        for SelectionState { selection, .. } in &self.selected {
-            match selection {
-                SelectionOption::None => {}
-                SelectionOption::Mastery => mastery += 1,
-                SelectionOption::Expertise => expertise += 1,
-                SelectionOption::Competence => competence += 1,
-                SelectionOption::Ineptitude => inept += 1,
-            }
-        }
-        if mastery < spirits_within::SpiritSelection::MASTERY_COUNT {
-            self.selection_options.push(SelectionOption::Mastery);
-        }
-        if expertise < spirits_within::SpiritSelection::EXPERTISE_COUNT {
-            self.selection_options.push(SelectionOption::Expertise);
-        }
-        if competence < spirits_within::SpiritSelection::COMPETENCE_COUNT {
-            self.selection_options.push(SelectionOption::Competence);
-        }
-        if inept < spirits_within::SpiritSelection::INEPTITUDE_COUNT {
-            self.selection_options.push(SelectionOption::Ineptitude);
-        }
+    /// Replaces the build with `character`'s.
+    pub fn load(&mut self, character: &crate::character::Character) {
+        self.build = character.into();
+        self.archetype = None;
+    }
+
+    /// The character as built so far.
+    pub fn character(&self) -> crate::character::Character {
+        self.build.character()
+    }
+
+    pub fn constellation(&self) -> Vec<renderer::SpiritNode> {
+        (&self.spirits)
+            .into_iter()
+            .map(|picker| {
+                use spirits_within::Stat;
+                let group = match picker.spirit.stat() {
+                    Stat::Proficiency => 0,
+                    Stat::Knowledge => 1,
+                    Stat::Discipline => 2,
+                };
+                renderer::SpiritNode {
+                    group,
+                    strength: SelectionOption::from(self.build.connection(picker.spirit))
+                        .strength(),
+                }
+            })
+            .collect()
+    }
+
+    /// The spirit at `index` in the order used by `constellation`.
+    pub fn spirit_at(&self, index: usize) -> Option<spirits_within::Spirit> {
+        (&self.spirits)
+            .into_iter()
+            .nth(index)
+            .map(|picker| picker.spirit)
     }
 }
 
@@ -290,8 +378,15 @@ pub enum SelectionOption {
 }
 
 impl SelectionOption {
-    pub fn is_some(self) -> bool {
-        self != SelectionOption::None
+    /// How strong the connection is, from 0 for no selection to 1 for mastery.
+    pub fn strength(self) -> f32 {
+        match self {
+            SelectionOption::None => 0.,
+            SelectionOption::Ineptitude => 0.25,
+            SelectionOption::Competence => 0.5,
+            SelectionOption::Expertise => 0.75,
+            SelectionOption::Mastery => 1.,
+        }
     }
 }
 
@@ -313,6 +408,23 @@ impl From<SelectionOption> for Option<spirits_within::Connection> {
     }
 }
 
+impl From<spirits_within::Connection> for SelectionOption {
+    fn from(connection: spirits_within::Connection) -> Self {
+        match connection {
+            spirits_within::Connection::Mastery => SelectionOption::Mastery,
+            spirits_within::Connection::Expertise => SelectionOption::Expertise,
+            spirits_within::Connection::Competence => SelectionOption::Competence,
+            spirits_within::Connection::Ineptitude => SelectionOption::Ineptitude,
+        }
+    }
+}
+
+impl From<Option<spirits_within::Connection>> for SelectionOption {
+    fn from(connection: Option<spirits_within::Connection>) -> Self {
+        connection.map_or(SelectionOption::None, Into::into)
+    }
+}
+
 impl std::fmt::Display for SelectionOption {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match Into::<Option<spirits_within::Connection>>::into(*self) {
@@ -323,24 +435,22 @@ impl std::fmt::Display for SelectionOption {
 }
 
 #[derive(Debug, Clone)]
-struct SelectionState {
+struct SpiritPicker {
     spirit: spirits_within::Spirit,
-    selection: SelectionOption,
     state: pick_list::State<SelectionOption>,
 }
 
-impl SelectionState {
-    pub fn spirit(spirit: spirits_within::Spirit) -> Self {
+impl SpiritPicker {
+    pub fn new(spirit: spirits_within::Spirit) -> Self {
         Self {
             spirit,
-            selection: Default::default(),
             state: Default::default(),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
-pub struct PrerogativeOption(Option<spirits_within::Prerogative>);
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PrerogativeOption(Option<PrerogativeId>);
 
 impl PrerogativeOption {
     pub fn none() -> Self {
@@ -348,8 +458,8 @@ impl PrerogativeOption {
     }
 }
 
-impl From<spirits_within::Prerogative> for PrerogativeOption {
-    fn from(v: spirits_within::Prerogative) -> Self {
+impl From<PrerogativeId> for PrerogativeOption {
+    fn from(v: PrerogativeId) -> Self {
         Self(Some(v))
     }
 }
@@ -363,8 +473,8 @@ impl std::fmt::Display for PrerogativeOption {
     }
 }
 
-#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
-pub struct BurdenOption(Option<spirits_within::Burden>);
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BurdenOption(Option<BurdenId>);
 
 impl BurdenOption {
     pub fn none() -> Self {
@@ -372,8 +482,8 @@ impl BurdenOption {
     }
 }
 
-impl From<spirits_within::Burden> for BurdenOption {
-    fn from(v: spirits_within::Burden) -> Self {
+impl From<BurdenId> for BurdenOption {
+    fn from(v: BurdenId) -> Self {
         Self(Some(v))
     }
 }
@@ -387,144 +497,73 @@ impl std::fmt::Display for BurdenOption {
     }
 }
 
-#[derive(Debug, Clone)]
-struct OptionalBurden {
-    burden: BurdenOption,
+#[derive(Debug, Clone, Default)]
+struct OptionalBurdenPickers {
     burden_state: pick_list::State<BurdenOption>,
-    prerogative: PrerogativeOption,
     prerogative_state: pick_list::State<PrerogativeOption>,
 }
 
-impl Default for OptionalBurden {
-    fn default() -> Self {
-        Self {
-            burden: BurdenOption::none(),
-            burden_state: Default::default(),
-            prerogative: Default::default(),
-            prerogative_state: Default::default(),
-        }
-    }
-}
-
-impl OptionalBurden {
-    fn zip(&self) -> Option<(spirits_within::Burden, spirits_within::Prerogative)> {
-        self.burden.0.zip(self.prerogative.0)
-    }
+/// Which of the prerogatives and burdens `PrerogativesState::view` shows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Part {
+    All,
+    BasePrerogatives,
+    Burdens,
 }
 
 #[derive(Debug, Default, Clone)]
 struct PrerogativesState {
-    base_prerogatives: [(PrerogativeOption, pick_list::State<PrerogativeOption>); 4],
-    prerogative_options: Vec<PrerogativeOption>,
-
-    burden_options: Vec<BurdenOption>,
-    optional_burdens: Vec<OptionalBurden>,
+    base_prerogatives: [pick_list::State<PrerogativeOption>; 4],
+    optional_burdens: Vec<OptionalBurdenPickers>,
     add_button: iced_winit::widget::button::State,
     remove_button: iced_winit::widget::button::State,
 }
 
 impl PrerogativesState {
-    pub fn new() -> Self {
-        Self {
-            base_prerogatives: [
-                Default::default(),
-                Default::default(),
-                Default::default(),
-                Default::default(),
-            ],
-            prerogative_options: std::iter::once(PrerogativeOption::none())
-                .chain(spirits_within::Prerogative::LIST.map(Into::into))
-                .collect(),
-            burden_options: std::iter::once(BurdenOption::none())
-                .chain(spirits_within::Burden::LIST.map(Into::into))
-                .collect(),
-            optional_burdens: vec![],
-            add_button: Default::default(),
-            remove_button: Default::default(),
-        }
-    }
-
-    fn clear(&mut self) {
-        *self = Self::new()
-    }
-
-    fn update_options(&mut self, mut stats: spirits_within::BaseStats) {
-        use spirits_within::{Prerogative, Stats};
-        self.prerogative_options.clear();
-        self.prerogative_options.push(PrerogativeOption::none());
-
-        let iter = self
-            .base_prerogatives
-            .iter()
-            .map(|(prerog, _)| prerog.0)
-            .chain(self.optional_burdens.iter().map(|b| b.prerogative.0));
-        for prerog in iter {
-            match prerog {
-                None => {}
-                Some(prerog) => match prerog {
-                    Prerogative::Conviction => stats.discipline += 3,
-                    Prerogative::Education => stats.knowledge += 3,
-                    Prerogative::Vocation => stats.proficiency += 3,
-                    _ => {}
-                },
-            }
-        }
-
-        for prerog in spirits_within::Prerogative::LIST {
-            let should_add = match prerog {
-                Prerogative::Conviction => stats.discipline < Stats::MAX_DISCIPLINE,
-                Prerogative::Education => stats.knowledge < Stats::MAX_KNOWLEDGE,
-                Prerogative::Vocation => stats.proficiency < Stats::MAX_PROFICIENCY,
-                _ => true,
-            };
-            if should_add {
-                self.prerogative_options.push(prerog.into());
-            }
-        }
-    }
-
-    fn stats(&self, base_stats: spirits_within::BaseStats) -> Result<spirits_within::Stats, ()> {
-        if self
-            .base_prerogatives
-            .iter()
-            .all(|(PrerogativeOption(p), _)| p.is_some())
-        {
-            let prerogatives = self
-                .base_prerogatives
-                .clone()
-                .map(|(PrerogativeOption(p), _)| p.unwrap());
-            let mut pb = spirits_within::PrerogativesAndBurdens::new(prerogatives);
-            for optional_burden in self.optional_burdens.iter() {
-                if let Some((b, p)) = optional_burden.zip() {
-                    pb.add_burden(b, p);
-                }
-            }
-            base_stats.with_prerogatives_and_burdens(&pb)
-        } else {
-            Err(())
-        }
-    }
-
     fn view(
         &mut self,
-        base_stats: spirits_within::BaseStats,
-    ) -> Element<'_, Message, <crate::Application as iced_winit::Program>::Renderer> {
-        self.update_options(base_stats);
-        let stats = self
-            .stats(base_stats)
-            .ok()
-            .map(|stats| Text::new(format!("{:#?}", stats)));
+        build: &CharacterBuild,
+        part: Part,
+        compendium: &Compendium,
+        theme: Theme,
+    ) -> Element<'_, Message, Renderer> {
+        let base_stats = build
+            .base_stats()
+            .expect("prerogatives are only shown once the spirits are chosen");
+        let preview = build.preview_stats().unwrap_or(base_stats);
+        let stats = build.stats().ok().map(StatValues::from);
+        let chart = crate::stat_chart::view(
+            stats.unwrap_or_else(|| preview.into()),
+            base_stats.into(),
+            theme,
+        );
+
+        let with_none = |options: Vec<PrerogativeId>| -> Vec<PrerogativeOption> {
+            std::iter::once(PrerogativeOption::none())
+                .chain(options.into_iter().map(Into::into))
+                .collect()
+        };
+        let burden_options: Vec<BurdenOption> = std::iter::once(BurdenOption::none())
+            .chain(build.burden_options().into_iter().map(Into::into))
+            .collect();
 
         let base_prerogs = Row::with_children(
             self.base_prerogatives
                 .iter_mut()
+                .zip(build.prerogatives())
                 .enumerate()
-                .map(|(index, (selection, state))| {
-                    iced_winit::widget::Container::new(pick_list::PickList::new(
+                .map(|(index, (state, selection))| {
+                    let picker = pick_list::PickList::new(
                         state,
-                        &self.prerogative_options,
-                        Some(*selection),
+                        with_none(build.prerogative_options(index)),
+                        Some(PrerogativeOption(selection.clone())),
                         move |prerogative| Message::BasePrerogativeSelected(index, prerogative),
+                    )
+                    .style(theme.pick_list());
+                    iced_winit::widget::Container::new(with_tooltip(
+                        picker,
+                        selection.as_ref().map(|p| compendium.prerogative(p)),
+                        theme,
                     ))
                     .align_y(iced_winit::alignment::Vertical::Top)
                     .width(Length::Fill)
@@ -534,18 +573,22 @@ impl PrerogativesState {
         )
         .width(Length::Fill);
 
-        let optional_prerogs = if stats.is_some() {
+        let optional_prerogs = if stats.is_some() && part != Part::BasePrerogatives {
             let buttons = {
-                let add_text = Text::new("+");
-                let add = Button::new(&mut self.add_button, add_text).width(Length::Fill);
-                let add = if self.optional_burdens.len() < 4 {
+                let add_text = theme.body("+");
+                let add = Button::new(&mut self.add_button, add_text)
+                    .width(Length::Fill)
+                    .style(theme.button());
+                let add = if build.can_add_burden() {
                     add.on_press(Message::AddOptionalBurden)
                 } else {
                     add
                 };
-                let remove_text = Text::new("-");
-                let remove = Button::new(&mut self.remove_button, remove_text).width(Length::Fill);
-                let remove = if self.optional_burdens.is_empty() {
+                let remove_text = theme.body("-");
+                let remove = Button::new(&mut self.remove_button, remove_text)
+                    .width(Length::Fill)
+                    .style(theme.button());
+                let remove = if build.burdens().is_empty() {
                     remove
                 } else {
                     remove.on_press(Message::RemoveOptionalBurden)
@@ -555,33 +598,50 @@ impl PrerogativesState {
                     .into()
             };
 
+            self.optional_burdens
+                .resize_with(build.burdens().len(), Default::default);
             let mut optionals = self
                 .optional_burdens
                 .iter_mut()
+                .zip(build.burdens())
                 .enumerate()
-                .map(|(index, optional_burden)| {
-                    let burden_picker = pick_list::PickList::new(
-                        &mut optional_burden.burden_state,
-                        &self.burden_options,
-                        Some(optional_burden.burden),
-                        move |burden| Message::OptionalBurdenSelected(index, burden),
-                    )
-                    .width(Length::Fill)
-                    .into();
-                    let prerogative_picker = pick_list::PickList::new(
-                        &mut optional_burden.prerogative_state,
-                        &self.prerogative_options,
-                        Some(optional_burden.prerogative),
-                        move |prerog| Message::OptionalPrerogativeSelected(index, prerog),
-                    )
-                    .width(Length::Fill)
-                    .into();
+                .map(|(index, (pickers, optional_burden))| {
+                    let burden_picker = with_tooltip(
+                        pick_list::PickList::new(
+                            &mut pickers.burden_state,
+                            burden_options.clone(),
+                            Some(BurdenOption(optional_burden.burden.clone())),
+                            move |burden| Message::OptionalBurdenSelected(index, burden),
+                        )
+                        .width(Length::Fill)
+                        .style(theme.pick_list()),
+                        optional_burden
+                            .burden
+                            .as_ref()
+                            .map(|b| compendium.burden(b)),
+                        theme,
+                    );
+                    let prerogative_picker = with_tooltip(
+                        pick_list::PickList::new(
+                            &mut pickers.prerogative_state,
+                            with_none(build.burden_prerogative_options(index)),
+                            Some(PrerogativeOption(optional_burden.prerogative.clone())),
+                            move |prerog| Message::OptionalPrerogativeSelected(index, prerog),
+                        )
+                        .width(Length::Fill)
+                        .style(theme.pick_list()),
+                        optional_burden
+                            .prerogative
+                            .as_ref()
+                            .map(|p| compendium.prerogative(p)),
+                        theme,
+                    );
                     Column::with_children(vec![burden_picker, prerogative_picker])
                         .width(Length::Fill)
                         .into()
                 })
                 .collect::<Vec<_>>();
-            while optionals.len() < 4 {
+            while optionals.len() < crate::rules::MAX_OPTIONAL_BURDENS {
                 optionals.push(iced_winit::widget::Space::new(Length::Fill, Length::Shrink).into());
             }
             let optionals = Row::with_children(optionals)
@@ -597,105 +657,242 @@ impl PrerogativesState {
             iced_winit::widget::Space::new(Length::Fill, Length::Shrink).into()
         };
 
+        let (title, parts) = match part {
+            Part::All => (
+                "Prerogatives And Burdens",
+                vec![base_prerogs.into(), optional_prerogs],
+            ),
+            Part::BasePrerogatives => ("Prerogatives", vec![base_prerogs.into()]),
+            Part::Burdens => ("Burdens", vec![optional_prerogs]),
+        };
         let titled_prerogs = Column::with_children(vec![
-            Row::new()
-                .push(Text::new("Prerogatives And Burdens").size(32))
-                .padding(5)
-                .into(),
-            Row::with_children(vec![base_prerogs.into(), optional_prerogs])
-                .width(Length::Fill)
-                .into(),
+            Row::new().push(theme.heading(title)).padding(5).into(),
+            Row::with_children(parts).width(Length::Fill).into(),
         ])
         .width(Length::Fill);
-        match stats {
-            Some(stats) => Column::new()
-                .push(titled_prerogs)
-                .push(stats)
-                .width(Length::Fill)
-                .into(),
-            None => titled_prerogs.into(),
-        }
+        Column::new()
+            .push(titled_prerogs)
+            .push(chart)
+            .width(Length::Fill)
+            .into()
     }
 }
 
-struct CustomStyle {
-    style: iced_winit::widget::container::Style,
+/// Something for each spirit, grouped by the spirits' stats in the order the creator shows them.
+pub(crate) struct StatRows<T> {
+    proficiency: Vec<T>,
+    knowledge: Vec<T>,
+    discipline: Vec<T>,
+}
+
+impl<T> StatRows<T> {
+    pub fn row(&mut self, stat: spirits_within::Stat) -> &mut Vec<T> {
+        use spirits_within::Stat;
+        match stat {
+            Stat::Knowledge => &mut self.knowledge,
+            Stat::Discipline => &mut self.discipline,
+            Stat::Proficiency => &mut self.proficiency,
+        }
+    }
 }
 
-impl CustomStyle {
-    pub fn with_bg(color: iced_winit::Color) -> Self {
+impl<T> Default for StatRows<T> {
+    fn default() -> Self {
         Self {
-            style: iced_winit::widget::container::Style {
-                background: Some(iced_solstice::Background::Color(color)),
-                ..Default::default()
-            },
+            proficiency: Vec::with_capacity(5),
+            knowledge: Vec::with_capacity(5),
+            discipline: Vec::with_capacity(5),
         }
     }
 }
 
-impl iced_winit::widget::container::StyleSheet for CustomStyle {
-    fn style(&self) -> iced_winit::widget::container::Style {
-        self.style
+impl<T> IntoIterator for StatRows<T> {
+    type Item = (spirits_within::Stat, Vec<T>);
+    type IntoIter = std::array::IntoIter<Self::Item, 3>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        use spirits_within::Stat::*;
+        [
+            (Proficiency, self.proficiency),
+            (Knowledge, self.knowledge),
+            (Discipline, self.discipline),
+        ]
+        .into_iter()
     }
 }
 
-impl iced_winit::widget::button::StyleSheet for CustomStyle {
-    fn active(&self) -> iced_winit::widget::button::Style {
-        iced_winit::widget::button::Style {
-            shadow_offset: Default::default(),
-            background: self.style.background,
-            border_radius: self.style.border_radius,
-            border_width: self.style.border_width,
-            border_color: iced_winit::Color::BLACK,
-            text_color: self.style.text_color.unwrap_or(iced_winit::Color::BLACK),
+/// The wizard's widgets, drawn over the same build as the grid.
+#[derive(Debug, Clone, Default)]
+struct WizardState {
+    step: Step,
+    back_button: iced_winit::widget::button::State,
+    next_button: iced_winit::widget::button::State,
+}
+
+impl WizardState {
+    fn view<'a>(
+        &'a mut self,
+        build: &CharacterBuild,
+        prerogatives: &'a mut PrerogativesState,
+        compendium: &Compendium,
+        theme: Theme,
+    ) -> Element<'a, Message, Renderer> {
+        let step = self.step;
+        let body: Element<'a, Message, Renderer> = match step {
+            Step::Spirits(_) => spirit_checkboxes(build, step, compendium, theme),
+            Step::Prerogatives | Step::Burdens if build.base_stats().is_none() => {
+                theme.body("Choose every spirit's connection first.").into()
+            }
+            Step::Prerogatives => {
+                prerogatives.view(build, Part::BasePrerogatives, compendium, theme)
+            }
+            Step::Burdens => prerogatives.view(build, Part::Burdens, compendium, theme),
+            Step::Review => crate::sheet::view(&build.character(), theme).into(),
+        };
+
+        let mut back = Button::new(&mut self.back_button, theme.body("Back")).style(theme.button());
+        if let Some(previous) = step.previous() {
+            back = back.on_press(Message::WizardStep(previous));
         }
+        let mut navigation = Row::new()
+            .push(back)
+            .spacing(10)
+            .align_items(iced_winit::Alignment::Center);
+        if let Some(next) = step.next() {
+            let mut next_button =
+                Button::new(&mut self.next_button, theme.body("Next")).style(theme.button());
+            match step.unfinished(build) {
+                Some(unfinished) => {
+                    navigation = navigation
+                        .push(next_button)
+                        .push(theme.body(unfinished).color(theme.palette().danger));
+                }
+                None => {
+                    next_button = next_button.on_press(Message::WizardStep(next));
+                    navigation = navigation.push(next_button);
+                }
+            }
+        }
+
+        Column::new()
+            .push(theme.heading(format!(
+                "Step {} of {}: {}",
+                step.number(),
+                Step::COUNT,
+                step.title()
+            )))
+            .push(theme.body(step.explanation(compendium)))
+            .push(body)
+            .push(navigation)
+            .spacing(10)
+            .padding(5)
+            .width(Length::Fill)
+            .into()
+    }
+}
+
+/// A checkbox per spirit, grouped by stat, for whether it has the connection of the spirit step
+/// `step`. Only spirits without a connection can be checked, while some of this one are left;
+/// those with another are labelled with it.
+fn spirit_checkboxes<'a>(
+    build: &CharacterBuild,
+    step: Step,
+    compendium: &Compendium,
+    theme: Theme,
+) -> Element<'a, Message, Renderer> {
+    let connection = step
+        .connection()
+        .expect("spirit checkboxes are only shown in a spirit step");
+    let typography = theme.typography();
+    let open = build.remaining(connection) > 0;
+    let mut groups = Column::new().spacing(5);
+    for stat in crate::rules::STATS {
+        let checkboxes = spirits_within::Spirit::LIST
+            .into_iter()
+            .filter(|spirit| spirit.stat() == stat)
+            .map(|spirit| {
+                let current = build.connection(spirit);
+                let label = match current {
+                    Some(other) if other != connection => format!("{:?} ({})", spirit, other),
+                    _ => format!("{:?}", spirit),
+                };
+                let checkbox = Checkbox::new(current == Some(connection), label, move |on| {
+                    let chosen = match (on, current) {
+                        (true, None) if open => Some(connection),
+                        (true, _) => current,
+                        (false, _) => None,
+                    };
+                    Message::SpiritSelected(spirit, chosen.into())
+                })
+                .size(typography.body_size)
+                .text_size(typography.body_size)
+                .width(Length::Fill);
+                theme.tooltip(checkbox, compendium.spirit(spirit)).into()
+            })
+            .collect();
+        groups = groups
+            .push(theme.heading(format!("{:?}", stat)))
+            .push(Row::with_children(checkboxes).spacing(2));
+    }
+    groups.into()
+}
+
+/// `picker` with the description of its choice as a tooltip, if anything is chosen.
+fn with_tooltip<'a>(
+    picker: impl Into<Element<'a, Message, Renderer>>,
+    description: Option<String>,
+    theme: Theme,
+) -> Element<'a, Message, Renderer> {
+    match description {
+        Some(description) => theme.tooltip(picker, description).into(),
+        None => picker.into(),
     }
 }
 
 #[derive(Debug, Clone)]
-struct SpiritSelection {
-    the_veil: SelectionState,
-    mirror: SelectionState,
-    the_path: SelectionState,
-    shadows: SelectionState,
-    instinct: SelectionState,
-    reason: SelectionState,
-    whispers: SelectionState,
-    respect: SelectionState,
-    drama: SelectionState,
-    motion: SelectionState,
-    muscle: SelectionState,
-    kinesis: SelectionState,
-    glamour: SelectionState,
-    balance: SelectionState,
-    the_pulse: SelectionState,
-}
-
-impl Default for SpiritSelection {
+struct SpiritPickers {
+    the_veil: SpiritPicker,
+    mirror: SpiritPicker,
+    the_path: SpiritPicker,
+    shadows: SpiritPicker,
+    instinct: SpiritPicker,
+    reason: SpiritPicker,
+    whispers: SpiritPicker,
+    respect: SpiritPicker,
+    drama: SpiritPicker,
+    motion: SpiritPicker,
+    muscle: SpiritPicker,
+    kinesis: SpiritPicker,
+    glamour: SpiritPicker,
+    balance: SpiritPicker,
+    the_pulse: SpiritPicker,
+}
+
+impl Default for SpiritPickers {
     fn default() -> Self {
         use spirits_within::Spirit::*;
         Self {
-            the_veil: SelectionState::spirit(TheVeil),
-            mirror: SelectionState::spirit(Mirror),
-            the_path: SelectionState::spirit(ThePath),
-            shadows: SelectionState::spirit(Shadows),
-            instinct: SelectionState::spirit(Instinct),
-            reason: SelectionState::spirit(Reason),
-            whispers: SelectionState::spirit(Whispers),
-            respect: SelectionState::spirit(Respect),
-            drama: SelectionState::spirit(Drama),
-            motion: SelectionState::spirit(Motion),
-            muscle: SelectionState::spirit(Muscle),
-            kinesis: SelectionState::spirit(Kinesis),
-            glamour: SelectionState::spirit(Glamour),
-            balance: SelectionState::spirit(Balance),
-            the_pulse: SelectionState::spirit(ThePulse),
+            the_veil: SpiritPicker::new(TheVeil),
+            mirror: SpiritPicker::new(Mirror),
+            the_path: SpiritPicker::new(ThePath),
+            shadows: SpiritPicker::new(Shadows),
+            instinct: SpiritPicker::new(Instinct),
+            reason: SpiritPicker::new(Reason),
+            whispers: SpiritPicker::new(Whispers),
+            respect: SpiritPicker::new(Respect),
+            drama: SpiritPicker::new(Drama),
+            motion: SpiritPicker::new(Motion),
+            muscle: SpiritPicker::new(Muscle),
+            kinesis: SpiritPicker::new(Kinesis),
+            glamour: SpiritPicker::new(Glamour),
+            balance: SpiritPicker::new(Balance),
+            the_pulse: SpiritPicker::new(ThePulse),
         }
     }
 }
 
-impl<'a> IntoIterator for &'a SpiritSelection {
-    type Item = &'a SelectionState;
+impl<'a> IntoIterator for &'a SpiritPickers {
+    type Item = &'a SpiritPicker;
     type IntoIter = std::array::IntoIter<Self::Item, 15>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -720,8 +917,8 @@ impl<'a> IntoIterator for &'a SpiritSelection {
     }
 }
 
-impl<'a> IntoIterator for &'a mut SpiritSelection {
-    type Item = &'a mut SelectionState;
+impl<'a> IntoIterator for &'a mut SpiritPickers {
+    type Item = &'a mut SpiritPicker;
     type IntoIter = std::array::IntoIter<Self::Item, 15>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -746,8 +943,8 @@ impl<'a> IntoIterator for &'a mut SpiritSelection {
     }
 }
 
-impl std::ops::Index<spirits_within::Spirit> for SpiritSelection {
-    type Output = SelectionState;
+impl std::ops::Index<spirits_within::Spirit> for SpiritPickers {
+    type Output = SpiritPicker;
 
     fn index(&self, index: spirits_within::Spirit) -> &Self::Output {
         use spirits_within::Spirit::*;
@@ -771,7 +968,7 @@ impl std::ops::Index<spirits_within::Spirit> for SpiritSelection {
     }
 }
 
-impl std::ops::IndexMut<spirits_within::Spirit> for SpiritSelection {
+impl std::ops::IndexMut<spirits_within::Spirit> for SpiritPickers {
     fn index_mut(&mut self, index: spirits_within::Spirit) -> &mut Self::Output {
         use spirits_within::Spirit::*;
         match index {
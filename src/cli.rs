@@ -0,0 +1,211 @@
+//! Command-line arguments for the native binary.
+
+use crate::{config::Config, ApplicationState};
+use clap::Parser;
+use eyre::WrapErr;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, Parser)]
+#[command(
+    name = "spirits-within",
+    about = "Build and play Spirits Within characters"
+)]
+pub struct Args {
+    /// Seed for the random number generator, overriding the configured seed behavior.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Window size as WIDTHxHEIGHT, e.g. 1600x900.
+    #[arg(long, value_parser = parse_window_size)]
+    pub window_size: Option<WindowSize>,
+    /// Start in fullscreen.
+    #[arg(long)]
+    pub fullscreen: bool,
+    /// Read the configuration from this TOML file instead of the default location.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Open a character file in the character creator.
+    #[arg(long, value_name = "CHARACTER FILE")]
+    pub open: Option<PathBuf>,
+    /// Work in a campaign file rather than the saved campaign. Changes are saved back to it.
+    #[arg(long, value_name = "CAMPAIGN FILE")]
+    pub campaign: Option<PathBuf>,
+    /// The screen to start on.
+    #[arg(long, value_enum)]
+    pub screen: Option<Screen>,
+    /// The most verbose log messages to print: off, error, warn, info, debug or trace. Overrides
+    /// `RUST_LOG`, which otherwise decides, printing warnings and errors if it isn't set either.
+    #[arg(long, value_parser = parse_log_level)]
+    pub log_level: Option<log::LevelFilter>,
+}
+
+impl Args {
+    /// The configuration from `--config`, or the saved configuration if that wasn't given.
+    pub fn load_config(&self) -> eyre::Result<Config> {
+        match &self.config {
+            Some(path) => Config::load_from(path),
+            None => Ok(Config::load()),
+        }
+    }
+
+    /// The character from `--open`, if given.
+    pub fn load_character(&self) -> eyre::Result<Option<crate::character::Character>> {
+        self.open
+            .as_deref()
+            .map(crate::character::Character::load)
+            .transpose()
+    }
+
+    /// The campaign from `--campaign`, if given.
+    pub fn load_campaign(&self) -> eyre::Result<Option<crate::campaign::Campaign>> {
+        self.campaign
+            .as_deref()
+            .map(crate::campaign::Campaign::load_from)
+            .transpose()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+fn parse_window_size(s: &str) -> eyre::Result<WindowSize> {
+    let (width, height) = s
+        .split_once(['x', 'X'])
+        .ok_or_else(|| eyre::eyre!("expected WIDTHxHEIGHT, got {:?}", s))?;
+    let parse = |value: &str| -> eyre::Result<u32> {
+        let value = value
+            .trim()
+            .parse()
+            .wrap_err_with(|| format!("invalid dimension {:?}", value))?;
+        if value == 0 {
+            eyre::bail!("window dimensions must be greater than zero");
+        }
+        Ok(value)
+    };
+    Ok(WindowSize {
+        width: parse(width)?,
+        height: parse(height)?,
+    })
+}
+
+fn parse_log_level(s: &str) -> eyre::Result<log::LevelFilter> {
+    s.parse().map_err(|_| {
+        eyre::eyre!(
+            "expected one of off, error, warn, info, debug or trace, got {:?}",
+            s
+        )
+    })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum Screen {
+    Game,
+    Creator,
+    Advancement,
+    Reference,
+    Compare,
+    Homebrew,
+    Settings,
+}
+
+impl From<Screen> for ApplicationState {
+    fn from(screen: Screen) -> Self {
+        match screen {
+            Screen::Game => ApplicationState::Game,
+            Screen::Creator => ApplicationState::CharacterCreator,
+            Screen::Advancement => ApplicationState::Advancement,
+            Screen::Reference => ApplicationState::Reference,
+            Screen::Compare => ApplicationState::Comparison,
+            Screen::Homebrew => ApplicationState::Homebrew,
+            Screen::Settings => ApplicationState::Settings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let args = Args::try_parse_from(["native"]).unwrap();
+        assert_eq!(args.seed, None);
+        assert_eq!(args.window_size, None);
+        assert!(!args.fullscreen);
+        assert_eq!(args.screen, None);
+        assert_eq!(args.log_level, None);
+    }
+
+    #[test]
+    fn all_arguments() {
+        let args = Args::try_parse_from([
+            "native",
+            "--seed",
+            "42",
+            "--window-size",
+            "1600x900",
+            "--fullscreen",
+            "--config",
+            "settings.toml",
+            "--open",
+            "hero.json",
+            "--campaign",
+            "night-market.campaign.json",
+            "--screen",
+            "game",
+            "--log-level",
+            "debug",
+        ])
+        .unwrap();
+        assert_eq!(
+            args,
+            Args {
+                seed: Some(42),
+                window_size: Some(WindowSize {
+                    width: 1600,
+                    height: 900
+                }),
+                fullscreen: true,
+                config: Some("settings.toml".into()),
+                open: Some("hero.json".into()),
+                campaign: Some("night-market.campaign.json".into()),
+                screen: Some(Screen::Game),
+                log_level: Some(log::LevelFilter::Debug),
+            }
+        );
+    }
+
+    #[test]
+    fn every_screen_can_be_started_on() {
+        let args = Args::try_parse_from(["native", "--screen", "settings"]).unwrap();
+        assert_eq!(args.screen, Some(Screen::Settings));
+        assert!(matches!(
+            ApplicationState::from(Screen::Settings),
+            ApplicationState::Settings
+        ));
+    }
+
+    #[test]
+    fn window_size() {
+        assert_eq!(
+            parse_window_size("1280X720").unwrap(),
+            WindowSize {
+                width: 1280,
+                height: 720
+            }
+        );
+        assert!(parse_window_size("1280").is_err());
+        assert!(parse_window_size("0x720").is_err());
+        assert!(parse_window_size("widexhigh").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(Args::try_parse_from(["native", "--screen", "lobby"]).is_err());
+        assert!(Args::try_parse_from(["native", "--seed", "-1"]).is_err());
+        assert!(Args::try_parse_from(["native", "--window-size", "big"]).is_err());
+        assert!(Args::try_parse_from(["native", "--log-level", "loud"]).is_err());
+    }
+}
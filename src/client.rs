@@ -0,0 +1,241 @@
+//! Joins a shared table hosted by a [`server`](crate::server).
+//!
+//! Connecting never blocks. The connection is made in the background, natively on its own thread
+//! and on the web by the browser's `WebSocket`, and everything the server says, down to why the
+//! connection closed, arrives through [`Client::events`].
+
+use crate::protocol::{ClientMessage, ServerMessage, VERSION};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Message(ServerMessage),
+    /// The connection closed or couldn't be made. Nothing follows this.
+    Closed(String),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct Client {
+    outbox: std::sync::mpsc::Sender<ClientMessage>,
+    events: std::sync::mpsc::Receiver<Event>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Client {
+    /// Connects to the server at `url`, e.g. `ws://localhost:7878`, and joins as `name`.
+    pub fn connect(url: &str, name: &str) -> Self {
+        let (outbox, inbox) = std::sync::mpsc::channel();
+        let (events_sx, events) = std::sync::mpsc::channel();
+        let hello = ClientMessage::Hello {
+            version: VERSION,
+            name: name.to_owned(),
+        };
+        let url = url.to_owned();
+        std::thread::spawn(move || {
+            let reason = match run(&url, &hello, &inbox, &events_sx) {
+                Ok(()) => "disconnected".to_owned(),
+                Err(err) => format!("{:#}", err),
+            };
+            let _ = events_sx.send(Event::Closed(reason));
+        });
+        Self { outbox, events }
+    }
+
+    pub fn send(&self, message: ClientMessage) {
+        // if the connection is gone a `Closed` event already says so
+        let _ = self.outbox.send(message);
+    }
+
+    /// Everything that happened since the last call, without waiting.
+    pub fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        self.events.try_iter()
+    }
+}
+
+/// Talks to the server until it hangs up or the `Client` is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+fn run(
+    url: &str,
+    hello: &ClientMessage,
+    inbox: &std::sync::mpsc::Receiver<ClientMessage>,
+    events: &std::sync::mpsc::Sender<Event>,
+) -> eyre::Result<()> {
+    use eyre::WrapErr;
+    use std::sync::mpsc::TryRecvError;
+    use tungstenite::{stream::MaybeTlsStream, Message};
+
+    let (mut socket, _) =
+        tungstenite::connect(url).wrap_err_with(|| format!("connecting to {}", url))?;
+    if let MaybeTlsStream::Plain(stream) = socket.get_mut() {
+        stream
+            .set_read_timeout(Some(crate::server::POLL_INTERVAL))
+            .wrap_err("setting the read timeout")?;
+    }
+    let send = |socket: &mut tungstenite::WebSocket<_>, message: &ClientMessage| {
+        socket
+            .send(Message::Text(message.to_json()?))
+            .wrap_err("writing to the server")
+    };
+
+    send(&mut socket, hello)?;
+    loop {
+        loop {
+            match inbox.try_recv() {
+                Ok(message) => send(&mut socket, &message)?,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    let _ = socket.close(None);
+                    return Ok(());
+                }
+            }
+        }
+        match socket.read() {
+            Ok(Message::Text(text)) => match ServerMessage::from_json(&text) {
+                Ok(message) => {
+                    if events.send(Event::Message(message)).is_err() {
+                        return Ok(());
+                    }
+                }
+                Err(err) => log::warn!("ignoring message from the server: {:?}", err),
+            },
+            Ok(_) => {}
+            Err(err) if crate::server::is_timeout(&err) => {}
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                return Ok(())
+            }
+            Err(err) => return Err(err).wrap_err("reading from the server"),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub struct Client {
+    events: crossbeam_channel::Receiver<Event>,
+    /// `None` if the browser refused to open the connection.
+    socket: Option<Socket>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Client {
+    /// Connects to the server at `url`, e.g. `ws://localhost:7878`, and joins as `name`.
+    pub fn connect(url: &str, name: &str) -> Self {
+        let (events_sx, events) = crossbeam_channel::unbounded();
+        let hello = ClientMessage::Hello {
+            version: VERSION,
+            name: name.to_owned(),
+        };
+        let socket = match Socket::open(url, &hello, events_sx.clone()) {
+            Ok(socket) => Some(socket),
+            Err(err) => {
+                let _ = events_sx.send(Event::Closed(format!("connecting to {}: {:?}", url, err)));
+                None
+            }
+        };
+        Self { events, socket }
+    }
+
+    pub fn send(&self, message: ClientMessage) {
+        let result = match (&self.socket, message.to_json()) {
+            (Some(socket), Ok(json)) => socket
+                .socket
+                .send_with_str(&json)
+                .map_err(|err| eyre::eyre!("{:?}", err)),
+            (None, _) => Ok(()),
+            (_, Err(err)) => Err(err),
+        };
+        if let Err(err) = result {
+            log::warn!("failed to send to the server: {:?}", err);
+        }
+    }
+
+    /// Everything that happened since the last call, without waiting.
+    pub fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        self.events.try_iter()
+    }
+}
+
+/// A browser `WebSocket` and the callbacks it holds onto.
+#[cfg(target_arch = "wasm32")]
+struct Socket {
+    socket: web_sys::WebSocket,
+    #[allow(unused)]
+    on_open: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>,
+    #[allow(unused)]
+    on_message: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::MessageEvent)>,
+    #[allow(unused)]
+    on_close: wasm_bindgen::closure::Closure<dyn FnMut(web_sys::CloseEvent)>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Socket {
+    fn open(
+        url: &str,
+        hello: &ClientMessage,
+        events: crossbeam_channel::Sender<Event>,
+    ) -> Result<Self, wasm_bindgen::JsValue> {
+        use wasm_bindgen::{closure::Closure, JsCast};
+
+        let hello = hello
+            .to_json()
+            .map_err(|err| wasm_bindgen::JsValue::from_str(&format!("{:#}", err)))?;
+        let socket = web_sys::WebSocket::new(url)?;
+
+        let on_open = {
+            let socket = socket.clone();
+            Closure::wrap(Box::new(move |_: web_sys::Event| {
+                if let Err(err) = socket.send_with_str(&hello) {
+                    log::warn!("failed to join: {:?}", err);
+                }
+            }) as Box<dyn FnMut(_)>)
+        };
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+
+        let on_message = {
+            let events = events.clone();
+            Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+                let text = match event.data().as_string() {
+                    Some(text) => text,
+                    None => return,
+                };
+                match ServerMessage::from_json(&text) {
+                    Ok(message) => {
+                        let _ = events.send(Event::Message(message));
+                    }
+                    Err(err) => log::warn!("ignoring message from the server: {:?}", err),
+                }
+            }) as Box<dyn FnMut(_)>)
+        };
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        let on_close = Closure::wrap(Box::new(move |event: web_sys::CloseEvent| {
+            let reason = match event.reason() {
+                reason if reason.is_empty() => "disconnected".to_owned(),
+                reason => reason,
+            };
+            let _ = events.send(Event::Closed(reason));
+        }) as Box<dyn FnMut(_)>);
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            on_open,
+            on_message,
+            on_close,
+        })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for Socket {
+    fn drop(&mut self) {
+        self.socket.set_onopen(None);
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        let _ = self.socket.close();
+    }
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client").finish_non_exhaustive()
+    }
+}
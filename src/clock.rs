@@ -0,0 +1,173 @@
+/// Tracks animation time for the front ends.
+///
+/// Timestamps are fed in with `tick` from whatever time source the platform has and turned into
+/// scaled, pausable delta times. Time is also accumulated into fixed-size steps for updates that
+/// should not depend on frame rate.
+#[derive(Debug, Clone)]
+pub struct Clock {
+    last: Option<f64>,
+    paused: bool,
+    time_scale: f32,
+    elapsed: f64,
+    fixed_step: f32,
+    accumulator: f32,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock {
+    /// The default fixed step: 60 updates per second.
+    pub const FIXED_STEP: f32 = 1. / 60.;
+    const MIN_TIME_SCALE: f32 = 0.125;
+    const MAX_TIME_SCALE: f32 = 8.;
+    /// Frames longer than this are clamped so a stalled tab doesn't produce a huge catch-up.
+    const MAX_DELTA: f32 = 0.25;
+
+    pub fn new() -> Self {
+        Self::with_fixed_step(Self::FIXED_STEP)
+    }
+
+    pub fn with_fixed_step(fixed_step: f32) -> Self {
+        Self {
+            last: None,
+            paused: false,
+            time_scale: 1.,
+            elapsed: 0.,
+            fixed_step,
+            accumulator: 0.,
+        }
+    }
+
+    /// Advances the clock to `now`, in seconds, and returns the scaled delta time.
+    ///
+    /// Returns 0 while paused and on the first tick.
+    pub fn tick(&mut self, now: f64) -> f32 {
+        let raw = match self.last.replace(now) {
+            Some(last) => ((now - last) as f32).clamp(0., Self::MAX_DELTA),
+            None => 0.,
+        };
+        if self.paused {
+            return 0.;
+        }
+        let dt = raw * self.time_scale;
+        self.elapsed += dt as f64;
+        self.accumulator += dt;
+        dt
+    }
+
+    /// Drains the time accumulated by `tick` into whole fixed steps.
+    ///
+    /// Returns how many updates of `fixed_step` seconds should be run.
+    pub fn fixed_steps(&mut self) -> usize {
+        let steps = (self.accumulator / self.fixed_step).floor();
+        self.accumulator -= steps * self.fixed_step;
+        steps as usize
+    }
+
+    pub fn fixed_step(&self) -> f32 {
+        self.fixed_step
+    }
+
+    /// Scaled time since the clock started, excluding time spent paused.
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// While paused, queues a single fixed step to be returned by the next `fixed_steps`.
+    pub fn step(&mut self) {
+        if self.paused {
+            self.elapsed += self.fixed_step as f64;
+            self.accumulator += self.fixed_step;
+        }
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.clamp(Self::MIN_TIME_SCALE, Self::MAX_TIME_SCALE);
+    }
+
+    pub fn reset_time_scale(&mut self) {
+        self.time_scale = 1.;
+    }
+
+    /// Applies the clock's keyboard controls: space pauses, `.` steps while paused and `+`/`-`
+    /// double or halve the time scale. `0` resets the time scale.
+    pub fn handle_event(&mut self, event: &iced_winit::Event) {
+        use iced_winit::keyboard::{Event, KeyCode};
+        if let iced_winit::Event::Keyboard(Event::KeyPressed { key_code, .. }) = event {
+            match key_code {
+                KeyCode::Space => self.toggle_pause(),
+                KeyCode::Period => self.step(),
+                KeyCode::Plus | KeyCode::Equals | KeyCode::NumpadAdd => {
+                    self.set_time_scale(self.time_scale * 2.)
+                }
+                KeyCode::Minus | KeyCode::NumpadSubtract => {
+                    self.set_time_scale(self.time_scale / 2.)
+                }
+                KeyCode::Key0 | KeyCode::Numpad0 => self.reset_time_scale(),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pausing_stops_time() {
+        let mut clock = Clock::new();
+        assert_eq!(clock.tick(1.), 0.);
+        assert!(clock.tick(1.1) > 0.);
+        clock.toggle_pause();
+        assert_eq!(clock.tick(1.2), 0.);
+        assert_eq!(clock.fixed_steps(), 6);
+        assert_eq!(clock.fixed_steps(), 0);
+        clock.toggle_pause();
+        assert!(clock.tick(1.3) > 0.);
+    }
+
+    #[test]
+    fn steps_only_while_paused() {
+        let mut clock = Clock::with_fixed_step(0.5);
+        clock.step();
+        assert_eq!(clock.fixed_steps(), 0);
+        clock.set_paused(true);
+        clock.step();
+        assert_eq!(clock.fixed_steps(), 1);
+        assert_eq!(clock.elapsed(), 0.5);
+    }
+
+    #[test]
+    fn time_scale_is_clamped_and_reset() {
+        let mut clock = Clock::new();
+        clock.set_time_scale(1000.);
+        assert_eq!(clock.time_scale(), Clock::MAX_TIME_SCALE);
+        clock.set_time_scale(0.);
+        assert_eq!(clock.time_scale(), Clock::MIN_TIME_SCALE);
+        clock.tick(0.);
+        assert_eq!(clock.tick(0.1), 0.1 * Clock::MIN_TIME_SCALE);
+        clock.reset_time_scale();
+        assert_eq!(clock.time_scale(), 1.);
+    }
+}
@@ -0,0 +1,255 @@
+//! The comparison screen: campaign members and the character in the creator, side by side.
+
+use crate::{
+    campaign::{Campaign, MemberChoice},
+    character::Character,
+    charactor_creator::StatRows,
+    comparison::{Compared, Comparison, Overlap},
+    rules,
+    theme::Theme,
+};
+use iced_winit::{
+    widget::{scrollable, Checkbox, Column, Row, Scrollable},
+    Element, Length,
+};
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+
+/// The width of the column naming each row, so that the characters' columns line up.
+const LABEL_WIDTH: u16 = 160;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    MemberToggled(MemberChoice, bool),
+    CreatorToggled(bool),
+}
+
+#[derive(Debug, Default)]
+pub struct Compare {
+    /// The campaign members to compare, in the order they were picked.
+    selected: Vec<MemberChoice>,
+    /// Whether the character in the creator is compared too.
+    include_creator: bool,
+
+    scroll: scrollable::State,
+}
+
+impl Compare {
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::MemberToggled(choice, true) => {
+                if !self.selected.contains(&choice) {
+                    self.selected.push(choice);
+                }
+            }
+            Message::MemberToggled(choice, false) => self.selected.retain(|c| *c != choice),
+            Message::CreatorToggled(include) => self.include_creator = include,
+        }
+    }
+
+    /// The picked characters, the one in the creator first if it's picked. Members whose
+    /// advances can't be applied are left out.
+    fn comparison(&self, campaign: &Campaign, creator: &Character) -> Comparison {
+        let creator = self.include_creator.then(|| Compared {
+            name: "In the creator".into(),
+            character: creator.clone(),
+        });
+        let members = self.selected.iter().filter_map(|choice| {
+            let member = campaign.member(choice)?;
+            match member.progression.current() {
+                Ok(character) => Some(Compared {
+                    name: choice.to_string(),
+                    character,
+                }),
+                Err(err) => {
+                    log::warn!("can't compare {}: {:#}", choice, err);
+                    None
+                }
+            }
+        });
+        Comparison::new(creator.into_iter().chain(members).collect())
+    }
+
+    pub fn view(
+        &mut self,
+        campaign: &Campaign,
+        creator: &Character,
+        theme: Theme,
+    ) -> Element<'_, Message, Renderer> {
+        let typography = theme.typography();
+        let palette = theme.palette();
+
+        let mut picker = Column::new()
+            .push(theme.heading("Characters"))
+            .push(
+                Checkbox::new(
+                    self.include_creator,
+                    "In the creator",
+                    Message::CreatorToggled,
+                )
+                .size(typography.body_size)
+                .text_size(typography.body_size),
+            )
+            .spacing(5)
+            .width(Length::Units(200));
+        for choice in campaign.choices() {
+            let checked = self.selected.contains(&choice);
+            let label = choice.to_string();
+            picker = picker.push(
+                Checkbox::new(checked, label, move |on| {
+                    Message::MemberToggled(choice.clone(), on)
+                })
+                .size(typography.body_size)
+                .text_size(typography.body_size),
+            );
+        }
+
+        let comparison = self.comparison(campaign, creator);
+        let mut table = Scrollable::new(&mut self.scroll)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .spacing(2);
+        if comparison.characters.len() < 2 {
+            table = table.push(theme.body("Pick two or more characters to compare."));
+            return Row::new()
+                .push(picker)
+                .push(table)
+                .spacing(10)
+                .padding(10)
+                .into();
+        }
+
+        table = table.push(row(
+            theme.body(""),
+            comparison
+                .characters
+                .iter()
+                .map(|compared| theme.heading(compared.name.as_str()).into())
+                .collect(),
+        ));
+
+        table = table.push(theme.heading("Stats"));
+        for stat in rules::STATS {
+            let values = comparison.stat_values(stat);
+            let differs = comparison.stat_differs(stat);
+            let (highest, lowest) = (
+                values.iter().flatten().max().copied(),
+                values.iter().flatten().min().copied(),
+            );
+            let cells = values
+                .iter()
+                .map(|value| match value {
+                    Some(value) => {
+                        let text = theme.body(value.to_string());
+                        if differs && Some(*value) == highest {
+                            text.color(palette.accent).into()
+                        } else if differs && Some(*value) == lowest {
+                            text.color(palette.danger).into()
+                        } else {
+                            text.into()
+                        }
+                    }
+                    None => theme.body("Not legal").into(),
+                })
+                .collect();
+            table = table.push(row(theme.body(format!("{:?}", stat)), cells));
+        }
+
+        let mut groups = StatRows::default();
+        for spirit in spirits_within::Spirit::LIST {
+            groups.row(spirit.stat()).push(spirit);
+        }
+        for (stat, spirits) in groups {
+            table = table.push(theme.heading(format!("{:?} spirits", stat)));
+            for spirit in spirits {
+                let differs = comparison.connections_differ(spirit);
+                let cells = comparison
+                    .connections(spirit)
+                    .into_iter()
+                    .map(|connection| {
+                        let text =
+                            theme.body(connection.map(|c| c.to_string()).unwrap_or_default());
+                        if differs {
+                            text.color(palette.accent).into()
+                        } else {
+                            text.into()
+                        }
+                    })
+                    .collect();
+                table = table.push(row(theme.body(format!("{:?}", spirit)), cells));
+            }
+        }
+
+        let overlaps = [
+            ("Prerogatives", names(comparison.prerogatives())),
+            ("Burdens", names(comparison.burdens())),
+        ];
+        for (heading, overlaps) in overlaps {
+            table = table.push(theme.heading(heading));
+            if overlaps.is_empty() {
+                table = table.push(theme.body("None"));
+            }
+            for overlap in overlaps {
+                // what every character has is picked out as their overlap
+                let label = if overlap.shared() {
+                    theme.body(overlap.value.as_str()).color(palette.accent)
+                } else {
+                    theme.body(overlap.value.as_str())
+                };
+                let cells = overlap
+                    .counts
+                    .iter()
+                    .map(|&count| {
+                        theme
+                            .body(match count {
+                                0 => String::new(),
+                                1 => "Yes".into(),
+                                count => format!("{} times", count),
+                            })
+                            .into()
+                    })
+                    .collect();
+                table = table.push(row(label, cells));
+            }
+        }
+
+        Row::new()
+            .push(picker)
+            .push(table)
+            .spacing(10)
+            .padding(10)
+            .into()
+    }
+}
+
+/// A row of `label`, then one cell per character.
+fn row<'a>(
+    label: impl Into<Element<'a, Message, Renderer>>,
+    cells: Vec<Element<'a, Message, Renderer>>,
+) -> Row<'a, Message, Renderer> {
+    Row::with_children(
+        std::iter::once(
+            Column::new()
+                .push(label)
+                .width(Length::Units(LABEL_WIDTH))
+                .into(),
+        )
+        .chain(
+            cells
+                .into_iter()
+                .map(|cell| Column::new().push(cell).width(Length::Fill).into()),
+        )
+        .collect(),
+    )
+    .spacing(2)
+}
+
+fn names<T: std::fmt::Display>(overlaps: Vec<Overlap<T>>) -> Vec<Overlap<String>> {
+    overlaps
+        .into_iter()
+        .map(|overlap| Overlap {
+            value: overlap.value.to_string(),
+            counts: overlap.counts,
+        })
+        .collect()
+}
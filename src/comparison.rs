@@ -0,0 +1,191 @@
+//! Characters side by side, for balancing a party or choosing between builds: how each connects
+//! to every spirit, where their stats differ and which prerogatives and burdens they share.
+
+use crate::{
+    character::Character,
+    content::{BurdenId, PrerogativeId},
+    rules,
+};
+use spirits_within::{Connection, Spirit, Stat};
+
+/// A character to compare, under the name it's shown with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Compared {
+    pub name: String,
+    pub character: Character,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comparison {
+    pub characters: Vec<Compared>,
+}
+
+/// Something any of the compared characters has, with how many times each has it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap<T> {
+    pub value: T,
+    pub counts: Vec<usize>,
+}
+
+impl<T> Overlap<T> {
+    /// Whether every character has it.
+    pub fn shared(&self) -> bool {
+        self.counts.iter().all(|&count| count > 0)
+    }
+}
+
+impl Comparison {
+    pub fn new(characters: Vec<Compared>) -> Self {
+        Self { characters }
+    }
+
+    /// Each character's connection to `spirit`.
+    pub fn connections(&self, spirit: Spirit) -> Vec<Option<Connection>> {
+        self.characters
+            .iter()
+            .map(|compared| {
+                compared
+                    .character
+                    .spirits
+                    .iter()
+                    .find(|(s, _)| *s == spirit)
+                    .map(|&(_, connection)| connection)
+            })
+            .collect()
+    }
+
+    /// Whether the characters don't all have the same connection to `spirit`.
+    pub fn connections_differ(&self, spirit: Spirit) -> bool {
+        differ(self.connections(spirit))
+    }
+
+    /// Each character's final value of `stat`, if the character is complete and legal.
+    pub fn stat_values(&self, stat: Stat) -> Vec<Option<u8>> {
+        self.characters
+            .iter()
+            .map(|compared| {
+                rules::stats(&compared.character)
+                    .ok()
+                    .map(|stats| rules::stat_value(&stats, stat))
+            })
+            .collect()
+    }
+
+    /// Whether the characters with legal stats don't all have the same value of `stat`.
+    pub fn stat_differs(&self, stat: Stat) -> bool {
+        differ(self.stat_values(stat).into_iter().flatten())
+    }
+
+    /// Every prerogative the characters have, base or for a burden, in the order they're first
+    /// found.
+    pub fn prerogatives(&self) -> Vec<Overlap<PrerogativeId>> {
+        overlap(self.characters.iter().map(|compared| {
+            let character = &compared.character;
+            character
+                .prerogatives
+                .iter()
+                .flatten()
+                .chain(character.burdens.iter().map(|(_, prerogative)| prerogative))
+                .cloned()
+                .collect()
+        }))
+    }
+
+    /// Every optional burden the characters have, in the order they're first found.
+    pub fn burdens(&self) -> Vec<Overlap<BurdenId>> {
+        overlap(self.characters.iter().map(|compared| {
+            compared
+                .character
+                .burdens
+                .iter()
+                .map(|(burden, _)| burden.clone())
+                .collect()
+        }))
+    }
+}
+
+fn differ<T: PartialEq>(values: impl IntoIterator<Item = T>) -> bool {
+    let mut values = values.into_iter();
+    match values.next() {
+        Some(first) => values.any(|value| value != first),
+        None => false,
+    }
+}
+
+fn overlap<T: Clone + PartialEq>(per_character: impl Iterator<Item = Vec<T>>) -> Vec<Overlap<T>> {
+    let per_character: Vec<Vec<T>> = per_character.collect();
+    let mut overlaps: Vec<Overlap<T>> = vec![];
+    for value in per_character.iter().flatten() {
+        if overlaps.iter().any(|overlap| overlap.value == *value) {
+            continue;
+        }
+        overlaps.push(Overlap {
+            value: value.clone(),
+            counts: per_character
+                .iter()
+                .map(|values| values.iter().filter(|v| *v == value).count())
+                .collect(),
+        });
+    }
+    overlaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archetype::Archetype;
+
+    fn archetype(index: usize) -> Compared {
+        let archetype = &Archetype::built_in()[index];
+        Compared {
+            name: archetype.name.clone(),
+            character: archetype.build().unwrap().character(),
+        }
+    }
+
+    #[test]
+    fn a_character_matches_itself() {
+        let comparison = Comparison::new(vec![archetype(0), archetype(0)]);
+        for spirit in Spirit::LIST {
+            assert!(!comparison.connections_differ(spirit));
+        }
+        for stat in rules::STATS {
+            assert!(!comparison.stat_differs(stat));
+            assert!(comparison.stat_values(stat).iter().all(Option::is_some));
+        }
+        let prerogatives = comparison.prerogatives();
+        assert!(!prerogatives.is_empty());
+        assert!(prerogatives.iter().all(Overlap::shared));
+        let total: usize = prerogatives.iter().map(|overlap| overlap.counts[0]).sum();
+        assert_eq!(total, rules::BASE_PREROGATIVE_COUNT);
+    }
+
+    #[test]
+    fn differences_are_found() {
+        let mut other = archetype(0);
+        let mastery = other
+            .character
+            .spirits
+            .iter_mut()
+            .find(|(_, connection)| *connection == Connection::Mastery)
+            .unwrap();
+        mastery.1 = Connection::Ineptitude;
+        let spirit = mastery.0;
+        let burden = (
+            BurdenId::from(spirits_within::Burden::LIST[0]),
+            other.character.prerogatives[0].clone().unwrap(),
+        );
+        other.character.burdens.push(burden);
+
+        let comparison = Comparison::new(vec![archetype(0), other]);
+        assert!(comparison.connections_differ(spirit));
+        // the changed spirits make the second illegal, so only the first has stats
+        assert!(comparison.stat_values(Stat::Knowledge)[1].is_none());
+        assert!(!comparison.stat_differs(Stat::Knowledge));
+
+        let burdens = comparison.burdens();
+        assert_eq!(burdens.len(), 1);
+        assert_eq!(burdens[0].counts, vec![0, 1]);
+        assert!(!burdens[0].shared());
+    }
+}
@@ -0,0 +1,286 @@
+//! Descriptions of everything in the rules, for the rules reference and the character creator's
+//! tooltips.
+//!
+//! The text comes from `resources/compendium.toml`, with any entries in a `compendium.toml` saved
+//! with the other user data taking precedence, so it can be changed without rebuilding.
+
+use crate::{
+    content::{self, BurdenId, PrerogativeDef, PrerogativeId},
+    storage,
+};
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use spirits_within::{Connection, Spirit, Stat};
+use std::collections::BTreeMap;
+
+/// The storage key of the user's own descriptions.
+pub const KEY: &str = "compendium.toml";
+
+const BUILT_IN: &str = include_str!("../resources/compendium.toml");
+
+/// Descriptions keyed by the rules' names for things, which are matched ignoring case.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Compendium {
+    pub spirits: BTreeMap<String, String>,
+    pub connections: BTreeMap<String, String>,
+    pub prerogatives: BTreeMap<String, String>,
+    pub burdens: BTreeMap<String, String>,
+}
+
+/// The parts of the rules the compendium describes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Section {
+    Spirits,
+    Connections,
+    Prerogatives,
+    Burdens,
+}
+
+impl Section {
+    pub const ALL: [Section; 4] = [
+        Section::Spirits,
+        Section::Connections,
+        Section::Prerogatives,
+        Section::Burdens,
+    ];
+}
+
+impl std::fmt::Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Section::Spirits => "Spirits",
+            Section::Connections => "Connections",
+            Section::Prerogatives => "Prerogatives",
+            Section::Burdens => "Burdens",
+        })
+    }
+}
+
+/// One thing in the rules and what the compendium says about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub section: Section,
+    pub name: String,
+    /// The stat a spirit belongs to or a prerogative raises.
+    pub stat: Option<Stat>,
+    pub text: String,
+}
+
+impl Compendium {
+    /// The built-in descriptions with the user's on top.
+    pub fn load() -> Self {
+        let mut compendium = Self::built_in();
+        if let Some(contents) = storage::load(KEY) {
+            match Self::from_toml(&contents) {
+                Ok(overrides) => compendium.extend(overrides),
+                Err(err) => log::warn!("ignoring {}: {:?}", KEY, err),
+            }
+        }
+        compendium
+    }
+
+    pub fn built_in() -> Self {
+        Self::from_toml(BUILT_IN).expect("the built-in compendium parses")
+    }
+
+    pub fn from_toml(contents: &str) -> eyre::Result<Self> {
+        toml::from_str(contents).wrap_err("parsing compendium")
+    }
+
+    /// Adds `other`'s descriptions, replacing any of the same things.
+    pub fn extend(&mut self, other: Compendium) {
+        let sections = [
+            (&mut self.spirits, other.spirits),
+            (&mut self.connections, other.connections),
+            (&mut self.prerogatives, other.prerogatives),
+            (&mut self.burdens, other.burdens),
+        ];
+        for (descriptions, overrides) in sections {
+            for (name, text) in overrides {
+                descriptions.retain(|existing, _| !existing.eq_ignore_ascii_case(&name));
+                descriptions.insert(name, text);
+            }
+        }
+    }
+
+    pub fn spirit(&self, spirit: Spirit) -> String {
+        describe(&self.spirits, spirit).unwrap_or_else(|| {
+            format!(
+                "A spirit of {:?}; its connection adds to that stat.",
+                spirit.stat()
+            )
+        })
+    }
+
+    pub fn connection(&self, connection: Connection) -> String {
+        describe(&self.connections, connection).unwrap_or_else(|| {
+            format!(
+                "How strongly a character is bound to a spirit: {}.",
+                connection
+            )
+        })
+    }
+
+    /// Falls back to the content pack's description, then to what it does to stats.
+    pub fn prerogative(&self, prerogative: &PrerogativeId) -> String {
+        describe(&self.prerogatives, prerogative).unwrap_or_else(|| {
+            match content::active().prerogative(prerogative.name()) {
+                Some(def) => prerogative_text(def),
+                None => "Changes no stat.".into(),
+            }
+        })
+    }
+
+    /// Falls back to the content pack's description.
+    pub fn burden(&self, burden: &BurdenId) -> String {
+        describe(&self.burdens, burden).unwrap_or_else(|| {
+            content::active()
+                .burden(burden.name())
+                .and_then(|def| nonempty(&def.description))
+                .unwrap_or_else(|| {
+                    format!(
+                        "{} is an optional burden, taken on in exchange for an extra \
+                         prerogative.",
+                        burden
+                    )
+                })
+        })
+    }
+
+    /// Everything in the rules, section by section, with the homebrew from the active content
+    /// after the core prerogatives and burdens.
+    pub fn entries(&self) -> Vec<Entry> {
+        let content = content::active();
+        let spirits = Spirit::LIST.into_iter().map(|spirit| Entry {
+            section: Section::Spirits,
+            name: format!("{:?}", spirit),
+            stat: Some(spirit.stat()),
+            text: self.spirit(spirit),
+        });
+        let connections = crate::rules::CONNECTION_COUNTS
+            .into_iter()
+            .map(|(connection, _)| Entry {
+                section: Section::Connections,
+                name: connection.to_string(),
+                stat: None,
+                text: self.connection(connection),
+            });
+        let prerogatives = content
+            .prerogative_ids()
+            .into_iter()
+            .map(|prerogative| Entry {
+                section: Section::Prerogatives,
+                name: prerogative.to_string(),
+                stat: crate::rules::prerogative_stat(&prerogative),
+                text: self.prerogative(&prerogative),
+            });
+        let burdens = content.burden_ids().into_iter().map(|burden| Entry {
+            section: Section::Burdens,
+            name: burden.to_string(),
+            stat: None,
+            text: self.burden(&burden),
+        });
+        spirits
+            .chain(connections)
+            .chain(prerogatives)
+            .chain(burdens)
+            .collect()
+    }
+
+    /// The entries whose name, stat or text contain `query`, ignoring case.
+    pub fn search(&self, query: &str) -> Vec<Entry> {
+        let query = query.trim().to_lowercase();
+        self.entries()
+            .into_iter()
+            .filter(|entry| {
+                let stat = entry.stat.map(|stat| format!("{:?}", stat));
+                format!("{} {} {}", entry.name, stat.unwrap_or_default(), entry.text)
+                    .to_lowercase()
+                    .contains(&query)
+            })
+            .collect()
+    }
+}
+
+/// The description of `value` in `descriptions`, by its name.
+fn describe(
+    descriptions: &BTreeMap<String, String>,
+    value: impl std::fmt::Debug,
+) -> Option<String> {
+    let name = format!("{:?}", value);
+    descriptions
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(&name))
+        .map(|(_, text)| text.clone())
+}
+
+fn nonempty(text: &str) -> Option<String> {
+    Some(text.trim())
+        .filter(|text| !text.is_empty())
+        .map(Into::into)
+}
+
+/// A content pack's description of a prerogative, or what it does to stats, then any
+/// restrictions on taking it.
+fn prerogative_text(def: &PrerogativeDef) -> String {
+    let text = nonempty(&def.description).unwrap_or_else(|| {
+        if def.effects.is_empty() {
+            "Changes no stat.".into()
+        } else {
+            let effects: Vec<String> = def.effects.iter().map(ToString::to_string).collect();
+            format!("Gives {}.", effects.join(", "))
+        }
+    });
+    if def.restrictions.is_empty() {
+        return text;
+    }
+    let restrictions: Vec<String> = def.restrictions.iter().map(ToString::to_string).collect();
+    format!("{} Can be taken {}.", text, restrictions.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_built_in_text_describes_every_spirit_and_connection() {
+        let compendium = Compendium::built_in();
+        for spirit in Spirit::LIST {
+            assert!(
+                describe(&compendium.spirits, spirit).is_some(),
+                "{:?}",
+                spirit
+            );
+        }
+        for (connection, _) in crate::rules::CONNECTION_COUNTS {
+            assert!(
+                describe(&compendium.connections, connection).is_some(),
+                "{:?}",
+                connection
+            );
+        }
+    }
+
+    #[test]
+    fn user_text_replaces_the_built_in_text() {
+        let mut compendium = Compendium::built_in();
+        compendium
+            .extend(Compendium::from_toml("[spirits]\nmuscle = \"Heavy lifting.\"\n").unwrap());
+        assert_eq!(compendium.spirit(Spirit::Muscle), "Heavy lifting.");
+        assert_eq!(compendium.spirits.len(), Spirit::LIST.len());
+    }
+
+    #[test]
+    fn search_matches_names_stats_and_text() {
+        let compendium = Compendium::built_in();
+        let found = compendium.search("SHADOWS");
+        assert_eq!(found[0].name, "Shadows");
+        assert!(compendium
+            .search("knowledge")
+            .iter()
+            .any(|entry| entry.section == Section::Spirits));
+        assert!(!compendium.search("second nature").is_empty());
+        assert_eq!(compendium.search("").len(), compendium.entries().len());
+    }
+}
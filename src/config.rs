@@ -0,0 +1,181 @@
+use crate::theme::Theme;
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+
+/// The storage key the configuration is saved under: a TOML file natively, a `localStorage` entry
+/// on the web.
+const STORAGE_KEY: &str = "config.toml";
+
+/// User configuration, loaded at startup and saved whenever it changes.
+///
+/// Missing fields take their default values so that old files keep loading as options are added.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Samples per pixel, or 0 to disable multisampling.
+    pub msaa: u16,
+    pub vsync: bool,
+    pub theme: Theme,
+    /// Multiplies the platform's scale factor for the UI.
+    pub ui_scale: f32,
+    /// Whether the 3D scene animates.
+    pub animation: bool,
+    /// The saved campaign to open at startup, by name.
+    pub campaign: String,
+    // tables come after plain values in TOML
+    pub window: WindowConfig,
+    pub seed: SeedBehavior,
+    /// The file this was loaded from with `load_from`, which `save` writes back to.
+    #[serde(skip)]
+    path: Option<std::path::PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            msaa: 8,
+            vsync: true,
+            theme: Default::default(),
+            ui_scale: 1.,
+            animation: true,
+            campaign: crate::campaign::DEFAULT_NAME.into(),
+            window: Default::default(),
+            seed: Default::default(),
+            path: None,
+        }
+    }
+}
+
+impl Config {
+    pub const MSAA_OPTIONS: [u16; 5] = [0, 2, 4, 8, 16];
+    pub const MIN_UI_SCALE: f32 = 0.5;
+    pub const MAX_UI_SCALE: f32 = 2.;
+
+    /// Loads the saved configuration, falling back to the defaults if there is none or it can't
+    /// be read.
+    pub fn load() -> Self {
+        match crate::storage::load(STORAGE_KEY) {
+            Some(contents) => Self::from_toml(&contents).unwrap_or_else(|err| {
+                log::warn!("ignoring invalid configuration: {:?}", err);
+                Self::default()
+            }),
+            None => Self::default(),
+        }
+    }
+
+    /// Loads the configuration from a TOML file at `path`, which later saves go to as well.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_from(path: &std::path::Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("reading {}", path.display()))?;
+        let config =
+            Self::from_toml(&contents).wrap_err_with(|| format!("loading {}", path.display()))?;
+        Ok(Self {
+            path: Some(path.to_owned()),
+            ..config
+        })
+    }
+
+    pub fn save(&self) {
+        let result = self.to_toml().and_then(|contents| match &self.path {
+            Some(path) => std::fs::write(path, contents)
+                .wrap_err_with(|| format!("writing {}", path.display())),
+            None => crate::storage::save(STORAGE_KEY, &contents),
+        });
+        if let Err(err) = result {
+            log::warn!("failed to save configuration: {:?}", err);
+        }
+    }
+
+    pub fn from_toml(contents: &str) -> eyre::Result<Self> {
+        let config: Self = toml::from_str(contents).wrap_err("parsing configuration")?;
+        Ok(config.sanitized())
+    }
+
+    pub fn to_toml(&self) -> eyre::Result<String> {
+        toml::to_string_pretty(self).wrap_err("serializing configuration")
+    }
+
+    /// Clamps values that may have been edited by hand into their valid ranges.
+    fn sanitized(mut self) -> Self {
+        self.ui_scale = self.ui_scale.clamp(Self::MIN_UI_SCALE, Self::MAX_UI_SCALE);
+        if !Self::MSAA_OPTIONS.contains(&self.msaa) {
+            self.msaa = Self::default().msaa;
+        }
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            fullscreen: false,
+        }
+    }
+}
+
+impl WindowConfig {
+    pub const SIZES: [(u32, u32); 5] = [
+        (1080, 720),
+        (1280, 720),
+        (1600, 900),
+        (1920, 1080),
+        (2560, 1440),
+    ];
+}
+
+/// How the random number generator is seeded at startup.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "behavior", rename_all = "snake_case")]
+pub enum SeedBehavior {
+    /// A new seed every run.
+    Random,
+    /// The same seed every run, for reproducible characters.
+    Fixed { seed: u64 },
+}
+
+impl Default for SeedBehavior {
+    fn default() -> Self {
+        Self::Random
+    }
+}
+
+impl SeedBehavior {
+    /// The seed to start with, calling `random` for a fresh one if needed.
+    pub fn seed(self, random: impl FnOnce() -> u64) -> u64 {
+        match self {
+            SeedBehavior::Random => random(),
+            SeedBehavior::Fixed { seed } => seed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut config = Config::default();
+        assert_eq!(
+            Config::from_toml(&config.to_toml().unwrap()).unwrap(),
+            config
+        );
+        config.seed = SeedBehavior::Fixed { seed: 42 };
+        config.window.fullscreen = true;
+        assert_eq!(
+            Config::from_toml(&config.to_toml().unwrap()).unwrap(),
+            config
+        );
+    }
+}
@@ -0,0 +1,805 @@
+//! Rules content that isn't compiled in: what prerogatives do to stats, and homebrew prerogatives
+//! and burdens, loaded from data packs.
+//!
+//! A pack is a TOML or JSON file. The core pack in `resources/content/core.toml` gives the core
+//! prerogatives their effects, the saved packs a campaign enables are loaded on top of it in
+//! order, and an entry named like an earlier one replaces it.
+
+use crate::{character::Named, rules, storage};
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use spirits_within::{BaseStats, Burden, Prerogative, Stat};
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// Where packs are saved with the other user data.
+pub const PACKS_FOLDER: &str = "packs";
+
+const CORE: &str = include_str!("../resources/content/core.toml");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContentPack {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prerogatives: Vec<PrerogativeDef>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub burdens: Vec<BurdenDef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrerogativeDef {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<Effect>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub restrictions: Vec<Restriction>,
+}
+
+/// A change to a stat.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Effect {
+    #[serde(with = "crate::character::stat_name")]
+    pub stat: Stat,
+    pub amount: i8,
+}
+
+impl std::fmt::Display for Effect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:+} {:?}", self.amount, self.stat)
+    }
+}
+
+/// A limit on who can take a prerogative, beyond the stats' maximums.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Restriction {
+    /// Can be taken only once.
+    Unique,
+    /// Can only be taken in exchange for an optional burden.
+    BurdenOnly,
+    /// Needs the base value of `stat`, before any prerogatives, to be at least `value`.
+    MinimumStat {
+        #[serde(with = "crate::character::stat_name")]
+        stat: Stat,
+        value: u8,
+    },
+}
+
+impl Restriction {
+    /// Whether `other` is the same kind of restriction, whatever its details.
+    pub fn same_kind(self, other: Restriction) -> bool {
+        std::mem::discriminant(&self) == std::mem::discriminant(&other)
+    }
+}
+
+impl std::fmt::Display for Restriction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Restriction::Unique => f.write_str("only once"),
+            Restriction::BurdenOnly => f.write_str("only for a burden"),
+            Restriction::MinimumStat { stat, value } => {
+                write!(f, "with base {:?} of {} or more", stat, value)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BurdenDef {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+impl ContentPack {
+    pub fn from_toml(contents: &str) -> eyre::Result<Self> {
+        let pack: Self = toml::from_str(contents).wrap_err("parsing content pack")?;
+        pack.validate()?;
+        Ok(pack)
+    }
+
+    pub fn from_json(contents: &str) -> eyre::Result<Self> {
+        let pack: Self = serde_json::from_str(contents).wrap_err("parsing content pack")?;
+        pack.validate()?;
+        Ok(pack)
+    }
+
+    /// Parses the contents of the pack file `file_name`: JSON if it ends in `.json` and TOML
+    /// otherwise.
+    pub fn parse(file_name: &str, contents: &str) -> eyre::Result<Self> {
+        if is_json(file_name) {
+            Self::from_json(contents)
+        } else {
+            Self::from_toml(contents)
+        }
+    }
+
+    /// The contents of the pack file `file_name`, in the format `parse` expects of it.
+    pub fn serialize(&self, file_name: &str) -> eyre::Result<String> {
+        if is_json(file_name) {
+            serde_json::to_string_pretty(self).wrap_err("serializing content pack")
+        } else {
+            toml::to_string_pretty(self).wrap_err("serializing content pack")
+        }
+    }
+
+    /// Validates the pack and saves it under `key`, or under a new key from its name if there is
+    /// none yet, returning the key.
+    pub fn save(&self, key: Option<&str>) -> eyre::Result<String> {
+        self.validate()?;
+        let key = key.map_or_else(|| self.new_key(&storage::keys(PACKS_FOLDER)), Into::into);
+        storage::save(&key, &self.serialize(&key)?)
+            .wrap_err_with(|| format!("saving content pack {:?}", self.name))?;
+        Ok(key)
+    }
+
+    /// A key for the pack from its name that isn't one of the `taken` keys. Names that differ
+    /// only in punctuation or case make the same file name, so later ones are numbered.
+    pub fn new_key(&self, taken: &[String]) -> String {
+        let stem = storage::file_stem(&self.name);
+        (1..)
+            .map(|number| match number {
+                1 => format!("{}/{}.toml", PACKS_FOLDER, stem),
+                number => format!("{}/{}_{}.toml", PACKS_FOLDER, stem, number),
+            })
+            .find(|key| !taken.iter().any(|taken| taken.eq_ignore_ascii_case(key)))
+            .expect("some number is free")
+    }
+
+    /// Checks what the format alone can't: that everything is named once and every effect fits
+    /// the stat it changes.
+    pub fn validate(&self) -> eyre::Result<()> {
+        if self.name.trim().is_empty() {
+            eyre::bail!("a content pack needs a name");
+        }
+        check_names("prerogative", self.prerogatives.iter().map(|p| &p.name))
+            .and_then(|()| check_names("burden", self.burdens.iter().map(|b| &b.name)))
+            .wrap_err_with(|| format!("in content pack {:?}", self.name))?;
+        for prerogative in &self.prerogatives {
+            check_effects(&prerogative.effects)
+                .and_then(|()| check_restrictions(&prerogative.restrictions))
+                .wrap_err_with(|| {
+                    format!(
+                        "in prerogative {:?} of content pack {:?}",
+                        prerogative.name, self.name
+                    )
+                })?;
+        }
+        Ok(())
+    }
+}
+
+fn check_names<'a>(kind: &str, names: impl Iterator<Item = &'a String>) -> eyre::Result<()> {
+    let mut seen: Vec<&str> = vec![];
+    for (index, name) in names.enumerate() {
+        let name = name.trim();
+        if name.is_empty() {
+            eyre::bail!("{} {} has no name", kind, index + 1);
+        }
+        if seen.iter().any(|seen| seen.eq_ignore_ascii_case(name)) {
+            eyre::bail!("{} {:?} is defined more than once", kind, name);
+        }
+        seen.push(name);
+    }
+    Ok(())
+}
+
+fn is_json(file_name: &str) -> bool {
+    file_name.to_ascii_lowercase().ends_with(".json")
+}
+
+fn check_effects(effects: &[Effect]) -> eyre::Result<()> {
+    for (index, effect) in effects.iter().enumerate() {
+        if effect.amount == 0 {
+            eyre::bail!("the effect on {:?} changes it by 0", effect.stat);
+        }
+        let max = rules::max_stat(effect.stat);
+        if effect.amount.unsigned_abs() > max {
+            eyre::bail!(
+                "{} is more than {:?}'s maximum of {}",
+                effect,
+                effect.stat,
+                max
+            );
+        }
+        if effects[..index].iter().any(|e| e.stat == effect.stat) {
+            eyre::bail!("there is more than one effect on {:?}", effect.stat);
+        }
+    }
+    Ok(())
+}
+
+fn check_restrictions(restrictions: &[Restriction]) -> eyre::Result<()> {
+    for (index, restriction) in restrictions.iter().enumerate() {
+        if let Restriction::MinimumStat { stat, value } = *restriction {
+            if value > rules::max_stat(stat) {
+                eyre::bail!(
+                    "it needs {:?} of {}, past the maximum of {}",
+                    stat,
+                    value,
+                    rules::max_stat(stat)
+                );
+            }
+        }
+        if restrictions[..index]
+            .iter()
+            .any(|r| r.same_kind(*restriction))
+        {
+            eyre::bail!("there is more than one {:?} restriction", restriction);
+        }
+    }
+    Ok(())
+}
+
+/// The prerogatives and burdens in play: the core pack's with the enabled packs' on top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Content {
+    /// The names of the packs, the core pack first.
+    pub packs: Vec<String>,
+    pub prerogatives: Vec<PrerogativeDef>,
+    pub burdens: Vec<BurdenDef>,
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Self::from_packs(vec![])
+    }
+}
+
+impl Content {
+    /// The core pack with `packs` on top, in order.
+    pub fn from_packs(packs: impl IntoIterator<Item = ContentPack>) -> Self {
+        let core = ContentPack::from_toml(CORE).expect("the core content pack is valid");
+        let mut content = Self {
+            packs: vec![],
+            prerogatives: vec![],
+            burdens: vec![],
+        };
+        for pack in std::iter::once(core).chain(packs) {
+            content.packs.push(pack.name);
+            for prerogative in pack.prerogatives {
+                replace_or_push(&mut content.prerogatives, prerogative, |p| &p.name);
+            }
+            for burden in pack.burdens {
+                replace_or_push(&mut content.burdens, burden, |b| &b.name);
+            }
+        }
+        content
+    }
+
+    pub fn prerogative(&self, name: &str) -> Option<&PrerogativeDef> {
+        self.prerogatives
+            .iter()
+            .find(|p| p.name.trim().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn burden(&self, name: &str) -> Option<&BurdenDef> {
+        self.burdens
+            .iter()
+            .find(|b| b.name.trim().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// What a prerogative does to stats.
+    pub fn effects(&self, prerogative: &PrerogativeId) -> &[Effect] {
+        self.prerogative(prerogative.name())
+            .map_or(&[], |p| &p.effects)
+    }
+
+    pub fn restrictions(&self, prerogative: &PrerogativeId) -> &[Restriction] {
+        self.prerogative(prerogative.name())
+            .map_or(&[], |p| &p.restrictions)
+    }
+
+    /// Whether the restrictions of `prerogative` let it be taken again; see
+    /// [`rules::restrictions_allow`].
+    pub fn check_restrictions(
+        &self,
+        prerogative: &PrerogativeId,
+        base: Option<BaseStats>,
+        taken: usize,
+        for_burden: bool,
+    ) -> eyre::Result<()> {
+        for &restriction in self.restrictions(prerogative) {
+            let allowed = match restriction {
+                Restriction::Unique => taken == 0,
+                Restriction::BurdenOnly => for_burden,
+                Restriction::MinimumStat { stat, value } => match base {
+                    Some(mut base) => *rules::base_stat(&mut base, stat) >= value,
+                    None => true,
+                },
+            };
+            if !allowed {
+                eyre::bail!("{} can be taken {}", prerogative, restriction);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `prerogative` is a core one that does what the core pack says, which the rules
+    /// library already knows, rather than one a pack added or changed.
+    pub fn is_core(&self, prerogative: &PrerogativeId) -> bool {
+        static CORE_CONTENT: OnceLock<Content> = OnceLock::new();
+        matches!(prerogative, PrerogativeId::Core(_))
+            && self.effects(prerogative)
+                == CORE_CONTENT
+                    .get_or_init(Default::default)
+                    .effects(prerogative)
+    }
+
+    /// Whether a prerogative is core or defined by one of the packs.
+    pub fn has_prerogative(&self, prerogative: &PrerogativeId) -> bool {
+        match prerogative {
+            PrerogativeId::Core(_) => true,
+            PrerogativeId::Homebrew(name) => self.prerogative(name).is_some(),
+        }
+    }
+
+    /// Whether a burden is core or defined by one of the packs.
+    pub fn has_burden(&self, burden: &BurdenId) -> bool {
+        match burden {
+            BurdenId::Core(_) => true,
+            BurdenId::Homebrew(name) => self.burden(name).is_some(),
+        }
+    }
+
+    /// Every prerogative that can be taken: the core ones, then the packs' own.
+    pub fn prerogative_ids(&self) -> Vec<PrerogativeId> {
+        let homebrew = self
+            .prerogatives
+            .iter()
+            .map(|def| PrerogativeId::named(&def.name))
+            .filter(|id| matches!(id, PrerogativeId::Homebrew(_)));
+        Prerogative::LIST
+            .into_iter()
+            .map(PrerogativeId::Core)
+            .chain(homebrew)
+            .collect()
+    }
+
+    /// Every burden that can be taken on: the core ones, then the packs' own.
+    pub fn burden_ids(&self) -> Vec<BurdenId> {
+        let homebrew = self
+            .burdens
+            .iter()
+            .map(|def| BurdenId::named(&def.name))
+            .filter(|id| matches!(id, BurdenId::Homebrew(_)));
+        Burden::LIST
+            .into_iter()
+            .map(BurdenId::Core)
+            .chain(homebrew)
+            .collect()
+    }
+}
+
+fn replace_or_push<T>(list: &mut Vec<T>, item: T, name: impl Fn(&T) -> &String) {
+    match list.iter_mut().find(|existing| {
+        name(existing)
+            .trim()
+            .eq_ignore_ascii_case(name(&item).trim())
+    }) {
+        Some(existing) => *existing = item,
+        None => list.push(item),
+    }
+}
+
+/// A prerogative of the core rules or a homebrew one, known by name.
+///
+/// Homebrew names are kept and shown as written and compare ignoring case. A homebrew prerogative
+/// may be one no enabled pack defines, which the rules treat as an error rather than failing to
+/// load the character.
+#[derive(Clone)]
+pub enum PrerogativeId {
+    Core(Prerogative),
+    Homebrew(Arc<str>),
+}
+
+/// A burden of the core rules or a homebrew one, known by name. See [`PrerogativeId`].
+#[derive(Clone)]
+pub enum BurdenId {
+    Core(Burden),
+    Homebrew(Arc<str>),
+}
+
+impl PrerogativeId {
+    /// The core prerogative called `name`, ignoring case, or else the homebrew one.
+    pub fn named(name: &str) -> Self {
+        let name = name.trim();
+        Prerogative::LIST
+            .into_iter()
+            .find(|&p| Self::Core(p).name().eq_ignore_ascii_case(name))
+            .map_or_else(|| Self::Homebrew(name.into()), Self::Core)
+    }
+
+    /// The name, as written where it was read for a homebrew prerogative.
+    pub fn name(&self) -> &str {
+        static CORE_NAMES: OnceLock<Vec<String>> = OnceLock::new();
+        match self {
+            PrerogativeId::Core(prerogative) => {
+                core_name(&CORE_NAMES, &Prerogative::LIST, *prerogative)
+            }
+            PrerogativeId::Homebrew(name) => name,
+        }
+    }
+}
+
+impl BurdenId {
+    /// The core burden called `name`, ignoring case, or else the homebrew one.
+    pub fn named(name: &str) -> Self {
+        let name = name.trim();
+        Burden::LIST
+            .into_iter()
+            .find(|&b| Self::Core(b).name().eq_ignore_ascii_case(name))
+            .map_or_else(|| Self::Homebrew(name.into()), Self::Core)
+    }
+
+    /// The name, as written where it was read for a homebrew burden.
+    pub fn name(&self) -> &str {
+        static CORE_NAMES: OnceLock<Vec<String>> = OnceLock::new();
+        match self {
+            BurdenId::Core(burden) => core_name(&CORE_NAMES, &Burden::LIST, *burden),
+            BurdenId::Homebrew(name) => name,
+        }
+    }
+}
+
+impl PartialEq for PrerogativeId {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PrerogativeId::Core(a), PrerogativeId::Core(b)) => a == b,
+            (PrerogativeId::Homebrew(a), PrerogativeId::Homebrew(b)) => a.eq_ignore_ascii_case(b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for PrerogativeId {}
+
+impl PartialEq for BurdenId {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BurdenId::Core(a), BurdenId::Core(b)) => a == b,
+            (BurdenId::Homebrew(a), BurdenId::Homebrew(b)) => a.eq_ignore_ascii_case(b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for BurdenId {}
+
+impl From<Prerogative> for PrerogativeId {
+    fn from(prerogative: Prerogative) -> Self {
+        Self::Core(prerogative)
+    }
+}
+
+impl From<Burden> for BurdenId {
+    fn from(burden: Burden) -> Self {
+        Self::Core(burden)
+    }
+}
+
+// Debug is the name files store, as for the rules enums.
+impl std::fmt::Debug for PrerogativeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrerogativeId::Core(prerogative) => prerogative.fmt(f),
+            PrerogativeId::Homebrew(name) => f.write_str(name),
+        }
+    }
+}
+
+impl std::fmt::Debug for BurdenId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BurdenId::Core(burden) => burden.fmt(f),
+            BurdenId::Homebrew(name) => f.write_str(name),
+        }
+    }
+}
+
+impl std::fmt::Display for PrerogativeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrerogativeId::Core(prerogative) => prerogative.fmt(f),
+            PrerogativeId::Homebrew(name) => f.write_str(name),
+        }
+    }
+}
+
+impl std::fmt::Display for BurdenId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BurdenId::Core(burden) => burden.fmt(f),
+            BurdenId::Homebrew(name) => f.write_str(name),
+        }
+    }
+}
+
+impl Named for PrerogativeId {
+    const KIND: &'static str = "prerogative";
+
+    fn from_name(name: &str) -> eyre::Result<Self> {
+        nonempty_name(Self::KIND, name).map(PrerogativeId::named)
+    }
+}
+
+impl Named for BurdenId {
+    const KIND: &'static str = "burden";
+
+    fn from_name(name: &str) -> eyre::Result<Self> {
+        nonempty_name(Self::KIND, name).map(BurdenId::named)
+    }
+}
+
+fn nonempty_name<'a>(kind: &str, name: &'a str) -> eyre::Result<&'a str> {
+    if name.trim().is_empty() {
+        eyre::bail!("a {} needs a name", kind);
+    }
+    Ok(name)
+}
+
+/// The name of `value` from the core rules' `list`, formatted into `names` the first time one is
+/// asked for.
+fn core_name<T: Copy + PartialEq + std::fmt::Debug>(
+    names: &'static OnceLock<Vec<String>>,
+    list: &[T],
+    value: T,
+) -> &'static str {
+    let names = names.get_or_init(|| list.iter().map(|v| format!("{:?}", v)).collect());
+    let index = list
+        .iter()
+        .position(|&v| v == value)
+        .expect("the rules' lists have every variant");
+    &names[index]
+}
+
+/// The content the rules use, until `set_active` is first called the core pack alone.
+static ACTIVE: RwLock<Option<Arc<Content>>> = RwLock::new(None);
+
+pub fn active() -> Arc<Content> {
+    if let Some(content) = &*ACTIVE
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+    {
+        return content.clone();
+    }
+    ACTIVE
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_or_insert_with(Default::default)
+        .clone()
+}
+
+pub fn set_active(content: Content) {
+    *ACTIVE
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::new(content));
+}
+
+/// Every pack saved with the user data by its key, and why any others couldn't be loaded.
+pub fn load_saved() -> (Vec<(String, ContentPack)>, Vec<String>) {
+    let mut packs = vec![];
+    let mut errors = vec![];
+    for key in storage::keys(PACKS_FOLDER) {
+        let pack = storage::load(&key)
+            .ok_or_else(|| eyre::eyre!("couldn't read it"))
+            .and_then(|contents| ContentPack::parse(&key, &contents));
+        match pack {
+            Ok(pack) => packs.push((key, pack)),
+            Err(err) => {
+                log::warn!("skipping content pack {}: {:?}", key, err);
+                errors.push(format!("{}: {:#}", key, err));
+            }
+        }
+    }
+    (packs, errors)
+}
+
+/// The content with the saved packs named in `enabled`, and why any of them couldn't be loaded.
+pub fn load_enabled(enabled: &[String]) -> (Content, Vec<String>) {
+    let (saved, mut errors) = load_saved();
+    let mut packs = vec![];
+    for name in enabled {
+        match saved
+            .iter()
+            .find(|(_, pack)| pack.name.trim().eq_ignore_ascii_case(name.trim()))
+        {
+            Some((_, pack)) => packs.push(pack.clone()),
+            None => errors.push(format!("{:?} is enabled but not saved", name)),
+        }
+    }
+    (Content::from_packs(packs), errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(contents: &str) -> String {
+        format!("{:#}", ContentPack::from_toml(contents).unwrap_err())
+    }
+
+    #[test]
+    fn the_core_pack_gives_the_core_prerogatives_their_effects() {
+        let content = Content::default();
+        assert_eq!(
+            content.effects(&Prerogative::Education.into()),
+            [Effect {
+                stat: Stat::Knowledge,
+                amount: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn later_packs_replace_entries_by_name() {
+        let pack = ContentPack::from_toml(
+            r#"
+            name = "Rebalanced"
+
+            [[prerogatives]]
+            name = "education"
+            effects = [{ stat = "Knowledge", amount = 2 }, { stat = "discipline", amount = -1 }]
+
+            [[prerogatives]]
+            name = "Old Money"
+            description = "Never wanted for anything."
+
+            [[burdens]]
+            name = "Feud"
+            "#,
+        )
+        .unwrap();
+        let content = Content::from_packs(vec![pack]);
+        assert_eq!(content.packs, ["Core", "Rebalanced"]);
+        assert_eq!(content.effects(&Prerogative::Education.into())[0].amount, 2);
+        assert!(content.prerogative("old money").unwrap().effects.is_empty());
+        assert!(content.burden("Feud").is_some());
+    }
+
+    #[test]
+    fn bad_packs_say_what_is_wrong() {
+        assert!(
+            error("name = \"X\"\n[[prerogatives]]\nname = \"A\"\nstats = []\n")
+                .contains("unknown field `stats`")
+        );
+        assert!(error(
+            "name = \"X\"\n[[prerogatives]]\nname = \"A\"\n\
+             effects = [{ stat = \"luck\", amount = 1 }]\n"
+        )
+        .contains("unknown stat \"luck\""));
+        assert!(error(
+            "name = \"X\"\n[[burdens]]\nname = \"Feud\"\n[[burdens]]\nname = \"feud\"\n"
+        )
+        .contains("burden \"feud\" is defined more than once"));
+        assert!(error(
+            "name = \"X\"\n[[prerogatives]]\nname = \"A\"\n\
+             effects = [{ stat = \"knowledge\", amount = 0 }]\n"
+        )
+        .contains("in prerogative \"A\""));
+        assert!(error("name = \" \"\n").contains("needs a name"));
+        assert!(error(
+            "name = \"X\"\n[[prerogatives]]\nname = \"A\"\n\
+             restrictions = [{ kind = \"minimum_stat\", stat = \"knowledge\", value = 99 }]\n"
+        )
+        .contains("past the maximum"));
+    }
+
+    #[test]
+    fn homebrew_is_offered_after_the_core_rules() {
+        let pack = ContentPack::from_toml(
+            "name = \"X\"\n[[prerogatives]]\nname = \"Old Money\"\n\
+             [[burdens]]\nname = \"Feud\"\n",
+        )
+        .unwrap();
+        let content = Content::from_packs(vec![pack]);
+        let prerogatives = content.prerogative_ids();
+        assert_eq!(prerogatives.len(), Prerogative::LIST.len() + 1);
+        assert_eq!(prerogatives.last().unwrap().name(), "Old Money");
+        assert!(content.has_prerogative(&PrerogativeId::Homebrew("old money".into())));
+        assert!(!Content::default().has_burden(&BurdenId::Homebrew("Feud".into())));
+        assert_eq!(
+            BurdenId::named(&format!("{:?}", Burden::LIST[0]).to_uppercase()),
+            BurdenId::Core(Burden::LIST[0])
+        );
+    }
+
+    #[test]
+    fn homebrew_ids_keep_their_spelling_and_ignore_case() {
+        let homebrew = PrerogativeId::named(" Old Money ");
+        assert_eq!(homebrew, PrerogativeId::named("old money"));
+        assert_eq!(homebrew.name(), "Old Money");
+        assert_ne!(homebrew, PrerogativeId::named("New Money"));
+
+        let core = BurdenId::Core(Burden::LIST[0]);
+        assert_eq!(core.name(), format!("{:?}", Burden::LIST[0]));
+        assert!(std::ptr::eq(core.name(), core.name()));
+    }
+
+    #[test]
+    fn restrictions_limit_who_can_take_a_prerogative() {
+        let pack = ContentPack::from_toml(
+            r#"
+            name = "X"
+
+            [[prerogatives]]
+            name = "Heirloom"
+            restrictions = [{ kind = "unique" }, { kind = "burden_only" }]
+
+            [[prerogatives]]
+            name = "Prodigy"
+            restrictions = [{ kind = "minimum_stat", stat = "knowledge", value = 5 }]
+            "#,
+        )
+        .unwrap();
+        let content = Content::from_packs(vec![pack]);
+        let heirloom = PrerogativeId::named("Heirloom");
+        assert!(content.check_restrictions(&heirloom, None, 0, true).is_ok());
+        let err = content
+            .check_restrictions(&heirloom, None, 1, true)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Heirloom can be taken only once");
+        assert!(content
+            .check_restrictions(&heirloom, None, 0, false)
+            .is_err());
+
+        let prodigy = PrerogativeId::named("Prodigy");
+        let mut rng: rand::rngs::SmallRng = rand::SeedableRng::seed_from_u64(0);
+        let mut base = BaseStats::new(&rules::selection(&rules::random(&mut rng)).unwrap());
+        base.knowledge = 4;
+        assert!(content.check_restrictions(&prodigy, None, 0, false).is_ok());
+        assert!(content
+            .check_restrictions(&prodigy, Some(base), 0, false)
+            .is_err());
+        base.knowledge = 5;
+        assert!(content
+            .check_restrictions(&prodigy, Some(base), 0, false)
+            .is_ok());
+    }
+
+    #[test]
+    fn packs_round_trip_through_both_formats() {
+        let pack = ContentPack::from_toml(
+            r#"
+            name = "Mine"
+
+            [[prerogatives]]
+            name = "Old Money"
+            effects = [{ stat = "proficiency", amount = 2 }]
+            restrictions = [{ kind = "unique" }, { kind = "minimum_stat", stat = "knowledge", value = 4 }]
+            "#,
+        )
+        .unwrap();
+        for key in ["packs/mine.toml", "packs/mine.json"] {
+            let contents = pack.serialize(key).unwrap();
+            assert_eq!(ContentPack::parse(key, &contents).unwrap(), pack);
+        }
+    }
+
+    #[test]
+    fn new_keys_skip_taken_ones() {
+        let pack = |name: &str| ContentPack::from_toml(&format!("name = {:?}", name)).unwrap();
+        let taken = vec!["packs/My_Pack.toml".to_owned()];
+        assert_eq!(pack("Other").new_key(&taken), "packs/Other.toml");
+        assert_eq!(pack("My.Pack").new_key(&taken), "packs/My_Pack_2.toml");
+        let taken = [taken, vec!["packs/my_pack_2.toml".to_owned()]].concat();
+        assert_eq!(pack("My Pack").new_key(&taken), "packs/My_Pack_3.toml");
+    }
+
+    #[test]
+    fn packs_can_be_json() {
+        let pack = ContentPack::parse(
+            "packs/mine.JSON",
+            r#"{"name": "Mine", "burdens": [{"name": "Curse"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(pack.burdens[0].name, "Curse");
+    }
+}
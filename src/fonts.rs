@@ -0,0 +1,70 @@
+//! The fonts bundled in `resources/fonts`.
+//!
+//! Fonts are embedded in the binary so that they're available on the web without any loading.
+
+use iced_winit::Font;
+
+pub const INCONSOLATA_BYTES: &[u8] = include_bytes!("../resources/fonts/Inconsolata-Regular.ttf");
+pub const PIXEL_BYTES: &[u8] = include_bytes!("../resources/fonts/04b03.ttf");
+
+pub const INCONSOLATA: Font = Font::External {
+    name: "Inconsolata",
+    bytes: INCONSOLATA_BYTES,
+};
+pub const PIXEL: Font = Font::External {
+    name: "04b03",
+    bytes: PIXEL_BYTES,
+};
+
+/// The default size of body text, in logical pixels.
+pub const BODY_SIZE: u16 = 20;
+/// The default size of headings, in logical pixels.
+pub const HEADING_SIZE: u16 = 32;
+
+/// Settings for the UI backend with the bundled body font as the default.
+pub fn settings() -> iced_solstice::Settings {
+    iced_solstice::Settings {
+        default_font: Some(INCONSOLATA_BYTES),
+        default_text_size: BODY_SIZE,
+        ..Default::default()
+    }
+}
+
+/// Which bundled font to use for a role in a theme.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FontChoice {
+    /// The UI backend's default, which is Inconsolata.
+    Default,
+    Inconsolata,
+    Pixel,
+}
+
+impl From<FontChoice> for Font {
+    fn from(choice: FontChoice) -> Self {
+        match choice {
+            FontChoice::Default => Font::Default,
+            FontChoice::Inconsolata => INCONSOLATA,
+            FontChoice::Pixel => PIXEL,
+        }
+    }
+}
+
+/// The fonts and text sizes of a theme.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Typography {
+    pub body: FontChoice,
+    pub heading: FontChoice,
+    pub body_size: u16,
+    pub heading_size: u16,
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Self {
+            body: FontChoice::Default,
+            heading: FontChoice::Inconsolata,
+            body_size: BODY_SIZE,
+            heading_size: HEADING_SIZE,
+        }
+    }
+}
@@ -1,23 +1,857 @@
-use iced_winit::{widget::Text, Element};
+//! The shared table: join a session server, show everyone your character and roll dice in the
+//! open.
+
+mod gm;
+
+use crate::{
+    campaign::Campaign,
+    character::Character,
+    client::{self, Client},
+    content::{BurdenId, PrerogativeId},
+    protocol::{self, ClientMessage, Player, PlayerId, PlayerRoll, ServerMessage},
+    session_log::{self, CheckRoll, Entry, Event},
+    settings::labelled,
+    theme::Theme,
+};
+use iced_winit::{
+    widget::{
+        button, pick_list, scrollable, text_input, Button, Checkbox, Column, PickList, Row,
+        Scrollable, TextInput,
+    },
+    Element, Length,
+};
+use spirits_within::{Spirit, Stat};
+use std::collections::BTreeMap;
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    Test,
+    AddressChanged(String),
+    NameChanged(String),
+    /// Names a saved campaign to open.
+    CampaignChanged(String),
+    OpenCampaign,
+    ExportCampaign,
+    Join,
+    Leave,
+    /// Shows the character from the character creator to the table.
+    ShareCharacter,
+    DiceSelected(Dice),
+    SpiritSelected(SpiritChoice),
+    Roll,
+    NoteChanged(String),
+    AddNote,
+    /// Brings one of your burdens, with the prerogative it is for, into play, which is logged, or
+    /// takes it out again.
+    BurdenToggled((BurdenId, PrerogativeId), bool),
+    /// Records something that happened at the table in the campaign's log.
+    Log(Entry),
+    Export(session_log::Format),
+    GameMasterNoteChanged(String),
+    AddGameMasterNote,
+    RemoveGameMasterNote(usize),
+    /// Something happened on the connection.
+    Session(client::Event),
+    GameMasterToggled(bool),
+    GameMaster(gm::Message),
+}
+
+#[derive(Debug)]
+pub struct Game {
+    address: String,
+    name: String,
+    dice: u8,
+    /// The spirit the next roll is for.
+    spirit: Option<Spirit>,
+    note: String,
+    /// Which of your character's burdens are in play, with the prerogatives they are for.
+    burdens_in_play: Vec<(BurdenId, PrerogativeId)>,
+    game_master_note: String,
+    session: Option<Session>,
+    /// Why the last session ended, or what the server or the campaign last objected to.
+    status: Option<String>,
+    /// Whether the Game Master's dashboard is showing instead of the table.
+    game_master_mode: bool,
+    game_master: gm::GameMaster,
+
+    address_input: text_input::State,
+    name_input: text_input::State,
+    campaign_bar: CampaignBar,
+    join_button: button::State,
+    leave_button: button::State,
+    share_button: button::State,
+    dice_picker: pick_list::State<Dice>,
+    spirit_picker: pick_list::State<SpiritChoice>,
+    roll_button: button::State,
+    note_input: text_input::State,
+    note_button: button::State,
+    markdown_button: button::State,
+    json_button: button::State,
+    log_scroll: scrollable::State,
+    game_master_note_input: text_input::State,
+    game_master_note_button: button::State,
+    /// One for each of the Game Master's notes.
+    remove_note_buttons: Vec<button::State>,
+    notes_scroll: scrollable::State,
+}
+
+/// A connection and what the server has said about the table over it.
+#[derive(Debug)]
+struct Session {
+    client: Client,
+    /// `None` until the server welcomes us.
+    you: Option<PlayerId>,
+    players: BTreeMap<PlayerId, Player>,
+    /// The stat and everyone's dice in the last group check.
+    last_check: Option<(Stat, BTreeMap<PlayerId, Vec<u8>>)>,
 }
 
-#[derive(Debug, Default, Clone)]
-pub struct Game;
+impl Session {
+    fn name(&self, id: PlayerId) -> String {
+        self.players
+            .get(&id)
+            .map_or_else(|| format!("Player {}", id), |p| p.name.clone())
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self {
+            address: format!("ws://localhost:{}", protocol::DEFAULT_PORT),
+            name: "Player".into(),
+            dice: 1,
+            spirit: None,
+            note: String::new(),
+            burdens_in_play: vec![],
+            game_master_note: String::new(),
+            session: None,
+            status: None,
+            game_master_mode: false,
+            game_master: Default::default(),
+            address_input: Default::default(),
+            name_input: Default::default(),
+            campaign_bar: Default::default(),
+            join_button: Default::default(),
+            leave_button: Default::default(),
+            share_button: Default::default(),
+            dice_picker: Default::default(),
+            spirit_picker: Default::default(),
+            roll_button: Default::default(),
+            note_input: Default::default(),
+            note_button: Default::default(),
+            markdown_button: Default::default(),
+            json_button: Default::default(),
+            log_scroll: Default::default(),
+            game_master_note_input: Default::default(),
+            game_master_note_button: Default::default(),
+            remove_note_buttons: vec![],
+            notes_scroll: Default::default(),
+        }
+    }
+}
 
 impl Game {
-    pub fn update(&mut self, message: Message) {
+    /// Whether one of the screen's text inputs has keyboard focus.
+    pub fn is_editing_text(&self) -> bool {
+        [
+            &self.address_input,
+            &self.name_input,
+            &self.campaign_bar.input,
+            &self.note_input,
+            &self.game_master_note_input,
+        ]
+        .into_iter()
+        .any(text_input::State::is_focused)
+    }
+
+    /// `character` is the one being built in the character creator, for sharing. Returns whether
+    /// `campaign` changed.
+    pub fn update(
+        &mut self,
+        message: Message,
+        character: &Character,
+        campaign: &mut Campaign,
+    ) -> bool {
         match message {
-            Message::Test => unimplemented!(),
+            Message::AddressChanged(address) => self.address = address,
+            Message::NameChanged(name) => self.name = name,
+            Message::CampaignChanged(name) => self.campaign_bar.name = name,
+            Message::OpenCampaign => {
+                let name = std::mem::take(&mut self.campaign_bar.name);
+                if !name.trim().is_empty() {
+                    match Campaign::load(name.trim()) {
+                        Ok(opened) => {
+                            *campaign = opened;
+                            self.status = Some(format!("opened campaign {}", campaign.name));
+                            return true;
+                        }
+                        Err(err) => self.status = Some(format!("{:#}", err)),
+                    }
+                }
+            }
+            Message::ExportCampaign => {
+                self.status = Some(match campaign.export() {
+                    Ok(place) => format!("exported the campaign to {}", place),
+                    Err(err) => format!("{:#}", err),
+                });
+            }
+            Message::Join => {
+                self.status = None;
+                self.session = Some(Session {
+                    client: Client::connect(self.address.trim(), self.name.trim()),
+                    you: None,
+                    players: BTreeMap::new(),
+                    last_check: None,
+                });
+            }
+            Message::Leave => {
+                self.status = Some("left the table".into());
+                if let Some(Session { you: Some(_), .. }) = self.session.take() {
+                    let entry = Entry::new(self.name.trim(), Event::Left);
+                    return self.update(Message::Log(entry), character, campaign);
+                }
+            }
+            Message::ShareCharacter => self.send(ClientMessage::ShareCharacter {
+                character: character.clone(),
+            }),
+            Message::DiceSelected(Dice(dice)) => self.dice = dice,
+            Message::SpiritSelected(SpiritChoice(spirit)) => self.spirit = spirit,
+            Message::Roll => self.send(ClientMessage::Roll {
+                dice: self.dice,
+                spirit: self.spirit,
+            }),
+            Message::NoteChanged(note) => self.note = note,
+            Message::AddNote => {
+                let note = std::mem::take(&mut self.note);
+                if !note.trim().is_empty() {
+                    let entry = Entry::new(self.name.trim(), Event::Note).with_notes(note.trim());
+                    return self.update(Message::Log(entry), character, campaign);
+                }
+            }
+            Message::BurdenToggled(pair, in_play) => {
+                // the character may have changed since, so forget burdens it no longer has
+                self.burdens_in_play
+                    .retain(|held| character.burdens.contains(held));
+                if !in_play {
+                    self.burdens_in_play.retain(|held| *held != pair);
+                } else if character.burdens.contains(&pair) && !self.burdens_in_play.contains(&pair)
+                {
+                    let burden = pair.0.clone();
+                    self.burdens_in_play.push(pair);
+                    let entry = Entry::new(self.name.trim(), Event::BurdenInvoked { burden });
+                    return self.update(Message::Log(entry), character, campaign);
+                }
+            }
+            Message::Log(entry) => {
+                campaign.log.push(entry);
+                return true;
+            }
+            Message::Export(format) => {
+                self.status = Some(match campaign.log.export(&campaign.name, format) {
+                    Ok(place) => format!("exported the log to {}", place),
+                    Err(err) => format!("{:#}", err),
+                });
+            }
+            Message::GameMasterNoteChanged(note) => self.game_master_note = note,
+            Message::AddGameMasterNote => {
+                let note = std::mem::take(&mut self.game_master_note);
+                if !note.trim().is_empty() {
+                    campaign.notes.push(note.trim().to_owned());
+                    return true;
+                }
+            }
+            Message::RemoveGameMasterNote(index) => {
+                if index < campaign.notes.len() {
+                    campaign.notes.remove(index);
+                    return true;
+                }
+            }
+            Message::Session(client::Event::Closed(reason)) => {
+                self.session = None;
+                // a rejection says more than the close that follows it
+                self.status.get_or_insert(reason);
+            }
+            Message::Session(client::Event::Message(message)) => {
+                if let Some(entry) = self.handle(message) {
+                    return self.update(Message::Log(entry), character, campaign);
+                }
+            }
+            Message::GameMasterToggled(mode) => self.game_master_mode = mode,
+            Message::GameMaster(message) => {
+                match self.game_master.update(message, character, campaign) {
+                    Some(gm::Action::Send(message)) => self.send(message),
+                    Some(gm::Action::Log(entry)) => {
+                        return self.update(Message::Log(entry), character, campaign)
+                    }
+                    None => {}
+                }
+            }
         }
+        false
     }
 
-    pub fn view(
-        &mut self,
-    ) -> Element<'_, Message, <crate::Application as iced_winit::Program>::Renderer> {
-        Text::new("unimplemented!").into()
+    fn send(&self, message: ClientMessage) {
+        if let Some(session) = &self.session {
+            session.client.send(message);
+        }
+    }
+
+    /// Applies `message` to the session, returning what to log about it.
+    fn handle(&mut self, message: ServerMessage) -> Option<Entry> {
+        let session = self.session.as_mut()?;
+        match message {
+            ServerMessage::Welcome { you, players, .. } => {
+                session.you = Some(you);
+                session.players = players.into_iter().map(|p| (p.id, p)).collect();
+                Some(Entry::new(session.name(you), Event::Joined))
+            }
+            ServerMessage::Rejected { reason } | ServerMessage::Error { reason } => {
+                self.status = Some(reason);
+                None
+            }
+            ServerMessage::Joined { player } => {
+                let entry = Entry::new(player.name.as_str(), Event::Joined);
+                session.players.insert(player.id, player);
+                Some(entry)
+            }
+            ServerMessage::Left { player } => {
+                let entry = Entry::new(session.name(player), Event::Left);
+                session.players.remove(&player);
+                self.game_master.player_left(player);
+                Some(entry)
+            }
+            ServerMessage::CharacterShared { player, character } => {
+                let entry = Entry::new(session.name(player), Event::SharedCharacter);
+                if let Some(player) = session.players.get_mut(&player) {
+                    player.character = Some(character);
+                }
+                Some(entry)
+            }
+            ServerMessage::Rolled {
+                player,
+                spirit,
+                results,
+            } => {
+                Some(Entry::new(session.name(player), Event::Roll { results }).with_spirit(spirit))
+            }
+            ServerMessage::GroupCheck {
+                player,
+                stat,
+                rolls,
+            } => {
+                let check_rolls = rolls
+                    .iter()
+                    .map(|roll| CheckRoll {
+                        actor: session.name(roll.player),
+                        results: roll.results.clone(),
+                        against: session
+                            .players
+                            .get(&roll.player)
+                            .and_then(|p| crate::rules::stats(p.character.as_ref()?).ok())
+                            .map(|stats| crate::rules::stat_value(&stats, stat).to_string()),
+                    })
+                    .collect();
+                let entry = Entry::new(
+                    session.name(player),
+                    Event::GroupCheck {
+                        stat,
+                        rolls: check_rolls,
+                    },
+                );
+                session.last_check = Some((
+                    stat,
+                    rolls
+                        .into_iter()
+                        .map(|PlayerRoll { player, results }| (player, results))
+                        .collect(),
+                ));
+                Some(entry)
+            }
+        }
+    }
+
+    /// Everything that happened on the connection since the last call.
+    pub fn poll(&self) -> Vec<client::Event> {
+        match &self.session {
+            Some(session) => session.client.events().collect(),
+            None => vec![],
+        }
+    }
+
+    /// `character` is the one being built in the character creator, whose burdens are tracked.
+    pub fn view<'a>(
+        &'a mut self,
+        campaign: &'a Campaign,
+        character: &Character,
+        theme: Theme,
+    ) -> Element<'a, Message, Renderer> {
+        let mode = labelled(
+            theme,
+            "Game Master",
+            Checkbox::new(self.game_master_mode, "", Message::GameMasterToggled)
+                .size(theme.typography().body_size),
+        );
+        let content = if self.game_master_mode {
+            self.game_master_view(campaign, theme)
+        } else {
+            self.table_view(campaign, character, theme)
+        };
+        Column::new()
+            .push(mode)
+            .push(content)
+            .spacing(5)
+            .padding(10)
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn game_master_view<'a>(
+        &'a mut self,
+        campaign: &'a Campaign,
+        theme: Theme,
+    ) -> Element<'a, Message, Renderer> {
+        let typography = theme.typography();
+        let (table, check) = match &self.session {
+            Some(Session {
+                you: Some(you),
+                players,
+                last_check,
+                ..
+            }) => {
+                let table: Vec<gm::TableMember> = players
+                    .values()
+                    .map(|player| gm::TableMember {
+                        id: player.id,
+                        name: player.name.clone(),
+                        you: player.id == *you,
+                        character: player.character.as_ref(),
+                        check: last_check
+                            .as_ref()
+                            .and_then(|(_, rolls)| rolls.get(&player.id))
+                            .map(Vec::as_slice),
+                    })
+                    .collect();
+                (table, last_check.as_ref().map(|(stat, _)| *stat))
+            }
+            _ => (vec![], None),
+        };
+        let dashboard = self
+            .game_master
+            .view(&table, check, campaign.choices(), theme)
+            .map(Message::GameMaster);
+
+        self.remove_note_buttons
+            .resize_with(campaign.notes.len(), Default::default);
+        let notes = campaign
+            .notes
+            .iter()
+            .zip(&mut self.remove_note_buttons)
+            .enumerate()
+            .fold(
+                Scrollable::new(&mut self.notes_scroll)
+                    .height(Length::Fill)
+                    .spacing(2),
+                |notes, (index, (note, state))| {
+                    notes.push(
+                        Row::new()
+                            .push(theme.body(note.as_str()).width(Length::Fill))
+                            .push(
+                                Button::new(state, theme.body("x"))
+                                    .on_press(Message::RemoveGameMasterNote(index))
+                                    .style(theme.danger_button()),
+                            )
+                            .spacing(5),
+                    )
+                },
+            );
+        let notes = Column::new()
+            .push(theme.heading("Notes"))
+            .push(
+                Row::new()
+                    .push(
+                        TextInput::new(
+                            &mut self.game_master_note_input,
+                            "Note",
+                            &self.game_master_note,
+                            Message::GameMasterNoteChanged,
+                        )
+                        .on_submit(Message::AddGameMasterNote)
+                        .font(typography.body.into())
+                        .size(typography.body_size)
+                        .padding(2),
+                    )
+                    .push(
+                        Button::new(&mut self.game_master_note_button, theme.body("Add"))
+                            .on_press(Message::AddGameMasterNote)
+                            .style(theme.button()),
+                    )
+                    .spacing(5)
+                    .align_items(iced_winit::Alignment::Center),
+            )
+            .push(notes)
+            .spacing(5)
+            .width(Length::FillPortion(1));
+
+        Column::new()
+            .push(self.campaign_bar.view(campaign, theme))
+            .push(
+                Row::new()
+                    .push(Column::new().push(dashboard).width(Length::FillPortion(3)))
+                    .push(notes)
+                    .spacing(10),
+            )
+            .spacing(5)
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// The players' view of the shared table.
+    fn table_view<'a>(
+        &'a mut self,
+        campaign: &'a Campaign,
+        character: &Character,
+        theme: Theme,
+    ) -> Element<'a, Message, Renderer> {
+        let typography = theme.typography();
+        let status = theme.body(self.status.clone().unwrap_or_default());
+
+        let session = match &self.session {
+            None => {
+                return Column::new()
+                    .push(self.campaign_bar.view(campaign, theme))
+                    .push(theme.heading("Shared table"))
+                    .push(labelled(
+                        theme,
+                        "Server",
+                        TextInput::new(
+                            &mut self.address_input,
+                            "ws://host:port",
+                            &self.address,
+                            Message::AddressChanged,
+                        )
+                        .font(typography.body.into())
+                        .size(typography.body_size)
+                        .padding(2)
+                        .width(Length::Units(240)),
+                    ))
+                    .push(labelled(
+                        theme,
+                        "Name",
+                        TextInput::new(
+                            &mut self.name_input,
+                            "Name",
+                            &self.name,
+                            Message::NameChanged,
+                        )
+                        .font(typography.body.into())
+                        .size(typography.body_size)
+                        .padding(2)
+                        .width(Length::Units(240)),
+                    ))
+                    .push(
+                        Button::new(&mut self.join_button, theme.body("Join"))
+                            .on_press(Message::Join)
+                            .style(theme.button()),
+                    )
+                    .push(status)
+                    .push(log_view(
+                        campaign,
+                        &mut self.log_scroll,
+                        &mut self.markdown_button,
+                        &mut self.json_button,
+                        theme,
+                    ))
+                    .spacing(5)
+                    .width(Length::Fill)
+                    .into();
+            }
+            Some(session) => session,
+        };
+
+        let leave = Button::new(&mut self.leave_button, theme.body("Leave"))
+            .on_press(Message::Leave)
+            .style(theme.danger_button());
+        let you = match session.you {
+            Some(you) => you,
+            None => {
+                return Column::new()
+                    .push(theme.heading("Shared table"))
+                    .push(theme.body(format!("Joining {}...", self.address)))
+                    .push(leave)
+                    .push(status)
+                    .spacing(5)
+                    .width(Length::Fill)
+                    .into()
+            }
+        };
+
+        let players = session.players.values().fold(
+            Column::new().push(theme.heading("Players")).spacing(5),
+            |column, player| {
+                let name = if player.id == you {
+                    format!("{} (you)", player.name)
+                } else {
+                    player.name.clone()
+                };
+                column
+                    .push(theme.body(name))
+                    .push(theme.body(summary(player.character.as_ref())))
+            },
+        );
+
+        // the roll about to be made, or else the last group check
+        let stat = self
+            .spirit
+            .map(|spirit| spirit.stat())
+            .or_else(|| session.last_check.as_ref().map(|(stat, _)| *stat));
+        let burdens = burdens_view(character, &self.burdens_in_play, stat, theme);
+
+        let dice: Vec<Dice> = (1..=protocol::MAX_DICE).map(Dice).collect();
+        let spirits: Vec<SpiritChoice> = std::iter::once(None)
+            .chain(Spirit::LIST.into_iter().map(Some))
+            .map(SpiritChoice)
+            .collect();
+        let controls = Row::new()
+            .push(
+                Button::new(&mut self.share_button, theme.body("Share character"))
+                    .on_press(Message::ShareCharacter)
+                    .style(theme.button()),
+            )
+            .push(
+                PickList::new(
+                    &mut self.dice_picker,
+                    dice,
+                    Some(Dice(self.dice)),
+                    Message::DiceSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            )
+            .push(
+                PickList::new(
+                    &mut self.spirit_picker,
+                    spirits,
+                    Some(SpiritChoice(self.spirit)),
+                    Message::SpiritSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            )
+            .push(
+                Button::new(&mut self.roll_button, theme.body("Roll"))
+                    .on_press(Message::Roll)
+                    .style(theme.button()),
+            )
+            .push(leave)
+            .spacing(5)
+            .align_items(iced_winit::Alignment::Center);
+        let note = Row::new()
+            .push(
+                TextInput::new(
+                    &mut self.note_input,
+                    "Note for the log",
+                    &self.note,
+                    Message::NoteChanged,
+                )
+                .on_submit(Message::AddNote)
+                .font(typography.body.into())
+                .size(typography.body_size)
+                .padding(2),
+            )
+            .push(
+                Button::new(&mut self.note_button, theme.body("Add note"))
+                    .on_press(Message::AddNote)
+                    .style(theme.button()),
+            )
+            .spacing(5)
+            .align_items(iced_winit::Alignment::Center);
+        let log = log_view(
+            campaign,
+            &mut self.log_scroll,
+            &mut self.markdown_button,
+            &mut self.json_button,
+            theme,
+        );
+
+        let table = Row::new()
+            .push(
+                Column::new()
+                    .push(players)
+                    .push(burdens)
+                    .spacing(10)
+                    .width(Length::FillPortion(1)),
+            )
+            .push(
+                Column::new()
+                    .push(controls)
+                    .push(status)
+                    .push(note)
+                    .push(log)
+                    .spacing(5)
+                    .width(Length::FillPortion(2)),
+            )
+            .spacing(10);
+
+        Column::new()
+            .push(self.campaign_bar.view(campaign, theme))
+            .push(table)
+            .spacing(5)
+            .width(Length::Fill)
+            .into()
+    }
+}
+
+/// Names the current campaign and opens or exports others.
+#[derive(Debug, Default)]
+struct CampaignBar {
+    /// The name of a saved campaign to open.
+    name: String,
+
+    input: text_input::State,
+    open_button: button::State,
+    export_button: button::State,
+}
+
+impl CampaignBar {
+    fn view(&mut self, campaign: &Campaign, theme: Theme) -> Row<'_, Message, Renderer> {
+        let typography = theme.typography();
+        Row::new()
+            .push(theme.heading(format!("Campaign: {}", campaign.name)))
+            .push(
+                TextInput::new(
+                    &mut self.input,
+                    "Saved campaign",
+                    &self.name,
+                    Message::CampaignChanged,
+                )
+                .on_submit(Message::OpenCampaign)
+                .font(typography.body.into())
+                .size(typography.body_size)
+                .padding(2)
+                .width(Length::Units(160)),
+            )
+            .push(
+                Button::new(&mut self.open_button, theme.body("Open"))
+                    .on_press(Message::OpenCampaign)
+                    .style(theme.button()),
+            )
+            .push(
+                Button::new(&mut self.export_button, theme.body("Export campaign"))
+                    .on_press(Message::ExportCampaign)
+                    .style(theme.button()),
+            )
+            .spacing(10)
+            .align_items(iced_winit::Alignment::Center)
+    }
+}
+
+/// The campaign's log, most recent first, with buttons to export it.
+fn log_view<'a>(
+    campaign: &'a Campaign,
+    scroll: &'a mut scrollable::State,
+    markdown_button: &'a mut button::State,
+    json_button: &'a mut button::State,
+    theme: Theme,
+) -> Column<'a, Message, Renderer> {
+    let export = Row::new()
+        .push(theme.heading("Log"))
+        .push(
+            Button::new(markdown_button, theme.body("Export Markdown"))
+                .on_press(Message::Export(session_log::Format::Markdown))
+                .style(theme.button()),
+        )
+        .push(
+            Button::new(json_button, theme.body("Export JSON"))
+                .on_press(Message::Export(session_log::Format::Json))
+                .style(theme.button()),
+        )
+        .spacing(5)
+        .align_items(iced_winit::Alignment::Center);
+    let entries = campaign.log.entries.iter().rev().fold(
+        Scrollable::new(scroll).height(Length::Fill).spacing(2),
+        |entries, entry| entries.push(theme.body(entry.to_string())),
+    );
+    Column::new().push(export).push(entries).spacing(5)
+}
+
+/// Your character's burdens with their rules text, to bring into play as scenes call for them.
+/// Those in play that bear on checks of `stat` get a reminder.
+fn burdens_view<'a>(
+    character: &Character,
+    in_play: &[(BurdenId, PrerogativeId)],
+    stat: Option<Stat>,
+    theme: Theme,
+) -> Column<'a, Message, Renderer> {
+    let typography = theme.typography();
+    let palette = theme.palette();
+    let column = Column::new().push(theme.heading("Your burdens")).spacing(5);
+    if character.burdens.is_empty() {
+        return column.push(theme.body("No optional burdens"));
+    }
+    character
+        .burdens
+        .iter()
+        .fold(column, |column, pair @ (burden, prerogative)| {
+            let active = in_play.contains(pair);
+            let toggled = pair.clone();
+            let mut column = column
+                .push(
+                    Checkbox::new(
+                        active,
+                        format!("{} (for {})", burden, prerogative),
+                        move |in_play| Message::BurdenToggled(toggled.clone(), in_play),
+                    )
+                    .size(typography.body_size)
+                    .text_size(typography.body_size)
+                    .font(typography.body.into()),
+                )
+                .push(theme.body(crate::rules::burden_text(burden, prerogative)));
+            let bears_on = crate::rules::prerogative_stat(prerogative);
+            if let Some(stat) = stat.filter(|&stat| active && bears_on == Some(stat)) {
+                column = column.push(
+                    theme
+                        .body(format!("Bears on this {:?} roll", stat))
+                        .color(palette.accent),
+                );
+            }
+            column
+        })
+}
+
+/// One line about a shared character.
+fn summary(character: Option<&Character>) -> String {
+    match character.map(crate::rules::stats) {
+        None => "  no character shared".into(),
+        Some(Ok(stats)) => format!(
+            "  Proficiency {}, Knowledge {}, Discipline {}",
+            stats.proficiency, stats.knowledge, stats.discipline
+        ),
+        Some(Err(err)) => format!("  unfinished character: {:#}", err),
+    }
+}
+
+/// A choice of how many dice to roll.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Dice(u8);
+
+impl std::fmt::Display for Dice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}d{}", self.0, protocol::DIE_SIDES)
+    }
+}
+
+/// A choice of spirit to roll for, or none.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SpiritChoice(Option<Spirit>);
+
+impl std::fmt::Display for SpiritChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(spirit) => write!(f, "{:?}", spirit),
+            None => write!(f, "No spirit"),
+        }
     }
 }
@@ -0,0 +1,495 @@
+//! The Game Master's dashboard: the whole party's spirits, stats and burdens side by side, with
+//! group checks called from one place.
+//!
+//! GM mode is advisory. Anyone can switch to it, and the server rolls a group check for whoever
+//! asks.
+
+use crate::{
+    campaign::{Campaign, MemberChoice},
+    character::Character,
+    content::{BurdenId, PrerogativeId},
+    protocol::{self, ClientMessage, PlayerId},
+    rules::{self, STATS},
+    session_log::{Entry, Event},
+    theme::Theme,
+};
+use iced_winit::{
+    widget::{button, pick_list, scrollable, Button, Checkbox, Column, PickList, Row, Scrollable},
+    Element, Length,
+};
+use spirits_within::{Connection, Spirit, Stat};
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+
+/// The width of each party member's column.
+const COLUMN_WIDTH: u16 = 160;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Adds the character from the character creator to the party.
+    AddFromCreator,
+    /// Adds a member of the campaign to the party.
+    AddFromCampaign(MemberChoice),
+    RemoveLocal(u32),
+    HighlightSelected(Highlight),
+    /// Brings a party member's burden into play, which is logged, or takes it out again.
+    BurdenToggled {
+        member: Member,
+        /// Who to log as invoking it.
+        actor: String,
+        burden: BurdenId,
+        /// The prerogative the burden is for.
+        prerogative: PrerogativeId,
+        in_play: bool,
+    },
+    CheckStatSelected(StatOption),
+    CheckDiceSelected(super::Dice),
+    CallCheck,
+}
+
+/// Someone in the party.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Member {
+    /// A player at the shared table.
+    Player(PlayerId),
+    /// A character the Game Master added themselves, by a number that stays the same when others
+    /// are removed.
+    Local(u32),
+}
+
+/// What the Game screen should do about a message to the dashboard.
+#[derive(Debug)]
+pub enum Action {
+    Send(ClientMessage),
+    Log(Entry),
+}
+
+/// A party member at the shared table, as the Game screen knows them.
+pub struct TableMember<'a> {
+    pub id: PlayerId,
+    pub name: String,
+    /// Whether this is the Game Master's own connection.
+    pub you: bool,
+    pub character: Option<&'a Character>,
+    /// Their dice in the last group check.
+    pub check: Option<&'a [u8]>,
+}
+
+#[derive(Debug)]
+pub struct GameMaster {
+    /// Characters added by the Game Master, with their `Member::Local` numbers and names.
+    local: Vec<(u32, String, Character)>,
+    next_local: u32,
+    highlight: Highlight,
+    /// Burdens that have come into play, with the prerogatives they are for.
+    burdens_in_play: Vec<(Member, BurdenId, PrerogativeId)>,
+    check_stat: Stat,
+    check_dice: u8,
+
+    add_button: button::State,
+    member_picker: pick_list::State<MemberChoice>,
+    /// One for each local character.
+    remove_buttons: Vec<button::State>,
+    highlight_picker: pick_list::State<Highlight>,
+    stat_picker: pick_list::State<StatOption>,
+    dice_picker: pick_list::State<super::Dice>,
+    check_button: button::State,
+    scroll: scrollable::State,
+}
+
+impl Default for GameMaster {
+    fn default() -> Self {
+        Self {
+            local: vec![],
+            next_local: 1,
+            highlight: Highlight(Some(Connection::Mastery)),
+            burdens_in_play: vec![],
+            check_stat: STATS[0],
+            check_dice: 1,
+            add_button: Default::default(),
+            member_picker: Default::default(),
+            remove_buttons: vec![],
+            highlight_picker: Default::default(),
+            stat_picker: Default::default(),
+            dice_picker: Default::default(),
+            check_button: Default::default(),
+            scroll: Default::default(),
+        }
+    }
+}
+
+impl GameMaster {
+    /// Applies `message`, returning anything to send to the table or log. `character` is the one
+    /// being built in the character creator.
+    pub fn update(
+        &mut self,
+        message: Message,
+        character: &Character,
+        campaign: &Campaign,
+    ) -> Option<Action> {
+        match message {
+            Message::AddFromCreator => {
+                let name = format!("Character {}", self.next_local);
+                self.add_local(name, character.clone());
+            }
+            Message::AddFromCampaign(choice) => {
+                if let Some(Ok(character)) = campaign
+                    .member(&choice)
+                    .map(|member| member.progression.current())
+                {
+                    self.add_local(choice.to_string(), character);
+                }
+            }
+            Message::RemoveLocal(id) => {
+                self.local.retain(|(local, _, _)| *local != id);
+                self.burdens_in_play
+                    .retain(|(member, _, _)| *member != Member::Local(id));
+            }
+            Message::HighlightSelected(highlight) => self.highlight = highlight,
+            Message::BurdenToggled {
+                member,
+                actor,
+                burden,
+                prerogative,
+                in_play,
+            } => {
+                let key = (member, burden, prerogative);
+                let was_in_play = self.burdens_in_play.contains(&key);
+                if !in_play {
+                    self.burdens_in_play.retain(|held| *held != key);
+                } else if !was_in_play {
+                    let burden = key.1.clone();
+                    self.burdens_in_play.push(key);
+                    return Some(Action::Log(Entry::new(
+                        actor,
+                        Event::BurdenInvoked { burden },
+                    )));
+                }
+            }
+            Message::CheckStatSelected(StatOption(stat)) => self.check_stat = stat,
+            Message::CheckDiceSelected(super::Dice(dice)) => self.check_dice = dice,
+            Message::CallCheck => {
+                return Some(Action::Send(ClientMessage::GroupCheck {
+                    stat: self.check_stat,
+                    dice: self.check_dice,
+                }))
+            }
+        }
+        None
+    }
+
+    fn add_local(&mut self, name: String, character: Character) {
+        self.local.push((self.next_local, name, character));
+        self.next_local += 1;
+    }
+
+    /// Forgets what was tracked for a player who left the table.
+    pub fn player_left(&mut self, id: PlayerId) {
+        self.burdens_in_play
+            .retain(|(member, _, _)| *member != Member::Player(id));
+    }
+
+    /// `table` is everyone at the shared table, if there is one, `check` the stat of the last
+    /// group check and `campaign_members` everyone in the campaign.
+    pub fn view(
+        &mut self,
+        table: &[TableMember<'_>],
+        check: Option<Stat>,
+        campaign_members: Vec<MemberChoice>,
+        theme: Theme,
+    ) -> Element<'_, Message, Renderer> {
+        let typography = theme.typography();
+        let palette = theme.palette();
+        let connected = !table.is_empty();
+
+        // the table first, then the Game Master's own
+        let members: Vec<PartyColumn> = table
+            .iter()
+            .map(|member| PartyColumn {
+                member: Member::Player(member.id),
+                name: &member.name,
+                character: member.character,
+                check: member.check,
+            })
+            .chain(self.local.iter().map(|(id, name, character)| PartyColumn {
+                member: Member::Local(*id),
+                name,
+                character: Some(character),
+                check: None,
+            }))
+            .collect();
+
+        let mut header = Row::new().push(cell(theme.body("")));
+        for member in table {
+            let name = if member.you {
+                format!("{} (you)", member.name)
+            } else {
+                member.name.clone()
+            };
+            header = header.push(cell(theme.body(name)));
+        }
+        self.remove_buttons
+            .resize_with(self.local.len(), Default::default);
+        for ((id, name, _), state) in self.local.iter().zip(&mut self.remove_buttons) {
+            header = header.push(
+                Row::new()
+                    .push(theme.body(name.as_str()))
+                    .push(
+                        Button::new(state, theme.body("x"))
+                            .on_press(Message::RemoveLocal(*id))
+                            .style(theme.danger_button()),
+                    )
+                    .spacing(5)
+                    .width(Length::Units(COLUMN_WIDTH)),
+            );
+        }
+
+        let mut matrix = Column::new().push(header).spacing(2);
+        for stat in STATS {
+            matrix = matrix.push(theme.heading(format!("{:?}", stat)));
+            for spirit in Spirit::LIST.into_iter().filter(|s| s.stat() == stat) {
+                let row = members.iter().fold(
+                    Row::new().push(cell(theme.body(format!("{:?}", spirit)))),
+                    |row, member| {
+                        let connection = member.character.and_then(|c| connection(c, spirit));
+                        let mut text = theme
+                            .body(connection.map_or_else(|| "-".to_owned(), |c| c.to_string()));
+                        if connection.is_some() && self.highlight.0 == connection {
+                            text = text.color(palette.accent);
+                        }
+                        row.push(cell(text))
+                    },
+                );
+                matrix = matrix.push(row);
+            }
+        }
+
+        matrix = matrix.push(theme.heading("Stats"));
+        for stat in STATS {
+            let row = members.iter().fold(
+                Row::new().push(cell(theme.body(format!("{:?}", stat)))),
+                |row, member| {
+                    let value = member
+                        .character
+                        .and_then(|c| crate::rules::stats(c).ok())
+                        .map_or_else(
+                            || "-".to_owned(),
+                            |stats| crate::rules::stat_value(&stats, stat).to_string(),
+                        );
+                    row.push(cell(theme.body(value)))
+                },
+            );
+            matrix = matrix.push(row);
+        }
+
+        matrix = matrix.push(theme.heading("Burdens in play"));
+        let burdens = members
+            .iter()
+            .fold(Row::new().push(cell(theme.body(""))), |row, member| {
+                let burdens = member.character.map_or(&[][..], |c| &c.burdens[..]);
+                let column = burdens.iter().fold(
+                    Column::new().spacing(2),
+                    |column, (burden, prerogative)| {
+                        let target = member.member;
+                        let actor = member.name.to_owned();
+                        let (burden, prerogative) = (burden.clone(), prerogative.clone());
+                        let key = (target, burden.clone(), prerogative.clone());
+                        column.push(
+                            Checkbox::new(
+                                self.burdens_in_play.contains(&key),
+                                burden.to_string(),
+                                move |in_play| Message::BurdenToggled {
+                                    member: target,
+                                    actor: actor.clone(),
+                                    burden: burden.clone(),
+                                    prerogative: prerogative.clone(),
+                                    in_play,
+                                },
+                            )
+                            .size(typography.body_size)
+                            .text_size(typography.body_size)
+                            .font(typography.body.into()),
+                        )
+                    },
+                );
+                row.push(column.width(Length::Units(COLUMN_WIDTH)))
+            });
+        matrix = matrix.push(burdens);
+
+        // burdens in play that the check about to be called bears on
+        let (in_play, check_stat) = (&self.burdens_in_play, self.check_stat);
+        let reminders: Vec<String> = members
+            .iter()
+            .flat_map(|member| {
+                let burdens = member.character.map_or(&[][..], |c| &c.burdens[..]);
+                burdens
+                    .iter()
+                    .filter(move |(burden, prerogative)| {
+                        in_play.iter().any(|(held, b, p)| {
+                            *held == member.member && b == burden && p == prerogative
+                        }) && rules::prerogative_stat(prerogative) == Some(check_stat)
+                    })
+                    .map(move |(burden, prerogative)| {
+                        format!(
+                            "{}: {}",
+                            member.name,
+                            rules::burden_text(burden, prerogative)
+                        )
+                    })
+            })
+            .collect();
+        if !reminders.is_empty() {
+            matrix =
+                matrix.push(theme.heading(format!("Reminders for a {:?} check", self.check_stat)));
+            for reminder in reminders {
+                matrix = matrix.push(theme.body(reminder).color(palette.accent));
+            }
+        }
+
+        if let Some(stat) = check {
+            let row = members.iter().fold(
+                Row::new().push(cell(theme.body(format!("{:?} check", stat)))),
+                |row, member| {
+                    let text = match member.check {
+                        Some(results) => results
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        None => "-".to_owned(),
+                    };
+                    row.push(cell(theme.body(text)))
+                },
+            );
+            matrix = matrix.push(theme.heading("Last check")).push(row);
+        }
+
+        let highlights: Vec<Highlight> = std::iter::once(Highlight(None))
+            .chain(
+                crate::rules::CONNECTION_COUNTS
+                    .into_iter()
+                    .map(|(connection, _)| Highlight(Some(connection))),
+            )
+            .collect();
+        let stats: Vec<StatOption> = STATS.into_iter().map(StatOption).collect();
+        let dice: Vec<super::Dice> = (1..=protocol::MAX_DICE).map(super::Dice).collect();
+        let mut check_button =
+            Button::new(&mut self.check_button, theme.body("Call check")).style(theme.button());
+        if connected {
+            check_button = check_button.on_press(Message::CallCheck);
+        }
+        let controls = Row::new()
+            .push(
+                Button::new(&mut self.add_button, theme.body("Add from creator"))
+                    .on_press(Message::AddFromCreator)
+                    .style(theme.button()),
+            )
+            .push(
+                PickList::new(
+                    &mut self.member_picker,
+                    campaign_members,
+                    None,
+                    Message::AddFromCampaign,
+                )
+                .placeholder("Add from campaign")
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            )
+            .push(theme.body("Highlight"))
+            .push(
+                PickList::new(
+                    &mut self.highlight_picker,
+                    highlights,
+                    Some(self.highlight),
+                    Message::HighlightSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            )
+            .push(
+                PickList::new(
+                    &mut self.stat_picker,
+                    stats,
+                    Some(StatOption(self.check_stat)),
+                    Message::CheckStatSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            )
+            .push(
+                PickList::new(
+                    &mut self.dice_picker,
+                    dice,
+                    Some(super::Dice(self.check_dice)),
+                    Message::CheckDiceSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            )
+            .push(check_button)
+            .spacing(5)
+            .align_items(iced_winit::Alignment::Center);
+
+        Column::new()
+            .push(theme.heading("Game Master"))
+            .push(controls)
+            .push(
+                Scrollable::new(&mut self.scroll)
+                    .push(matrix)
+                    .height(Length::Fill),
+            )
+            .spacing(5)
+            .width(Length::Fill)
+            .into()
+    }
+}
+
+/// A fixed-width cell of the dashboard's grid.
+fn cell<'a>(content: impl Into<Element<'a, Message, Renderer>>) -> Column<'a, Message, Renderer> {
+    Column::new()
+        .push(content)
+        .width(Length::Units(COLUMN_WIDTH))
+}
+
+/// A party member's column in the dashboard.
+struct PartyColumn<'a> {
+    member: Member,
+    name: &'a str,
+    character: Option<&'a Character>,
+    check: Option<&'a [u8]>,
+}
+
+fn connection(character: &Character, spirit: Spirit) -> Option<Connection> {
+    character
+        .spirits
+        .iter()
+        .find(|(s, _)| *s == spirit)
+        .map(|&(_, connection)| connection)
+}
+
+/// Which connection to pick out across the party, if any.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Highlight(Option<Connection>);
+
+impl std::fmt::Display for Highlight {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(connection) => write!(f, "{}", connection),
+            None => write!(f, "Nothing"),
+        }
+    }
+}
+
+/// A choice of stat for a group check.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StatOption(Stat);
+
+impl std::fmt::Display for StatOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
@@ -0,0 +1,789 @@
+//! The homebrew editor: content packs of custom prerogatives and burdens, saved with the user
+//! data and enabled campaign by campaign.
+
+use crate::{
+    campaign::Campaign,
+    content::{self, BurdenDef, ContentPack, Effect, PrerogativeDef, Restriction},
+    rules::{self, STATS},
+    settings::labelled,
+    storage,
+    theme::Theme,
+};
+use iced_winit::{
+    widget::{
+        button, pick_list, scrollable, slider, text_input, Button, Checkbox, Column, PickList, Row,
+        Scrollable, Slider, TextInput,
+    },
+    Element, Length,
+};
+use spirits_within::Stat;
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+
+/// A minimum stat restriction, as first added and for finding one of any stat.
+const ANY_MINIMUM_STAT: Restriction = Restriction::MinimumStat {
+    stat: Stat::Proficiency,
+    value: 1,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Edits the saved pack at this index.
+    Open(usize),
+    New,
+    /// Enables or disables the saved pack at this index in the campaign.
+    EnabledToggled(usize, bool),
+    NameChanged(String),
+    DescriptionChanged(String),
+    AddPrerogative,
+    RemovePrerogative(usize),
+    PrerogativeNameChanged(usize, String),
+    PrerogativeDescriptionChanged(usize, String),
+    AddEffect(usize),
+    /// Removes an effect, by the index of its prerogative and its own.
+    RemoveEffect(usize, usize),
+    EffectStatSelected(usize, usize, StatName),
+    EffectAmountChanged(usize, usize, f32),
+    UniqueToggled(usize, bool),
+    BurdenOnlyToggled(usize, bool),
+    MinimumStatToggled(usize, bool),
+    MinimumStatSelected(usize, StatName),
+    MinimumValueChanged(usize, f32),
+    AddBurden,
+    RemoveBurden(usize),
+    BurdenNameChanged(usize, String),
+    BurdenDescriptionChanged(usize, String),
+    Save,
+}
+
+#[derive(Debug)]
+pub struct Homebrew {
+    /// The saved packs by key, as of the last `refresh` or save.
+    saved: Vec<(String, ContentPack)>,
+    /// Why any saved packs couldn't be loaded.
+    errors: Vec<String>,
+    /// The key of the saved pack being edited, or none for a new one.
+    key: Option<String>,
+    pack: ContentPack,
+    status: Option<String>,
+
+    pack_buttons: Vec<button::State>,
+    new_button: button::State,
+    name_input: text_input::State,
+    description_input: text_input::State,
+    prerogative_editors: Vec<PrerogativeEditor>,
+    burden_editors: Vec<BurdenEditor>,
+    add_prerogative_button: button::State,
+    add_burden_button: button::State,
+    save_button: button::State,
+    scroll: scrollable::State,
+}
+
+#[derive(Debug, Default)]
+struct PrerogativeEditor {
+    name_input: text_input::State,
+    description_input: text_input::State,
+    remove_button: button::State,
+    effects: Vec<EffectEditor>,
+    add_effect_button: button::State,
+    minimum_stat_picker: pick_list::State<StatName>,
+    minimum_value_slider: slider::State,
+}
+
+#[derive(Debug, Default)]
+struct EffectEditor {
+    stat_picker: pick_list::State<StatName>,
+    amount_slider: slider::State,
+    remove_button: button::State,
+}
+
+#[derive(Debug, Default)]
+struct BurdenEditor {
+    name_input: text_input::State,
+    description_input: text_input::State,
+    remove_button: button::State,
+}
+
+impl Default for Homebrew {
+    fn default() -> Self {
+        Self {
+            saved: vec![],
+            errors: vec![],
+            key: None,
+            pack: new_pack(),
+            status: None,
+            pack_buttons: vec![],
+            new_button: Default::default(),
+            name_input: Default::default(),
+            description_input: Default::default(),
+            prerogative_editors: vec![],
+            burden_editors: vec![],
+            add_prerogative_button: Default::default(),
+            add_burden_button: Default::default(),
+            save_button: Default::default(),
+            scroll: Default::default(),
+        }
+    }
+}
+
+fn new_pack() -> ContentPack {
+    ContentPack {
+        name: String::new(),
+        description: String::new(),
+        prerogatives: vec![],
+        burdens: vec![],
+    }
+}
+
+impl Homebrew {
+    /// Whether one of the screen's text inputs has keyboard focus.
+    pub fn is_editing_text(&self) -> bool {
+        let prerogatives = self
+            .prerogative_editors
+            .iter()
+            .flat_map(|editor| [&editor.name_input, &editor.description_input]);
+        let burdens = self
+            .burden_editors
+            .iter()
+            .flat_map(|editor| [&editor.name_input, &editor.description_input]);
+        [&self.name_input, &self.description_input]
+            .into_iter()
+            .chain(prerogatives)
+            .chain(burdens)
+            .any(text_input::State::is_focused)
+    }
+
+    /// Reloads the saved packs, for when the screen is opened.
+    pub fn refresh(&mut self) {
+        let (saved, errors) = content::load_saved();
+        self.saved = saved;
+        self.errors = errors;
+    }
+
+    /// Applies `message`, returning whether the saved packs or the campaign's enabled ones
+    /// changed, so that the content should be reloaded.
+    pub fn update(&mut self, message: Message, campaign: &mut Campaign) -> bool {
+        let pack = &mut self.pack;
+        match message {
+            Message::Open(index) => {
+                if let Some((key, saved)) = self.saved.get(index) {
+                    self.key = Some(key.clone());
+                    self.pack = saved.clone();
+                    self.status = None;
+                }
+            }
+            Message::New => {
+                self.key = None;
+                self.pack = new_pack();
+                self.status = None;
+            }
+            Message::EnabledToggled(index, enabled) => {
+                if let Some((_, saved)) = self.saved.get(index) {
+                    campaign
+                        .packs
+                        .retain(|name| !name.eq_ignore_ascii_case(&saved.name));
+                    if enabled {
+                        campaign.packs.push(saved.name.clone());
+                    }
+                    return true;
+                }
+            }
+            Message::NameChanged(name) => pack.name = name,
+            Message::DescriptionChanged(description) => pack.description = description,
+            Message::AddPrerogative => pack.prerogatives.push(PrerogativeDef {
+                name: String::new(),
+                description: String::new(),
+                effects: vec![],
+                restrictions: vec![],
+            }),
+            Message::RemovePrerogative(index) => {
+                if index < pack.prerogatives.len() {
+                    pack.prerogatives.remove(index);
+                }
+            }
+            Message::PrerogativeNameChanged(index, name) => {
+                if let Some(prerogative) = pack.prerogatives.get_mut(index) {
+                    prerogative.name = name;
+                }
+            }
+            Message::PrerogativeDescriptionChanged(index, description) => {
+                if let Some(prerogative) = pack.prerogatives.get_mut(index) {
+                    prerogative.description = description;
+                }
+            }
+            Message::AddEffect(index) => {
+                if let Some(prerogative) = pack.prerogatives.get_mut(index) {
+                    // the first stat not changed yet, since each can only be changed once
+                    let stat = STATS
+                        .into_iter()
+                        .find(|&stat| prerogative.effects.iter().all(|e| e.stat != stat));
+                    if let Some(stat) = stat {
+                        prerogative.effects.push(Effect { stat, amount: 1 });
+                    }
+                }
+            }
+            Message::RemoveEffect(index, effect) => {
+                if let Some(prerogative) = pack.prerogatives.get_mut(index) {
+                    if effect < prerogative.effects.len() {
+                        prerogative.effects.remove(effect);
+                    }
+                }
+            }
+            Message::EffectStatSelected(index, effect, StatName(stat)) => {
+                // each stat can only be changed once
+                if let Some(prerogative) = pack.prerogatives.get_mut(index) {
+                    let changed = prerogative
+                        .effects
+                        .iter()
+                        .enumerate()
+                        .any(|(other, e)| other != effect && e.stat == stat);
+                    if !changed {
+                        if let Some(effect) = prerogative.effects.get_mut(effect) {
+                            effect.stat = stat;
+                        }
+                    }
+                }
+            }
+            Message::EffectAmountChanged(index, effect, amount) => {
+                // an effect of 0 changes nothing, so the slider skips over it
+                let amount = amount.round() as i8;
+                if amount != 0 {
+                    if let Some(effect) = effect_mut(pack, index, effect) {
+                        effect.amount = amount;
+                    }
+                }
+            }
+            Message::UniqueToggled(index, on) => {
+                set_restriction(pack, index, Restriction::Unique, on);
+            }
+            Message::BurdenOnlyToggled(index, on) => {
+                set_restriction(pack, index, Restriction::BurdenOnly, on);
+            }
+            Message::MinimumStatToggled(index, on) => {
+                set_restriction(pack, index, ANY_MINIMUM_STAT, on);
+            }
+            Message::MinimumStatSelected(index, StatName(stat)) => {
+                if let Some(Restriction::MinimumStat { stat: minimum, .. }) =
+                    minimum_stat_mut(pack, index)
+                {
+                    *minimum = stat;
+                }
+            }
+            Message::MinimumValueChanged(index, value) => {
+                if let Some(Restriction::MinimumStat { value: minimum, .. }) =
+                    minimum_stat_mut(pack, index)
+                {
+                    *minimum = value.round() as u8;
+                }
+            }
+            Message::AddBurden => pack.burdens.push(BurdenDef {
+                name: String::new(),
+                description: String::new(),
+            }),
+            Message::RemoveBurden(index) => {
+                if index < pack.burdens.len() {
+                    pack.burdens.remove(index);
+                }
+            }
+            Message::BurdenNameChanged(index, name) => {
+                if let Some(burden) = pack.burdens.get_mut(index) {
+                    burden.name = name;
+                }
+            }
+            Message::BurdenDescriptionChanged(index, description) => {
+                if let Some(burden) = pack.burdens.get_mut(index) {
+                    burden.description = description;
+                }
+            }
+            Message::Save => match self.save(campaign) {
+                Ok(key) => {
+                    self.status = Some(format!("Saved to {}", key));
+                    return true;
+                }
+                Err(err) => self.status = Some(format!("{:#}", err)),
+            },
+        }
+        false
+    }
+
+    /// Saves the pack being edited, keeping it enabled in the campaign under its new name if it
+    /// was renamed.
+    fn save(&mut self, campaign: &mut Campaign) -> eyre::Result<String> {
+        let key = self.key_to_save(&storage::keys(content::PACKS_FOLDER))?;
+        self.pack.name = self.pack.name.trim().to_owned();
+        let old_name = self
+            .saved
+            .iter()
+            .find(|(saved_key, _)| *saved_key == key)
+            .map(|(_, saved)| saved.name.clone());
+
+        self.pack.save(Some(&key))?;
+        if let Some(old_name) = old_name {
+            rename_enabled(&mut campaign.packs, &old_name, &self.pack.name);
+        }
+        self.key = Some(key.clone());
+        self.refresh();
+        Ok(key)
+    }
+
+    /// The key to save the pack being edited under: its own if it was saved before, and otherwise
+    /// a new one from its name that none of the `taken` keys has. Fails if another saved pack
+    /// already has the name, since packs are enabled by name.
+    fn key_to_save(&self, taken: &[String]) -> eyre::Result<String> {
+        let name = self.pack.name.trim();
+        if let Some((key, _)) = self.saved.iter().find(|(key, saved)| {
+            Some(key) != self.key.as_ref() && saved.name.trim().eq_ignore_ascii_case(name)
+        }) {
+            eyre::bail!("{} is already called {:?}", key, name);
+        }
+        Ok(match &self.key {
+            Some(key) => key.clone(),
+            None => self.pack.new_key(taken),
+        })
+    }
+
+    pub fn view(&mut self, campaign: &Campaign, theme: Theme) -> Element<'_, Message, Renderer> {
+        let typography = theme.typography();
+
+        let mut packs = Column::new()
+            .push(theme.heading("Content packs"))
+            .push(theme.body(format!("Enabled in {}", campaign.name)))
+            .spacing(5)
+            .width(Length::FillPortion(1));
+        self.pack_buttons
+            .resize_with(self.saved.len(), Default::default);
+        for (index, (state, (key, saved))) in
+            self.pack_buttons.iter_mut().zip(&self.saved).enumerate()
+        {
+            let enabled = campaign
+                .packs
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&saved.name));
+            let mut edit = Button::new(state, theme.body("Edit")).style(theme.button());
+            if self.key.as_ref() != Some(key) {
+                edit = edit.on_press(Message::Open(index));
+            }
+            packs = packs.push(
+                Row::new()
+                    .push(
+                        Checkbox::new(enabled, saved.name.as_str(), move |enabled| {
+                            Message::EnabledToggled(index, enabled)
+                        })
+                        .size(typography.body_size)
+                        .text_size(typography.body_size)
+                        .width(Length::Fill),
+                    )
+                    .push(edit)
+                    .spacing(5)
+                    .align_items(iced_winit::Alignment::Center),
+            );
+        }
+        if self.saved.is_empty() {
+            packs = packs.push(theme.body("None saved yet"));
+        }
+        for error in &self.errors {
+            packs = packs.push(
+                theme
+                    .body(format!("Not loaded: {}", error))
+                    .color(theme.palette().danger),
+            );
+        }
+        packs = packs.push(
+            Button::new(&mut self.new_button, theme.body("New pack"))
+                .on_press(Message::New)
+                .style(theme.button()),
+        );
+
+        let mut editor = Scrollable::new(&mut self.scroll)
+            .height(Length::Fill)
+            .spacing(5)
+            .push(theme.heading(match &self.key {
+                Some(key) => format!("Editing {}", key),
+                None => "New pack".to_owned(),
+            }))
+            .push(labelled(
+                theme,
+                "Name",
+                text_input(
+                    theme,
+                    &mut self.name_input,
+                    "Name",
+                    &self.pack.name,
+                    Message::NameChanged,
+                ),
+            ))
+            .push(labelled(
+                theme,
+                "Description",
+                text_input(
+                    theme,
+                    &mut self.description_input,
+                    "Description",
+                    &self.pack.description,
+                    Message::DescriptionChanged,
+                ),
+            ))
+            .push(theme.heading("Prerogatives"));
+
+        self.prerogative_editors
+            .resize_with(self.pack.prerogatives.len(), Default::default);
+        for (index, (widgets, prerogative)) in self
+            .prerogative_editors
+            .iter_mut()
+            .zip(&self.pack.prerogatives)
+            .enumerate()
+        {
+            let mut block = Column::new()
+                .push(
+                    Row::new()
+                        .push(text_input(
+                            theme,
+                            &mut widgets.name_input,
+                            "Name",
+                            &prerogative.name,
+                            move |name| Message::PrerogativeNameChanged(index, name),
+                        ))
+                        .push(
+                            Button::new(&mut widgets.remove_button, theme.body("Remove"))
+                                .on_press(Message::RemovePrerogative(index))
+                                .style(theme.danger_button()),
+                        )
+                        .spacing(5),
+                )
+                .push(text_input(
+                    theme,
+                    &mut widgets.description_input,
+                    "Description",
+                    &prerogative.description,
+                    move |description| Message::PrerogativeDescriptionChanged(index, description),
+                ))
+                .spacing(2)
+                .padding(5);
+
+            widgets
+                .effects
+                .resize_with(prerogative.effects.len(), Default::default);
+            for (effect_index, (effect_widgets, effect)) in widgets
+                .effects
+                .iter_mut()
+                .zip(&prerogative.effects)
+                .enumerate()
+            {
+                let max = f32::from(rules::max_stat(effect.stat));
+                // the stats no other effect of the prerogative changes
+                let stats = STATS.into_iter().filter(|&stat| {
+                    prerogative
+                        .effects
+                        .iter()
+                        .enumerate()
+                        .all(|(other, e)| other == effect_index || e.stat != stat)
+                });
+                block = block.push(
+                    labelled(
+                        theme,
+                        "Effect",
+                        stat_picker(
+                            theme,
+                            &mut effect_widgets.stat_picker,
+                            stats,
+                            effect.stat,
+                            move |stat| Message::EffectStatSelected(index, effect_index, stat),
+                        ),
+                    )
+                    .push(
+                        Slider::new(
+                            &mut effect_widgets.amount_slider,
+                            -max..=max,
+                            f32::from(effect.amount),
+                            move |amount| Message::EffectAmountChanged(index, effect_index, amount),
+                        )
+                        .step(1.0)
+                        .width(Length::Units(160)),
+                    )
+                    .push(theme.body(format!("{:+}", effect.amount)))
+                    .push(
+                        Button::new(&mut effect_widgets.remove_button, theme.body("-"))
+                            .on_press(Message::RemoveEffect(index, effect_index))
+                            .style(theme.button()),
+                    ),
+                );
+            }
+            let mut add_effect =
+                Button::new(&mut widgets.add_effect_button, theme.body("Add effect"))
+                    .style(theme.button());
+            if prerogative.effects.len() < STATS.len() {
+                add_effect = add_effect.on_press(Message::AddEffect(index));
+            }
+            block = block.push(add_effect);
+
+            let has = |kind: Restriction| {
+                prerogative
+                    .restrictions
+                    .iter()
+                    .any(|restriction| restriction.same_kind(kind))
+            };
+            let checkbox = |checked: bool, label: &str, on_toggle: fn(usize, bool) -> Message| {
+                Checkbox::new(checked, label, move |on| on_toggle(index, on))
+                    .size(typography.body_size)
+                    .text_size(typography.body_size)
+            };
+            let mut restrictions = Row::new()
+                .push(checkbox(
+                    has(Restriction::Unique),
+                    "Only once",
+                    Message::UniqueToggled,
+                ))
+                .push(checkbox(
+                    has(Restriction::BurdenOnly),
+                    "Only for a burden",
+                    Message::BurdenOnlyToggled,
+                ))
+                .push(checkbox(
+                    has(ANY_MINIMUM_STAT),
+                    "Minimum base stat",
+                    Message::MinimumStatToggled,
+                ))
+                .spacing(10)
+                .align_items(iced_winit::Alignment::Center);
+            let minimum = prerogative.restrictions.iter().find_map(|r| match *r {
+                Restriction::MinimumStat { stat, value } => Some((stat, value)),
+                _ => None,
+            });
+            if let Some((stat, value)) = minimum {
+                restrictions = restrictions
+                    .push(stat_picker(
+                        theme,
+                        &mut widgets.minimum_stat_picker,
+                        STATS,
+                        stat,
+                        move |stat| Message::MinimumStatSelected(index, stat),
+                    ))
+                    .push(
+                        Slider::new(
+                            &mut widgets.minimum_value_slider,
+                            1.0..=f32::from(rules::max_stat(stat)),
+                            f32::from(value),
+                            move |value| Message::MinimumValueChanged(index, value),
+                        )
+                        .step(1.0)
+                        .width(Length::Units(120)),
+                    )
+                    .push(theme.body(value.to_string()));
+            }
+            editor = editor.push(block.push(restrictions));
+        }
+        editor = editor
+            .push(
+                Button::new(
+                    &mut self.add_prerogative_button,
+                    theme.body("Add prerogative"),
+                )
+                .on_press(Message::AddPrerogative)
+                .style(theme.button()),
+            )
+            .push(theme.heading("Burdens"));
+
+        self.burden_editors
+            .resize_with(self.pack.burdens.len(), Default::default);
+        for (index, (widgets, burden)) in self
+            .burden_editors
+            .iter_mut()
+            .zip(&self.pack.burdens)
+            .enumerate()
+        {
+            editor = editor.push(
+                Column::new()
+                    .push(
+                        Row::new()
+                            .push(text_input(
+                                theme,
+                                &mut widgets.name_input,
+                                "Name",
+                                &burden.name,
+                                move |name| Message::BurdenNameChanged(index, name),
+                            ))
+                            .push(
+                                Button::new(&mut widgets.remove_button, theme.body("Remove"))
+                                    .on_press(Message::RemoveBurden(index))
+                                    .style(theme.danger_button()),
+                            )
+                            .spacing(5),
+                    )
+                    .push(text_input(
+                        theme,
+                        &mut widgets.description_input,
+                        "Description",
+                        &burden.description,
+                        move |description| Message::BurdenDescriptionChanged(index, description),
+                    ))
+                    .spacing(2)
+                    .padding(5),
+            );
+        }
+        editor = editor
+            .push(
+                Button::new(&mut self.add_burden_button, theme.body("Add burden"))
+                    .on_press(Message::AddBurden)
+                    .style(theme.button()),
+            )
+            .push(
+                Row::new()
+                    .push(
+                        Button::new(&mut self.save_button, theme.body("Save"))
+                            .on_press(Message::Save)
+                            .style(theme.button()),
+                    )
+                    .push(theme.body(self.status.as_deref().unwrap_or_default()))
+                    .spacing(10)
+                    .align_items(iced_winit::Alignment::Center),
+            );
+
+        Row::new()
+            .push(packs)
+            .push(Column::new().push(editor).width(Length::FillPortion(3)))
+            .spacing(10)
+            .padding(10)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+fn text_input<'a>(
+    theme: Theme,
+    state: &'a mut text_input::State,
+    placeholder: &str,
+    value: &str,
+    on_change: impl Fn(String) -> Message + 'static,
+) -> TextInput<'a, Message, Renderer> {
+    TextInput::new(state, placeholder, value, on_change)
+        .font(theme.typography().body.into())
+        .size(theme.typography().body_size)
+        .padding(2)
+}
+
+fn stat_picker<'a>(
+    theme: Theme,
+    state: &'a mut pick_list::State<StatName>,
+    stats: impl IntoIterator<Item = Stat>,
+    selected: Stat,
+    on_selected: impl Fn(StatName) -> Message + 'static,
+) -> PickList<'a, StatName, Message, Renderer> {
+    PickList::new(
+        state,
+        stats.into_iter().map(StatName).collect::<Vec<_>>(),
+        Some(StatName(selected)),
+        on_selected,
+    )
+    .font(theme.typography().body.into())
+    .text_size(theme.typography().body_size)
+    .style(theme.pick_list())
+}
+
+/// Has the campaign enable a renamed pack under its new name.
+fn rename_enabled(enabled: &mut [String], old_name: &str, new_name: &str) {
+    for name in enabled {
+        if name.eq_ignore_ascii_case(old_name) {
+            *name = new_name.to_owned();
+        }
+    }
+}
+
+fn effect_mut(pack: &mut ContentPack, index: usize, effect: usize) -> Option<&mut Effect> {
+    pack.prerogatives.get_mut(index)?.effects.get_mut(effect)
+}
+
+fn minimum_stat_mut(pack: &mut ContentPack, index: usize) -> Option<&mut Restriction> {
+    pack.prerogatives
+        .get_mut(index)?
+        .restrictions
+        .iter_mut()
+        .find(|restriction| restriction.same_kind(ANY_MINIMUM_STAT))
+}
+
+/// Adds `restriction` to a prerogative if `on`, or removes any of its kind.
+fn set_restriction(pack: &mut ContentPack, index: usize, restriction: Restriction, on: bool) {
+    if let Some(prerogative) = pack.prerogatives.get_mut(index) {
+        prerogative
+            .restrictions
+            .retain(|existing| !existing.same_kind(restriction));
+        if on {
+            prerogative.restrictions.push(restriction);
+        }
+    }
+}
+
+/// A stat as a pick list option.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StatName(Stat);
+
+impl std::fmt::Display for StatName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(name: &str) -> ContentPack {
+        ContentPack {
+            name: name.into(),
+            ..new_pack()
+        }
+    }
+
+    #[test]
+    fn effects_change_each_stat_once_and_never_by_0() {
+        let (mut homebrew, mut campaign) = (Homebrew::default(), Campaign::default());
+        for message in [
+            Message::AddPrerogative,
+            Message::AddEffect(0),
+            Message::AddEffect(0),
+        ] {
+            homebrew.update(message, &mut campaign);
+        }
+        let first = homebrew.pack.prerogatives[0].effects[0];
+        homebrew.update(
+            Message::EffectStatSelected(0, 1, StatName(first.stat)),
+            &mut campaign,
+        );
+        homebrew.update(Message::EffectAmountChanged(0, 0, 0.2), &mut campaign);
+        let effects = &homebrew.pack.prerogatives[0].effects;
+        assert_eq!(effects[0], first);
+        assert_ne!(effects[1].stat, first.stat);
+
+        homebrew.update(Message::EffectAmountChanged(0, 0, -2.), &mut campaign);
+        assert_eq!(homebrew.pack.prerogatives[0].effects[0].amount, -2);
+    }
+
+    #[test]
+    fn new_packs_get_a_key_no_saved_pack_has() {
+        let mut homebrew = Homebrew {
+            saved: vec![("packs/My_Pack.toml".into(), pack("My Pack"))],
+            pack: pack("My.Pack"),
+            ..Default::default()
+        };
+        let taken: Vec<String> = homebrew.saved.iter().map(|(key, _)| key.clone()).collect();
+        assert_eq!(
+            homebrew.key_to_save(&taken).unwrap(),
+            "packs/My_Pack_2.toml"
+        );
+
+        // packs are enabled by name, so two can't share one
+        homebrew.pack.name = " my pack ".into();
+        let err = homebrew.key_to_save(&taken).unwrap_err();
+        assert!(err.to_string().contains("already called"), "{}", err);
+
+        // unless it's the same pack being saved again
+        homebrew.key = Some("packs/My_Pack.toml".into());
+        assert_eq!(homebrew.key_to_save(&taken).unwrap(), "packs/My_Pack.toml");
+    }
+
+    #[test]
+    fn renamed_packs_stay_enabled() {
+        let mut enabled = vec!["Other".to_owned(), "old name".to_owned()];
+        rename_enabled(&mut enabled, "Old Name", "New Name");
+        assert_eq!(enabled, ["Other", "New Name"]);
+    }
+}
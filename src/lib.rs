@@ -1,38 +1,236 @@
+#[cfg(feature = "gui")]
+mod advancement;
+pub mod archetype;
+pub mod build;
+#[cfg(feature = "gui")]
+pub mod camera_controller;
+pub mod campaign;
+pub mod character;
+#[cfg(feature = "gui")]
 mod charactor_creator;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+pub mod cli;
+pub mod client;
+#[cfg(feature = "gui")]
+pub mod clock;
+#[cfg(feature = "gui")]
+mod compare;
+pub mod comparison;
+pub mod compendium;
+#[cfg(feature = "gui")]
+pub mod config;
+pub mod content;
+#[cfg(feature = "gui")]
+pub mod fonts;
+#[cfg(feature = "gui")]
 mod game;
-#[cfg(target_arch = "wasm32")]
+#[cfg(feature = "gui")]
+mod homebrew;
+pub mod progression;
+pub mod protocol;
+#[cfg(feature = "gui")]
+mod reference;
+pub mod rules;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod server;
+pub mod session_log;
+#[cfg(feature = "gui")]
+mod settings;
+#[cfg(feature = "gui")]
+mod sheet;
+#[cfg(feature = "gui")]
+mod stat_chart;
+mod storage;
+#[cfg(feature = "gui")]
+pub mod theme;
+#[cfg(all(feature = "gui", target_arch = "wasm32"))]
 pub mod web;
+pub mod wizard;
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone)]
 pub enum ApplicationState {
     CharacterCreator,
     Game,
+    Advancement,
+    Reference,
+    Comparison,
+    Homebrew,
+    Settings,
 }
 
-#[derive(Debug, Clone)]
+/// An object in the 3D scene that was clicked.
+#[cfg(feature = "gui")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScenePick {
+    Spirit(spirits_within::Spirit),
+    /// A face of the die, numbered from 0.
+    DieFace(usize),
+}
+
+#[cfg(feature = "gui")]
+impl std::fmt::Display for ScenePick {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenePick::Spirit(spirit) => write!(f, "{:?}", spirit),
+            ScenePick::DieFace(face) => write!(f, "Die face {}", face + 1),
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(Debug)]
 pub struct Application {
+    seed: u64,
     rng: rand::rngs::SmallRng,
     state: ApplicationState,
+    picked: Option<ScenePick>,
+    /// The campaign the character creator and the Game screen work in.
+    campaign: campaign::Campaign,
+    /// The descriptions behind the rules reference and the character creator's tooltips.
+    compendium: compendium::Compendium,
+    /// The character creator's starting points.
+    archetypes: Vec<archetype::Archetype>,
+    /// Why any content packs the campaign enables couldn't be loaded, for the rules reference.
+    content_errors: Vec<String>,
+    /// The campaign's enabled packs as of when the content was last loaded.
+    enabled_packs: Vec<String>,
     game: game::Game,
+    advancement: advancement::Advancement,
+    reference: reference::Reference,
+    compare: compare::Compare,
+    homebrew: homebrew::Homebrew,
     character_creator: charactor_creator::CharacterCreator,
+    settings: settings::Settings,
+    config: config::Config,
 
     game_button: iced_winit::widget::button::State,
+    advancement_button: iced_winit::widget::button::State,
+    reference_button: iced_winit::widget::button::State,
+    compare_button: iced_winit::widget::button::State,
+    homebrew_button: iced_winit::widget::button::State,
     character_creator_button: iced_winit::widget::button::State,
+    settings_button: iced_winit::widget::button::State,
+    theme_picker: iced_winit::widget::pick_list::State<theme::Theme>,
 }
 
+#[cfg(feature = "gui")]
 impl Application {
-    pub fn new(seed: u64) -> Self {
-        Self {
+    pub fn new(seed: u64, config: config::Config) -> Self {
+        let campaign = campaign::Campaign::load(&config.campaign).unwrap_or_else(|err| {
+            log::warn!("starting a new campaign: {:?}", err);
+            campaign::Campaign::new(config.campaign.as_str())
+        });
+        let mut application = Self {
+            seed,
             rng: rand::SeedableRng::seed_from_u64(seed),
             state: ApplicationState::CharacterCreator,
+            picked: None,
+            campaign,
+            compendium: compendium::Compendium::load(),
+            archetypes: archetype::Archetype::load(),
+            content_errors: vec![],
+            enabled_packs: vec![],
             game: Default::default(),
+            advancement: Default::default(),
+            reference: Default::default(),
+            compare: Default::default(),
+            homebrew: Default::default(),
             character_creator: Default::default(),
+            settings: Default::default(),
+            config,
             game_button: Default::default(),
+            advancement_button: Default::default(),
+            reference_button: Default::default(),
+            compare_button: Default::default(),
+            homebrew_button: Default::default(),
             character_creator_button: Default::default(),
+            settings_button: Default::default(),
+            theme_picker: Default::default(),
+        };
+        application.load_content();
+        application
+    }
+
+    pub fn theme(&self) -> theme::Theme {
+        self.config.theme
+    }
+
+    pub fn config(&self) -> &config::Config {
+        &self.config
+    }
+
+    /// Loads `character` into the character creator.
+    pub fn open(&mut self, character: &character::Character) {
+        self.character_creator.load(character);
+    }
+
+    /// Makes `campaign` the one being worked in, saving it.
+    pub fn open_campaign(&mut self, campaign: campaign::Campaign) {
+        self.campaign = campaign;
+        self.save_campaign();
+    }
+
+    /// Saves the campaign and, unless it has its own file, remembers to open it next time.
+    fn save_campaign(&mut self) {
+        if let Err(err) = self.campaign.save() {
+            log::warn!("failed to save the campaign: {:?}", err);
+        }
+        if !self.campaign.is_file() && self.config.campaign != self.campaign.name {
+            self.config.campaign = self.campaign.name.clone();
+            self.config.save();
         }
+        if self.campaign.packs != self.enabled_packs {
+            self.load_content();
+        }
+    }
+
+    /// Makes the core rules with the saved packs the campaign enables the active content.
+    fn load_content(&mut self) {
+        let (content, errors) = content::load_enabled(&self.campaign.packs);
+        content::set_active(content);
+        self.content_errors = errors;
+        self.enabled_packs = self.campaign.packs.clone();
+    }
+
+    pub fn set_state(&mut self, state: ApplicationState) {
+        if matches!(state, ApplicationState::Homebrew) {
+            self.homebrew.refresh();
+        }
+        self.state = state;
+    }
+
+    /// Messages for whatever happened at the shared table since the last call, for the event loop
+    /// to queue.
+    pub fn session_messages(&self) -> Vec<Message> {
+        self.game
+            .poll()
+            .into_iter()
+            .map(|event| Message::Game(game::Message::Session(event)))
+            .collect()
+    }
+
+    /// Whether a text input on the current screen has keyboard focus, so that key presses are
+    /// typing rather than the scene's controls.
+    pub fn is_editing_text(&self) -> bool {
+        match self.state {
+            ApplicationState::CharacterCreator => self.character_creator.is_editing_text(),
+            ApplicationState::Game => self.game.is_editing_text(),
+            ApplicationState::Advancement => self.advancement.is_editing_text(),
+            ApplicationState::Reference => self.reference.is_editing_text(),
+            ApplicationState::Homebrew => self.homebrew.is_editing_text(),
+            ApplicationState::Settings => self.settings.is_editing_text(),
+            _ => false,
+        }
+    }
+
+    /// The spirits of the character being built, for the renderer to visualize.
+    pub fn constellation(&self) -> Vec<renderer::SpiritNode> {
+        self.character_creator.constellation()
     }
 }
 
+#[cfg(feature = "gui")]
 impl iced_winit::Program for Application {
     type Renderer = iced_solstice::Renderer;
     type Message = Message;
@@ -45,10 +243,72 @@ impl iced_winit::Program for Application {
             Message::TransitionGame => {
                 self.state = ApplicationState::Game;
             }
+            Message::TransitionAdvancement => {
+                self.state = ApplicationState::Advancement;
+            }
+            Message::TransitionReference => {
+                self.state = ApplicationState::Reference;
+            }
+            Message::TransitionComparison => {
+                self.state = ApplicationState::Comparison;
+            }
+            Message::TransitionHomebrew => {
+                self.homebrew.refresh();
+                self.state = ApplicationState::Homebrew;
+            }
+            Message::TransitionSettings => {
+                self.state = ApplicationState::Settings;
+            }
             Message::CharactorCreator(message) => {
-                self.character_creator.update(message, &mut self.rng);
+                if self
+                    .character_creator
+                    .update(message, &mut self.rng, &mut self.campaign)
+                {
+                    self.save_campaign();
+                }
+            }
+            Message::Game(message) => {
+                if self.game.update(
+                    message,
+                    &self.character_creator.character(),
+                    &mut self.campaign,
+                ) {
+                    self.save_campaign();
+                }
+            }
+            Message::Advancement(message) => {
+                if self.advancement.update(message, &mut self.campaign) {
+                    self.save_campaign();
+                }
+            }
+            Message::Reference(message) => self.reference.update(message),
+            Message::Compare(message) => self.compare.update(message),
+            Message::Homebrew(message) => {
+                if self.homebrew.update(message, &mut self.campaign) {
+                    self.save_campaign();
+                    // a saved pack may have changed without the enabled ones changing
+                    self.load_content();
+                }
+            }
+            Message::CampaignOpened(campaign) => self.open_campaign(campaign),
+            Message::Settings(message) => {
+                if self.settings.update(message, &mut self.config, self.seed) {
+                    self.config.save();
+                }
+            }
+            Message::ScenePicked(pick) => {
+                self.picked = match pick {
+                    renderer::Pick::Spirit(index) => self
+                        .character_creator
+                        .spirit_at(index)
+                        .map(ScenePick::Spirit),
+                    renderer::Pick::DieFace(face) => Some(ScenePick::DieFace(face)),
+                };
+            }
+            Message::ThemeSelected(theme) => {
+                self.config.theme = theme;
+                self.config.save();
             }
-            Message::Game(message) => self.game.update(message),
         }
 
         iced_winit::Command::none()
@@ -56,57 +316,183 @@ impl iced_winit::Program for Application {
 
     fn view(&mut self) -> iced_winit::Element<'_, Self::Message, Self::Renderer> {
         use iced_winit::{
-            widget::{Button, Column, Row, Text},
+            widget::{pick_list::PickList, Button, Column, Container, Row},
             Length,
         };
+        let theme = self.config.theme;
         let content = match &mut self.state {
-            ApplicationState::CharacterCreator => self.character_creator.view().map(Into::into),
-            ApplicationState::Game => self.game.view().map(Into::into),
+            ApplicationState::CharacterCreator => self
+                .character_creator
+                .view(&self.campaign, &self.compendium, &self.archetypes, theme)
+                .map(Into::into),
+            ApplicationState::Game => self
+                .game
+                .view(&self.campaign, &self.character_creator.character(), theme)
+                .map(Into::into),
+            ApplicationState::Advancement => {
+                self.advancement.view(&self.campaign, theme).map(Into::into)
+            }
+            ApplicationState::Reference => self
+                .reference
+                .view(&self.compendium, &self.content_errors, theme)
+                .map(Into::into),
+            ApplicationState::Comparison => self
+                .compare
+                .view(&self.campaign, &self.character_creator.character(), theme)
+                .map(Into::into),
+            ApplicationState::Homebrew => self.homebrew.view(&self.campaign, theme).map(Into::into),
+            ApplicationState::Settings => self.settings.view(&self.config).map(Into::into),
         };
-        Column::new()
+        let root = Column::new()
             .push(
-                iced_winit::widget::Container::new(
+                Container::new(
                     Row::new()
                         .push(iced_winit::widget::Space::with_width(Length::Units(2)))
                         .push(
-                            Button::new(&mut self.game_button, Text::new("Game"))
-                                .on_press(Message::TransitionGame),
+                            Button::new(&mut self.game_button, theme.body("Game"))
+                                .on_press(Message::TransitionGame)
+                                .style(theme.button()),
                         )
                         .push(
                             Button::new(
                                 &mut self.character_creator_button,
-                                Text::new("Character Creator"),
+                                theme.body("Character Creator"),
+                            )
+                            .on_press(Message::TransitionCharacterCreator)
+                            .style(theme.button()),
+                        )
+                        .push(
+                            Button::new(&mut self.advancement_button, theme.body("Advancement"))
+                                .on_press(Message::TransitionAdvancement)
+                                .style(theme.button()),
+                        )
+                        .push(
+                            Button::new(&mut self.reference_button, theme.body("Rules"))
+                                .on_press(Message::TransitionReference)
+                                .style(theme.button()),
+                        )
+                        .push(
+                            Button::new(&mut self.compare_button, theme.body("Compare"))
+                                .on_press(Message::TransitionComparison)
+                                .style(theme.button()),
+                        )
+                        .push(
+                            Button::new(&mut self.homebrew_button, theme.body("Homebrew"))
+                                .on_press(Message::TransitionHomebrew)
+                                .style(theme.button()),
+                        )
+                        .push(
+                            Button::new(&mut self.settings_button, theme.body("Settings"))
+                                .on_press(Message::TransitionSettings)
+                                .style(theme.button()),
+                        )
+                        .push(
+                            theme
+                                .body(
+                                    self.picked
+                                        .map(|picked| picked.to_string())
+                                        .unwrap_or_default(),
+                                )
+                                .width(Length::Fill),
+                        )
+                        .push(
+                            PickList::new(
+                                &mut self.theme_picker,
+                                &theme::Theme::ALL[..],
+                                Some(theme),
+                                Message::ThemeSelected,
                             )
-                            .on_press(Message::TransitionCharacterCreator),
+                            .font(theme.typography().body.into())
+                            .text_size(theme.typography().body_size)
+                            .style(theme.pick_list()),
                         )
+                        .align_items(iced_winit::Alignment::Center)
                         .spacing(2),
                 )
-                .width(Length::Fill),
+                .width(Length::Fill)
+                .style(theme.toolbar()),
             )
             .push(content)
             .width(Length::Fill)
             .height(Length::Fill)
-            .spacing(2)
+            .spacing(2);
+        Container::new(root)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme.root())
             .into()
     }
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone)]
 pub enum Message {
     TransitionCharacterCreator,
     TransitionGame,
+    TransitionAdvancement,
+    TransitionReference,
+    TransitionComparison,
+    TransitionHomebrew,
+    TransitionSettings,
     CharactorCreator(charactor_creator::Message),
     Game(game::Message),
+    Advancement(advancement::Message),
+    Reference(reference::Message),
+    Compare(compare::Message),
+    Homebrew(homebrew::Message),
+    Settings(settings::Message),
+    /// An object in the 3D scene was clicked.
+    ScenePicked(renderer::Pick),
+    /// A campaign was imported and replaces the current one.
+    CampaignOpened(campaign::Campaign),
+    ThemeSelected(theme::Theme),
 }
 
+#[cfg(feature = "gui")]
 impl From<charactor_creator::Message> for Message {
     fn from(inner: charactor_creator::Message) -> Self {
         Message::CharactorCreator(inner)
     }
 }
 
+#[cfg(feature = "gui")]
 impl From<game::Message> for Message {
     fn from(inner: game::Message) -> Self {
         Message::Game(inner)
     }
 }
+
+#[cfg(feature = "gui")]
+impl From<advancement::Message> for Message {
+    fn from(inner: advancement::Message) -> Self {
+        Message::Advancement(inner)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<reference::Message> for Message {
+    fn from(inner: reference::Message) -> Self {
+        Message::Reference(inner)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<compare::Message> for Message {
+    fn from(inner: compare::Message) -> Self {
+        Message::Compare(inner)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<homebrew::Message> for Message {
+    fn from(inner: homebrew::Message) -> Self {
+        Message::Homebrew(inner)
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<settings::Message> for Message {
+    fn from(inner: settings::Message) -> Self {
+        Message::Settings(inner)
+    }
+}
@@ -0,0 +1,318 @@
+//! What happens to a character after creation: a history of advances, each checked against the
+//! same rules as creation, from which the character at any point in its timeline can be rebuilt.
+
+use crate::{
+    character::Character,
+    content::{BurdenId, PrerogativeId},
+    rules,
+};
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use spirits_within::Spirit;
+
+/// A change to a character after creation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    /// Gives `spirit` the connection of `swap_with` and `swap_with` the old connection of `spirit`,
+    /// so that each connection is still held by as many spirits as at creation.
+    ShiftConnection {
+        #[serde(with = "crate::character::by_name")]
+        spirit: Spirit,
+        #[serde(with = "crate::character::by_name")]
+        swap_with: Spirit,
+    },
+    /// Takes on an optional burden in exchange for `prerogative`.
+    GainBurden {
+        #[serde(with = "crate::character::by_name")]
+        burden: BurdenId,
+        #[serde(with = "crate::character::by_name")]
+        prerogative: PrerogativeId,
+    },
+    /// Gives up the most recently gained `burden` along with the prerogative taken for it.
+    BuyOffBurden {
+        #[serde(with = "crate::character::by_name")]
+        burden: BurdenId,
+    },
+    /// Replaces the base prerogative in `slot`.
+    ChangePrerogative {
+        slot: usize,
+        #[serde(with = "crate::character::by_name")]
+        prerogative: PrerogativeId,
+    },
+}
+
+impl Change {
+    /// Applies the change to `character`, failing if it doesn't fit the character. The result
+    /// may still break the rules.
+    fn apply(&self, character: &mut Character) -> eyre::Result<()> {
+        match *self {
+            Change::ShiftConnection { spirit, swap_with } => {
+                let position = |spirit: Spirit| {
+                    character
+                        .spirits
+                        .iter()
+                        .position(|(s, _)| *s == spirit)
+                        .ok_or_else(|| eyre::eyre!("{:?} has no connection", spirit))
+                };
+                let (a, b) = (position(spirit)?, position(swap_with)?);
+                if character.spirits[a].1 == character.spirits[b].1 {
+                    eyre::bail!(
+                        "{:?} and {:?} already have the same connection",
+                        spirit,
+                        swap_with
+                    );
+                }
+                let connection = character.spirits[a].1;
+                character.spirits[a].1 = character.spirits[b].1;
+                character.spirits[b].1 = connection;
+            }
+            Change::GainBurden {
+                ref burden,
+                ref prerogative,
+            } => character
+                .burdens
+                .push((burden.clone(), prerogative.clone())),
+            Change::BuyOffBurden { ref burden } => {
+                let index = character
+                    .burdens
+                    .iter()
+                    .rposition(|(b, _)| b == burden)
+                    .ok_or_else(|| eyre::eyre!("there is no {} to buy off", burden))?;
+                character.burdens.remove(index);
+            }
+            Change::ChangePrerogative {
+                slot,
+                ref prerogative,
+            } => {
+                let current = character
+                    .prerogatives
+                    .get_mut(slot)
+                    .ok_or_else(|| eyre::eyre!("there is no base prerogative {}", slot + 1))?;
+                if current.as_ref() == Some(prerogative) {
+                    eyre::bail!("base prerogative {} is already {}", slot + 1, prerogative);
+                }
+                *current = Some(prerogative.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::ShiftConnection { spirit, swap_with } => write!(
+                f,
+                "swap the connections of {:?} and {:?}",
+                spirit, swap_with
+            ),
+            Change::GainBurden {
+                burden,
+                prerogative,
+            } => write!(f, "take on {} for {}", burden, prerogative),
+            Change::BuyOffBurden { burden } => write!(f, "buy off {}", burden),
+            Change::ChangePrerogative { slot, prerogative } => {
+                write!(f, "take {} as base prerogative {}", prerogative, slot + 1)
+            }
+        }
+    }
+}
+
+/// A change and when it was made.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Advance {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    #[serde(flatten)]
+    pub change: Change,
+    #[serde(default)]
+    pub notes: String,
+}
+
+/// A character as created and everything that has happened to it since.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Progression {
+    /// The character as created.
+    pub character: Character,
+    /// Oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub advances: Vec<Advance>,
+}
+
+impl Progression {
+    pub fn new(character: Character) -> Self {
+        Self {
+            character,
+            advances: vec![],
+        }
+    }
+
+    /// The character after the first `step` advances, or as created for step 0.
+    pub fn at(&self, step: usize) -> eyre::Result<Character> {
+        let mut character = self.character.clone();
+        for (index, advance) in self.advances.iter().take(step).enumerate() {
+            advance
+                .change
+                .apply(&mut character)
+                .wrap_err_with(|| format!("advance {}", index + 1))?;
+        }
+        Ok(character)
+    }
+
+    /// The character after every advance.
+    pub fn current(&self) -> eyre::Result<Character> {
+        self.at(self.advances.len())
+    }
+
+    /// Records `change`, unless the character it leads to would break the rules, such as by
+    /// raising a stat past its maximum or taking on too many burdens.
+    pub fn advance(&mut self, change: Change, notes: &str) -> eyre::Result<()> {
+        let mut character = self.current()?;
+        change
+            .apply(&mut character)
+            .and_then(|()| rules::stats(&character).map(drop))
+            .wrap_err_with(|| format!("can't {}", change))?;
+        self.advances.push(Advance {
+            time: crate::session_log::now(),
+            change,
+            notes: notes.trim().to_owned(),
+        });
+        Ok(())
+    }
+
+    /// Forgets the most recent advance.
+    pub fn undo(&mut self) -> Option<Advance> {
+        self.advances.pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spirits_within::{Burden, Connection, Prerogative};
+
+    /// A prerogative that raises no stat.
+    fn neutral() -> PrerogativeId {
+        Prerogative::LIST
+            .into_iter()
+            .map(PrerogativeId::from)
+            .find(|p| rules::prerogative_stat(p).is_none())
+            .unwrap()
+    }
+
+    /// A finished character with room to grow.
+    fn progression() -> Progression {
+        let mut rng: rand::rngs::SmallRng = rand::SeedableRng::seed_from_u64(0);
+        let mut character = rules::random(&mut rng);
+        character.prerogatives = [(); 4].map(|()| Some(neutral()));
+        character.burdens.clear();
+        assert!(rules::stats(&character).is_ok());
+        Progression::new(character)
+    }
+
+    fn connection(character: &Character, spirit: Spirit) -> Connection {
+        character
+            .spirits
+            .iter()
+            .find(|(s, _)| *s == spirit)
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn the_timeline_replays_advances() {
+        let mut progression = progression();
+        let created = progression.character.clone();
+        let (spirit, swap_with) = {
+            let (a, b) = created
+                .spirits
+                .iter()
+                .zip(&created.spirits[1..])
+                .find(|(a, b)| a.1 != b.1)
+                .unwrap();
+            (a.0, b.0)
+        };
+        progression
+            .advance(Change::ShiftConnection { spirit, swap_with }, "")
+            .unwrap();
+        progression
+            .advance(
+                Change::GainBurden {
+                    burden: Burden::LIST[0].into(),
+                    prerogative: neutral(),
+                },
+                "a favor owed",
+            )
+            .unwrap();
+
+        assert_eq!(progression.at(0).unwrap(), created);
+        let shifted = progression.at(1).unwrap();
+        assert_eq!(
+            connection(&shifted, spirit),
+            connection(&created, swap_with)
+        );
+        assert_eq!(
+            connection(&shifted, swap_with),
+            connection(&created, spirit)
+        );
+        assert_eq!(progression.current().unwrap().burdens.len(), 1);
+
+        progression.undo();
+        assert_eq!(progression.current().unwrap(), shifted);
+    }
+
+    #[test]
+    fn advances_keep_to_the_caps() {
+        let mut progression = progression();
+        // each of these raises Knowledge by 3, which only fits so many times
+        let changes = (0..rules::BASE_PREROGATIVE_COUNT)
+            .map(|slot| Change::ChangePrerogative {
+                slot,
+                prerogative: Prerogative::Education.into(),
+            })
+            .chain(
+                (0..rules::MAX_OPTIONAL_BURDENS).map(|_| Change::GainBurden {
+                    burden: Burden::LIST[0].into(),
+                    prerogative: Prerogative::Education.into(),
+                }),
+            );
+        let results: Vec<bool> = changes
+            .map(|change| progression.advance(change, "").is_ok())
+            .collect();
+        assert!(results.contains(&false), "{:?}", results);
+        // only the ones that fit were recorded
+        let recorded = results.iter().filter(|ok| **ok).count();
+        assert_eq!(progression.advances.len(), recorded);
+        assert!(rules::stats(&progression.current().unwrap()).is_ok());
+
+        assert!(progression
+            .advance(
+                Change::BuyOffBurden {
+                    burden: Burden::LIST[1].into()
+                },
+                ""
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn advances_round_trip_through_json() {
+        let mut progression = progression();
+        progression
+            .advance(
+                Change::GainBurden {
+                    burden: Burden::LIST[0].into(),
+                    prerogative: neutral(),
+                },
+                "",
+            )
+            .unwrap();
+        let json = serde_json::to_string(&progression).unwrap();
+        assert!(json.contains(r#""change":"gain_burden""#), "{}", json);
+        assert_eq!(
+            serde_json::from_str::<Progression>(&json).unwrap(),
+            progression
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! The messages of a shared table, exchanged as JSON text frames over a WebSocket.
+//!
+//! A client opens with [`ClientMessage::Hello`]. The server answers with
+//! [`ServerMessage::Welcome`], or with [`ServerMessage::Rejected`] before closing the connection
+//! if it speaks a different [`VERSION`] or its name is blank or longer than [`MAX_NAME_CHARS`].
+//! After that either side may send at any time: the server
+//! relays everything that happens at the table to every player, including whoever caused it, so
+//! clients only ever show what the server has confirmed.
+//!
+//! Messages are objects tagged by `type`, for example
+//!
+//! ```json
+//! {"type":"hello","version":1,"name":"Ada"}
+//! {"type":"welcome","version":1,"you":1,"players":[{"id":1,"name":"Ada","character":null}]}
+//! {"type":"roll","dice":2,"spirit":"Shadows"}
+//! {"type":"rolled","player":1,"spirit":"Shadows","results":[7,12]}
+//! {"type":"group_check","stat":"Knowledge","dice":1}
+//! {"type":"group_check","player":1,"stat":"Knowledge","rolls":[{"player":1,"results":[4]}]}
+//! ```
+//!
+//! Characters take the same form as in character files, and stats are named as in them.
+
+use crate::character::Character;
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use spirits_within::{Spirit, Stat};
+
+/// The protocol version, raised whenever a message is added, removed or changes shape.
+pub const VERSION: u32 = 1;
+/// The port servers listen on unless told otherwise.
+pub const DEFAULT_PORT: u16 = 7878;
+/// Every die at the table is a d12.
+pub const DIE_SIDES: u8 = 12;
+/// The most dice a single roll can have.
+pub const MAX_DICE: u8 = 10;
+/// The longest name a player can join with, in characters, not counting surrounding whitespace.
+pub const MAX_NAME_CHARS: usize = 40;
+
+/// Identifies a player for as long as they are connected.
+pub type PlayerId = u32;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Player {
+    pub id: PlayerId,
+    pub name: String,
+    /// The character the player last shared.
+    pub character: Option<Character>,
+}
+
+/// One player's dice in a group check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerRoll {
+    pub player: PlayerId,
+    pub results: Vec<u8>,
+}
+
+/// Sent by clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Joins the table. Must be the first message.
+    Hello { version: u32, name: String },
+    /// Shows a character to the table, replacing any shared before.
+    ShareCharacter { character: Character },
+    /// Asks the server to roll `dice` d12s, for `spirit` if the roll is for one.
+    Roll {
+        dice: u8,
+        #[serde(default, with = "crate::character::optional_spirit_name")]
+        spirit: Option<Spirit>,
+    },
+    /// Asks the server to roll `dice` d12s for everyone at the table against `stat`.
+    ///
+    /// Any player can call one: the server doesn't know who runs the game, so the Game Master
+    /// dashboard being the only place that sends these is a convention of the table, not a
+    /// permission.
+    GroupCheck {
+        #[serde(with = "crate::character::stat_name")]
+        stat: Stat,
+        dice: u8,
+    },
+}
+
+/// Sent by the server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// The client joined as `you`; `players` is everyone at the table, the client included.
+    Welcome {
+        version: u32,
+        you: PlayerId,
+        players: Vec<Player>,
+    },
+    /// The client can't join. The connection closes after this.
+    Rejected {
+        reason: String,
+    },
+    Joined {
+        player: Player,
+    },
+    Left {
+        player: PlayerId,
+    },
+    CharacterShared {
+        player: PlayerId,
+        character: Character,
+    },
+    Rolled {
+        player: PlayerId,
+        #[serde(default, with = "crate::character::optional_spirit_name")]
+        spirit: Option<Spirit>,
+        results: Vec<u8>,
+    },
+    /// `player` called a check of `stat`, rolled for everyone at the table.
+    GroupCheck {
+        player: PlayerId,
+        #[serde(with = "crate::character::stat_name")]
+        stat: Stat,
+        rolls: Vec<PlayerRoll>,
+    },
+    /// The client sent something the server couldn't act on. The connection stays open.
+    Error {
+        reason: String,
+    },
+}
+
+impl ClientMessage {
+    pub fn from_json(contents: &str) -> eyre::Result<Self> {
+        serde_json::from_str(contents).wrap_err("parsing client message")
+    }
+
+    pub fn to_json(&self) -> eyre::Result<String> {
+        serde_json::to_string(self).wrap_err("serializing client message")
+    }
+}
+
+impl ServerMessage {
+    pub fn from_json(contents: &str) -> eyre::Result<Self> {
+        serde_json::from_str(contents).wrap_err("parsing server message")
+    }
+
+    pub fn to_json(&self) -> eyre::Result<String> {
+        serde_json::to_string(self).wrap_err("serializing server message")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_match_the_documented_form() {
+        assert_eq!(
+            ClientMessage::Hello {
+                version: VERSION,
+                name: "Ada".into()
+            }
+            .to_json()
+            .unwrap(),
+            r#"{"type":"hello","version":1,"name":"Ada"}"#
+        );
+        assert_eq!(
+            ServerMessage::from_json(
+                r#"{"type":"rolled","player":1,"spirit":"Shadows","results":[7,12]}"#
+            )
+            .unwrap(),
+            ServerMessage::Rolled {
+                player: 1,
+                spirit: Some(Spirit::Shadows),
+                results: vec![7, 12]
+            }
+        );
+        assert_eq!(
+            ClientMessage::from_json(r#"{"type":"group_check","stat":"Knowledge","dice":1}"#)
+                .unwrap(),
+            ClientMessage::GroupCheck {
+                stat: Stat::Knowledge,
+                dice: 1
+            }
+        );
+    }
+
+    #[test]
+    fn characters_round_trip() {
+        let mut rng: rand::rngs::SmallRng = rand::SeedableRng::seed_from_u64(3);
+        let message = ServerMessage::CharacterShared {
+            player: 2,
+            character: crate::rules::random(&mut rng),
+        };
+        let json = message.to_json().unwrap();
+        assert_eq!(ServerMessage::from_json(&json).unwrap(), message);
+    }
+
+    #[test]
+    fn unknown_messages_are_errors() {
+        assert!(ClientMessage::from_json(r#"{"type":"shout"}"#).is_err());
+        assert!(ClientMessage::from_json("not json").is_err());
+    }
+}
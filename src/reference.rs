@@ -0,0 +1,112 @@
+//! The rules reference: everything the compendium describes, searchable, and the content packs
+//! in play.
+
+use crate::{
+    compendium::{Compendium, Section},
+    content,
+    theme::Theme,
+};
+use iced_winit::{
+    widget::{scrollable, text_input, Column, Row, Scrollable, TextInput},
+    Element, Length,
+};
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    QueryChanged(String),
+}
+
+#[derive(Debug, Default)]
+pub struct Reference {
+    query: String,
+
+    query_input: text_input::State,
+    scroll: scrollable::State,
+}
+
+impl Reference {
+    /// Whether one of the screen's text inputs has keyboard focus.
+    pub fn is_editing_text(&self) -> bool {
+        self.query_input.is_focused()
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::QueryChanged(query) => self.query = query,
+        }
+    }
+
+    pub fn view(
+        &mut self,
+        compendium: &Compendium,
+        content_errors: &[String],
+        theme: Theme,
+    ) -> Element<'_, Message, Renderer> {
+        let typography = theme.typography();
+        let palette = theme.palette();
+        let entries = compendium.search(&self.query);
+
+        let mut list = Scrollable::new(&mut self.scroll)
+            .height(Length::Fill)
+            .spacing(5)
+            .push(theme.body(format!(
+                "Content packs: {}",
+                content::active().packs.join(", ")
+            )));
+        for error in content_errors {
+            list = list.push(
+                theme
+                    .body(format!("Not loaded: {}", error))
+                    .color(palette.danger),
+            );
+        }
+        if entries.is_empty() {
+            list = list.push(theme.body(format!("Nothing mentions {:?}", self.query.trim())));
+        }
+        for section in Section::ALL {
+            let mut in_section = entries
+                .iter()
+                .filter(|entry| entry.section == section)
+                .peekable();
+            if in_section.peek().is_none() {
+                continue;
+            }
+            list = list.push(theme.heading(section.to_string()));
+            for entry in in_section {
+                let mut name = Row::new()
+                    .push(theme.body(entry.name.as_str()).color(palette.accent))
+                    .spacing(10);
+                if let Some(stat) = entry.stat {
+                    name = name.push(theme.body(format!("{:?}", stat)));
+                }
+                list = list.push(
+                    Column::new()
+                        .push(name)
+                        .push(theme.body(entry.text.as_str()))
+                        .spacing(2),
+                );
+            }
+        }
+
+        Column::new()
+            .push(
+                TextInput::new(
+                    &mut self.query_input,
+                    "Search the rules",
+                    &self.query,
+                    Message::QueryChanged,
+                )
+                .font(typography.body.into())
+                .size(typography.body_size)
+                .padding(2)
+                .width(Length::Units(320)),
+            )
+            .push(list)
+            .spacing(10)
+            .padding(10)
+            .width(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,388 @@
+//! The character creation rules, independent of any front end.
+
+use crate::{
+    character::Character,
+    content::{self, BurdenId, PrerogativeId},
+};
+use spirits_within::{
+    BaseStats, Connection, PrerogativesAndBurdens, Spirit, SpiritSelection, Stat, Stats,
+};
+
+/// How many base prerogatives a character has.
+pub const BASE_PREROGATIVE_COUNT: usize = 4;
+/// How many optional burdens a character can take on.
+pub const MAX_OPTIONAL_BURDENS: usize = 4;
+
+/// The stats in the order the character creator groups spirits by them.
+pub const STATS: [Stat; 3] = [Stat::Proficiency, Stat::Knowledge, Stat::Discipline];
+
+/// Each connection, strongest first, with how many spirits a character has it with.
+pub const CONNECTION_COUNTS: [(Connection, usize); 4] = [
+    (Connection::Mastery, SpiritSelection::MASTERY_COUNT),
+    (Connection::Expertise, SpiritSelection::EXPERTISE_COUNT),
+    (Connection::Competence, SpiritSelection::COMPETENCE_COUNT),
+    (Connection::Ineptitude, SpiritSelection::INEPTITUDE_COUNT),
+];
+
+/// The maximum of `stat`.
+pub fn max_stat(stat: Stat) -> u8 {
+    match stat {
+        Stat::Proficiency => Stats::MAX_PROFICIENCY,
+        Stat::Knowledge => Stats::MAX_KNOWLEDGE,
+        Stat::Discipline => Stats::MAX_DISCIPLINE,
+    }
+}
+
+/// The value of `stat` in `stats`, to change.
+pub fn base_stat(stats: &mut BaseStats, stat: Stat) -> &mut u8 {
+    match stat {
+        Stat::Proficiency => &mut stats.proficiency,
+        Stat::Knowledge => &mut stats.knowledge,
+        Stat::Discipline => &mut stats.discipline,
+    }
+}
+
+/// The stat a prerogative raises, if any: the first its effects in the active content raise.
+pub fn prerogative_stat(prerogative: &PrerogativeId) -> Option<Stat> {
+    content::active()
+        .effects(prerogative)
+        .iter()
+        .find(|effect| effect.amount > 0)
+        .map(|effect| effect.stat)
+}
+
+/// What an optional burden means at the table. It bears on checks of the stat raised by the
+/// prerogative taken for it, if any.
+pub fn burden_text(burden: &BurdenId, prerogative: &PrerogativeId) -> String {
+    let content = content::active();
+    let effects: Vec<String> = content
+        .effects(prerogative)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    match prerogative_stat(prerogative) {
+        Some(stat) => format!(
+            "{} was taken on in exchange for {}, which gives {}. Bring it into play when a {:?} \
+             check leans on what {} gives.",
+            burden,
+            prerogative,
+            effects.join(", "),
+            stat,
+            prerogative
+        ),
+        None => format!(
+            "{} was taken on in exchange for {}. Bring it into play when the story leans on \
+             what {} gives.",
+            burden, prerogative, prerogative
+        ),
+    }
+}
+
+/// `stats` with the effects of `prerogatives` in the active content applied, ignoring the stats'
+/// maximums.
+pub fn with_prerogative_bonuses(
+    mut stats: BaseStats,
+    prerogatives: impl IntoIterator<Item = PrerogativeId>,
+) -> BaseStats {
+    let content = content::active();
+    for prerogative in prerogatives {
+        for effect in content.effects(&prerogative) {
+            let value = base_stat(&mut stats, effect.stat);
+            *value = (i16::from(*value) + i16::from(effect.amount)).clamp(0, u8::MAX.into()) as u8;
+        }
+    }
+    stats
+}
+
+/// Whether `prerogative` can still be taken without raising a stat of `stats` past its maximum.
+pub fn prerogative_allowed(mut stats: BaseStats, prerogative: &PrerogativeId) -> bool {
+    content::active()
+        .effects(prerogative)
+        .iter()
+        .filter(|effect| effect.amount > 0)
+        .all(|effect| *base_stat(&mut stats, effect.stat) < max_stat(effect.stat))
+}
+
+/// Whether the restrictions of `prerogative` let it be taken again by a character with `base`
+/// stats, if they are known yet, that already has it `taken` times. It is taken in exchange for
+/// an optional burden if `for_burden` is set, and as a base prerogative otherwise.
+pub fn restrictions_allow(
+    prerogative: &PrerogativeId,
+    base: Option<BaseStats>,
+    taken: usize,
+    for_burden: bool,
+) -> eyre::Result<()> {
+    content::active().check_restrictions(prerogative, base, taken, for_burden)
+}
+
+/// The value of `stat` in `stats`.
+pub fn stat_value(stats: &Stats, stat: Stat) -> u8 {
+    match stat {
+        Stat::Proficiency => stats.proficiency,
+        Stat::Knowledge => stats.knowledge,
+        Stat::Discipline => stats.discipline,
+    }
+}
+
+/// The spirit selection of `character`, if every spirit has a connection and each connection is
+/// used the right number of times.
+pub fn selection(character: &Character) -> eyre::Result<SpiritSelection> {
+    let connection = |spirit: Spirit| {
+        character
+            .spirits
+            .iter()
+            .find(|(s, _)| *s == spirit)
+            .map(|&(_, connection)| connection)
+    };
+    let missing: Vec<String> = Spirit::LIST
+        .into_iter()
+        .filter(|&spirit| connection(spirit).is_none())
+        .map(|spirit| format!("{:?}", spirit))
+        .collect();
+    if !missing.is_empty() {
+        eyre::bail!("no connection chosen for {}", missing.join(", "));
+    }
+
+    for (expected, count) in CONNECTION_COUNTS {
+        let actual = character
+            .spirits
+            .iter()
+            .filter(|(_, connection)| *connection == expected)
+            .count();
+        if actual != count {
+            eyre::bail!("expected {} {}, found {}", count, expected, actual);
+        }
+    }
+
+    SpiritSelection::try_from_iter(
+        Spirit::LIST
+            .into_iter()
+            .map(|spirit| (spirit, connection(spirit).unwrap())),
+    )
+    .map_err(|err| eyre::eyre!("invalid spirit selection: {:?}", err))
+}
+
+/// The final stats of a complete and legal `character`.
+pub fn stats(character: &Character) -> eyre::Result<Stats> {
+    let base = BaseStats::new(&selection(character)?);
+
+    let missing = character
+        .prerogatives
+        .iter()
+        .filter(|prerogative| prerogative.is_none())
+        .count();
+    if missing > 0 {
+        eyre::bail!("{} base prerogatives still to choose", missing);
+    }
+    if character.burdens.len() > MAX_OPTIONAL_BURDENS {
+        eyre::bail!(
+            "at most {} optional burdens can be taken, found {}",
+            MAX_OPTIONAL_BURDENS,
+            character.burdens.len()
+        );
+    }
+
+    let content = content::active();
+    if let Some((burden, _)) = character
+        .burdens
+        .iter()
+        .find(|(burden, _)| !content.has_burden(burden))
+    {
+        eyre::bail!(
+            "burden {:?} isn't in any enabled content pack",
+            burden.name()
+        );
+    }
+    let prerogatives: Vec<(&PrerogativeId, bool)> = character
+        .prerogatives
+        .iter()
+        .flatten()
+        .map(|prerogative| (prerogative, false))
+        .chain(
+            character
+                .burdens
+                .iter()
+                .map(|(_, prerogative)| (prerogative, true)),
+        )
+        .collect();
+    for (index, &(prerogative, for_burden)) in prerogatives.iter().enumerate() {
+        if !content.has_prerogative(prerogative) {
+            eyre::bail!(
+                "prerogative {:?} isn't in any enabled content pack",
+                prerogative.name()
+            );
+        }
+        let taken = prerogatives[..index]
+            .iter()
+            .filter(|(earlier, _)| *earlier == prerogative)
+            .count();
+        restrictions_allow(prerogative, Some(base), taken, for_burden)?;
+    }
+
+    // the rules library is the authority on the core rules, and the content's effects only
+    // stand in for it where packs add or change something
+    if let Some(pb) = core_prerogatives_and_burdens(character, &content) {
+        return base
+            .with_prerogatives_and_burdens(&pb)
+            .map_err(|()| eyre::eyre!("the prerogatives raise a stat past its maximum"));
+    }
+    let mut stats =
+        with_prerogative_bonuses(base, prerogatives.into_iter().map(|(p, _)| p.clone()));
+    for stat in STATS {
+        if *base_stat(&mut stats, stat) > max_stat(stat) {
+            eyre::bail!("the prerogatives raise {:?} past its maximum", stat);
+        }
+    }
+    Ok(Stats {
+        proficiency: stats.proficiency,
+        knowledge: stats.knowledge,
+        discipline: stats.discipline,
+    })
+}
+
+/// The prerogatives and burdens of `character` for the rules library, if they are all core ones
+/// that `content` leaves as they are.
+fn core_prerogatives_and_burdens(
+    character: &Character,
+    content: &content::Content,
+) -> Option<PrerogativesAndBurdens> {
+    let core = |prerogative: &PrerogativeId| match prerogative {
+        PrerogativeId::Core(core) if content.is_core(prerogative) => Some(*core),
+        _ => None,
+    };
+    let prerogatives: Vec<_> = character
+        .prerogatives
+        .iter()
+        .map(|prerogative| prerogative.as_ref().and_then(core))
+        .collect::<Option<_>>()?;
+    let mut pb = PrerogativesAndBurdens::new(prerogatives.try_into().ok()?);
+    for (burden, prerogative) in &character.burdens {
+        match burden {
+            BurdenId::Core(burden) => pb.add_burden(*burden, core(prerogative)?),
+            BurdenId::Homebrew(_) => return None,
+        }
+    }
+    Some(pb)
+}
+
+/// A random build in the same way the character creator's Randomize does it: every spirit gets a
+/// connection from those with some left, then prerogatives and burdens are picked at random.
+///
+/// The spirits are always legal; the prerogatives may push a stat past its maximum.
+pub fn random<R: rand::Rng>(rng: &mut R) -> Character {
+    let mut remaining = CONNECTION_COUNTS;
+    let spirits = Spirit::LIST
+        .into_iter()
+        .map(|spirit| {
+            let available: Vec<usize> = (0..remaining.len())
+                .filter(|&index| remaining[index].1 > 0)
+                .collect();
+            let index = available[rng.gen_range(0..available.len())];
+            remaining[index].1 -= 1;
+            (spirit, remaining[index].0)
+        })
+        .collect();
+
+    let content = content::active();
+    let (all_prerogatives, all_burdens) = (content.prerogative_ids(), content.burden_ids());
+    let random_prerogative =
+        |rng: &mut R| all_prerogatives[rng.gen_range(0..all_prerogatives.len())].clone();
+    let prerogatives = [(); BASE_PREROGATIVE_COUNT].map(|()| Some(random_prerogative(rng)));
+
+    let burdens = (0..MAX_OPTIONAL_BURDENS)
+        .map(|_| {
+            let burden = all_burdens[rng.gen_range(0..all_burdens.len())].clone();
+            (burden, random_prerogative(rng))
+        })
+        .collect();
+
+    Character {
+        spirits,
+        prerogatives,
+        burdens,
+        finalized: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archetype::Archetype;
+
+    fn legal_character() -> Character {
+        Archetype::built_in()[0].build().unwrap().character()
+    }
+
+    #[test]
+    fn prerogatives_stop_at_the_stat_maximums() {
+        let base = BaseStats::new(&selection(&legal_character()).unwrap());
+        let raising: Vec<(PrerogativeId, Stat)> = content::active()
+            .prerogative_ids()
+            .into_iter()
+            .filter_map(|prerogative| {
+                let stat = prerogative_stat(&prerogative)?;
+                Some((prerogative, stat))
+            })
+            .collect();
+        assert!(!raising.is_empty());
+        for (prerogative, stat) in raising {
+            let mut at_max = base;
+            *base_stat(&mut at_max, stat) = max_stat(stat);
+            assert!(!prerogative_allowed(at_max, &prerogative));
+            let mut raised = with_prerogative_bonuses(at_max, [prerogative.clone()]);
+            assert!(*base_stat(&mut raised, stat) > max_stat(stat));
+
+            let mut below_max = base;
+            *base_stat(&mut below_max, stat) = max_stat(stat) - 1;
+            assert!(prerogative_allowed(below_max, &prerogative));
+        }
+    }
+
+    #[test]
+    fn the_library_rules_on_core_content_only() {
+        let character = legal_character();
+        let content = content::Content::default();
+        assert!(core_prerogatives_and_burdens(&character, &content).is_some());
+
+        let changed = character.prerogatives[0].as_ref().unwrap();
+        let pack = content::ContentPack::from_toml(&format!(
+            "name = \"X\"\n[[prerogatives]]\nname = \"{}\"\n",
+            changed
+        ))
+        .unwrap();
+        let content = content::Content::from_packs(vec![pack]);
+        assert!(!content.is_core(changed));
+        assert!(core_prerogatives_and_burdens(&character, &content).is_none());
+    }
+
+    #[test]
+    fn burdens_are_limited() {
+        let mut character = legal_character();
+        let prerogative = character.prerogatives[0].clone().unwrap();
+        let burden = content::active().burden_ids()[0].clone();
+        character.burdens = vec![(burden, prerogative); MAX_OPTIONAL_BURDENS + 1];
+        let err = stats(&character).unwrap_err();
+        assert!(format!("{:#}", err).contains("at most"), "{:#}", err);
+    }
+
+    #[test]
+    fn illegal_selections_are_rejected() {
+        let mut character = legal_character();
+        assert!(stats(&character).is_ok());
+        let mastery = character
+            .spirits
+            .iter_mut()
+            .find(|(_, connection)| *connection == Connection::Mastery)
+            .unwrap();
+        mastery.1 = Connection::Ineptitude;
+        assert!(selection(&character).is_err());
+        assert!(stats(&character).is_err());
+
+        character.spirits.pop();
+        assert!(selection(&character).is_err());
+
+        let mut character = legal_character();
+        character.prerogatives[0] = None;
+        assert!(stats(&character).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! Hosts a shared table for clients on the same machine or network.
+//!
+//! Every connection gets its own thread, which reads the client's messages and writes whatever
+//! the table has queued for it in turns, waking at least every `POLL_INTERVAL`.
+
+use crate::protocol::{
+    ClientMessage, Player, PlayerId, PlayerRoll, ServerMessage, DIE_SIDES, MAX_DICE,
+    MAX_NAME_CHARS, VERSION,
+};
+use eyre::WrapErr;
+use rand::Rng;
+use std::{
+    collections::BTreeMap,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{mpsc, Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tungstenite::{Message, WebSocket};
+
+/// How long a connection waits for the client before checking for messages to send it.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// How long a new connection has to finish the WebSocket handshake and say hello before it is
+/// dropped, so that silent clients don't hold a thread forever.
+pub const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct Server {
+    listener: TcpListener,
+    table: Arc<Mutex<Table>>,
+    hello_timeout: Duration,
+}
+
+impl Server {
+    /// Listens on `address`, e.g. `0.0.0.0:7878` for the whole network or `127.0.0.1:0` for any
+    /// free local port.
+    pub fn bind(address: impl ToSocketAddrs) -> eyre::Result<Self> {
+        let listener = TcpListener::bind(address).wrap_err("binding the server")?;
+        Ok(Self {
+            listener,
+            table: Arc::new(Mutex::new(Table::new(rand::SeedableRng::from_entropy()))),
+            hello_timeout: HELLO_TIMEOUT,
+        })
+    }
+
+    /// Rolls dice with a generator seeded with `seed` rather than a random one.
+    pub fn with_seed(self, seed: u64) -> Self {
+        *self.table.lock().unwrap() = Table::new(rand::SeedableRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Gives new connections `timeout` rather than `HELLO_TIMEOUT` to say hello.
+    pub fn with_hello_timeout(mut self, timeout: Duration) -> Self {
+        self.hello_timeout = timeout;
+        self
+    }
+
+    pub fn local_addr(&self) -> eyre::Result<SocketAddr> {
+        self.listener
+            .local_addr()
+            .wrap_err("getting the server address")
+    }
+
+    /// Serves clients until accepting connections fails.
+    pub fn run(self) -> eyre::Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = stream.wrap_err("accepting a connection")?;
+            let table = self.table.clone();
+            let hello_timeout = self.hello_timeout;
+            std::thread::spawn(move || {
+                let peer = stream.peer_addr().ok();
+                if let Err(err) = serve(stream, &table, hello_timeout) {
+                    log::warn!("connection from {:?} ended: {:#}", peer, err);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Everyone at the table, with a queue of messages for each.
+struct Table {
+    next_id: PlayerId,
+    seats: BTreeMap<PlayerId, Seat>,
+    rng: rand::rngs::SmallRng,
+}
+
+struct Seat {
+    player: Player,
+    outbox: mpsc::Sender<ServerMessage>,
+}
+
+impl Table {
+    fn new(rng: rand::rngs::SmallRng) -> Self {
+        Self {
+            next_id: 1,
+            seats: BTreeMap::new(),
+            rng,
+        }
+    }
+
+    fn players(&self) -> Vec<Player> {
+        self.seats
+            .values()
+            .map(|seat| seat.player.clone())
+            .collect()
+    }
+
+    fn broadcast(&self, message: ServerMessage) {
+        for seat in self.seats.values() {
+            // a closed outbox means that connection is on its way out
+            let _ = seat.outbox.send(message.clone());
+        }
+    }
+
+    /// Seats a new player, telling everyone else, and returns the welcome for them.
+    fn join(&mut self, name: String, outbox: mpsc::Sender<ServerMessage>) -> ServerMessage {
+        let id = self.next_id;
+        self.next_id += 1;
+        let player = Player {
+            id,
+            name,
+            character: None,
+        };
+        self.broadcast(ServerMessage::Joined {
+            player: player.clone(),
+        });
+        self.seats.insert(id, Seat { player, outbox });
+        ServerMessage::Welcome {
+            version: VERSION,
+            you: id,
+            players: self.players(),
+        }
+    }
+
+    fn leave(&mut self, id: PlayerId) {
+        if self.seats.remove(&id).is_some() {
+            self.broadcast(ServerMessage::Left { player: id });
+        }
+    }
+
+    fn handle(&mut self, id: PlayerId, message: ClientMessage) {
+        match message {
+            ClientMessage::Hello { .. } => self.reply(id, "already joined".into()),
+            ClientMessage::ShareCharacter { character } => {
+                if let Some(seat) = self.seats.get_mut(&id) {
+                    seat.player.character = Some(character.clone());
+                }
+                self.broadcast(ServerMessage::CharacterShared {
+                    player: id,
+                    character,
+                });
+            }
+            ClientMessage::Roll { dice, spirit } => {
+                if let Some(results) = self.roll(id, dice) {
+                    self.broadcast(ServerMessage::Rolled {
+                        player: id,
+                        spirit,
+                        results,
+                    });
+                }
+            }
+            ClientMessage::GroupCheck { stat, dice } => {
+                if self.dice_allowed(id, dice) {
+                    let players: Vec<PlayerId> = self.seats.keys().copied().collect();
+                    let rolls = players
+                        .into_iter()
+                        .map(|player| PlayerRoll {
+                            player,
+                            results: self.roll_dice(dice),
+                        })
+                        .collect();
+                    self.broadcast(ServerMessage::GroupCheck {
+                        player: id,
+                        stat,
+                        rolls,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Rolls `dice` d12s for `id`, or tells them why not.
+    fn roll(&mut self, id: PlayerId, dice: u8) -> Option<Vec<u8>> {
+        self.dice_allowed(id, dice).then(|| self.roll_dice(dice))
+    }
+
+    /// Whether `dice` d12s can be rolled at once, telling `id` why not if they can't.
+    fn dice_allowed(&self, id: PlayerId, dice: u8) -> bool {
+        let allowed = (1..=MAX_DICE).contains(&dice);
+        if !allowed {
+            self.reply(id, format!("can roll 1 to {} dice, not {}", MAX_DICE, dice));
+        }
+        allowed
+    }
+
+    fn roll_dice(&mut self, dice: u8) -> Vec<u8> {
+        (0..dice)
+            .map(|_| self.rng.gen_range(1..=DIE_SIDES))
+            .collect()
+    }
+
+    fn reply(&self, id: PlayerId, reason: String) {
+        if let Some(seat) = self.seats.get(&id) {
+            let _ = seat.outbox.send(ServerMessage::Error { reason });
+        }
+    }
+}
+
+fn serve(stream: TcpStream, table: &Mutex<Table>, hello_timeout: Duration) -> eyre::Result<()> {
+    let deadline = Instant::now() + hello_timeout;
+    stream
+        .set_read_timeout(Some(hello_timeout))
+        .wrap_err("setting the read timeout")?;
+    let mut socket = tungstenite::accept(stream).wrap_err("WebSocket handshake")?;
+
+    let name = match read_hello(&mut socket, deadline)? {
+        Some(ClientMessage::Hello { version, name }) if version == VERSION => name,
+        Some(ClientMessage::Hello { version, .. }) => {
+            return reject(
+                socket,
+                format!("server speaks version {}, not {}", VERSION, version),
+            )
+        }
+        Some(_) => return reject(socket, "expected hello".into()),
+        None => return Ok(()),
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return reject(socket, "a name is needed to join".into());
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return reject(
+            socket,
+            format!("names can be at most {} characters", MAX_NAME_CHARS),
+        );
+    }
+    let name = name.to_owned();
+
+    let (outbox, inbox) = mpsc::channel();
+    let welcome = table.lock().unwrap().join(name, outbox);
+    let id = match welcome {
+        ServerMessage::Welcome { you, .. } => you,
+        _ => unreachable!("joining always welcomes"),
+    };
+    log::info!("player {} joined", id);
+    let result = (|| {
+        write(&mut socket, &welcome)?;
+        socket
+            .get_mut()
+            .set_read_timeout(Some(POLL_INTERVAL))
+            .wrap_err("setting the read timeout")?;
+        loop {
+            for message in inbox.try_iter() {
+                write(&mut socket, &message)?;
+            }
+            match socket.read() {
+                Ok(Message::Text(text)) => match ClientMessage::from_json(&text) {
+                    Ok(message) => table.lock().unwrap().handle(id, message),
+                    Err(err) => table.lock().unwrap().reply(id, format!("{:#}", err)),
+                },
+                Ok(Message::Close(_)) => return Ok(()),
+                Ok(_) => {}
+                Err(err) if is_timeout(&err) => {}
+                Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    return Ok(())
+                }
+                Err(err) => return Err(err).wrap_err("reading from the client"),
+            }
+        }
+    })();
+    table.lock().unwrap().leave(id);
+    log::info!("player {} left", id);
+    result
+}
+
+/// The first message from a client that hasn't joined yet, or `None` if it hung up. Fails if
+/// none comes by `deadline`.
+fn read_hello(
+    socket: &mut WebSocket<TcpStream>,
+    deadline: Instant,
+) -> eyre::Result<Option<ClientMessage>> {
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => return ClientMessage::from_json(&text).map(Some),
+            Ok(Message::Close(_))
+            | Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                return Ok(None)
+            }
+            // pings alone don't keep a client that never says hello
+            Ok(_) if Instant::now() < deadline => {}
+            Ok(_) => eyre::bail!("no hello in time"),
+            Err(err) if is_timeout(&err) => eyre::bail!("no hello in time"),
+            Err(err) => return Err(err).wrap_err("reading from the client"),
+        }
+    }
+}
+
+fn write(socket: &mut WebSocket<TcpStream>, message: &ServerMessage) -> eyre::Result<()> {
+    socket
+        .send(Message::Text(message.to_json()?))
+        .wrap_err("writing to the client")
+}
+
+fn reject(mut socket: WebSocket<TcpStream>, reason: String) -> eyre::Result<()> {
+    log::info!("rejected a client: {}", reason);
+    write(&mut socket, &ServerMessage::Rejected { reason })?;
+    socket.close(None).wrap_err("closing the connection")?;
+    // let the close handshake finish so the client sees a clean close, unless it never answers
+    socket
+        .get_mut()
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .wrap_err("setting the read timeout")?;
+    while socket.read().is_ok() {}
+    Ok(())
+}
+
+/// Whether `err` only means a read timed out with nothing to read.
+pub(crate) fn is_timeout(err: &tungstenite::Error) -> bool {
+    matches!(
+        err,
+        tungstenite::Error::Io(err)
+            if matches!(err.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut)
+    )
+}
@@ -0,0 +1,301 @@
+//! A structured record of what happened at the table, kept with the campaign so each session
+//! carries on from the last, and exportable as Markdown or JSON for recaps.
+
+use crate::{content::BurdenId, storage};
+use eyre::WrapErr;
+use serde::{Deserialize, Serialize};
+use spirits_within::{Spirit, Stat};
+
+/// Something that happened at the table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    /// Who did it.
+    pub actor: String,
+    /// The spirit it was for, if any.
+    #[serde(default, with = "crate::character::optional_spirit_name")]
+    pub spirit: Option<Spirit>,
+    #[serde(flatten)]
+    pub event: Event,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    Joined,
+    Left,
+    SharedCharacter,
+    Roll {
+        results: Vec<u8>,
+    },
+    /// The actor called a check of `stat` for everyone at the table.
+    GroupCheck {
+        #[serde(with = "crate::character::stat_name")]
+        stat: Stat,
+        rolls: Vec<CheckRoll>,
+    },
+    /// The actor brought one of their optional burdens into play.
+    BurdenInvoked {
+        #[serde(with = "crate::character::by_name")]
+        burden: BurdenId,
+    },
+    /// Only `notes`, written by the actor.
+    Note,
+}
+
+/// One player's dice in a group check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckRoll {
+    pub actor: String,
+    pub results: Vec<u8>,
+    /// The player's value of the checked stat, if they had shared a finished character.
+    #[serde(default)]
+    pub against: Option<String>,
+}
+
+impl Entry {
+    /// An entry for `event` by `actor`, happening now.
+    pub fn new(actor: impl Into<String>, event: Event) -> Self {
+        Self {
+            time: now(),
+            actor: actor.into(),
+            spirit: None,
+            event,
+            notes: String::new(),
+        }
+    }
+
+    pub fn with_spirit(self, spirit: Option<Spirit>) -> Self {
+        Self { spirit, ..self }
+    }
+
+    pub fn with_notes(self, notes: impl Into<String>) -> Self {
+        Self {
+            notes: notes.into(),
+            ..self
+        }
+    }
+
+    /// What came of it, in a few words.
+    pub fn result(&self) -> String {
+        match &self.event {
+            Event::Joined => "joined the table".into(),
+            Event::Left => "left the table".into(),
+            Event::SharedCharacter => "shared a character".into(),
+            Event::Roll { results } => format!(
+                "rolled {}d{}: {} (total {})",
+                results.len(),
+                crate::protocol::DIE_SIDES,
+                faces(results),
+                results.iter().map(|&r| u32::from(r)).sum::<u32>()
+            ),
+            Event::GroupCheck { stat, rolls } => {
+                let rolls: Vec<String> = rolls
+                    .iter()
+                    .map(|roll| match &roll.against {
+                        Some(against) => {
+                            format!("{} {} vs {}", roll.actor, faces(&roll.results), against)
+                        }
+                        None => format!("{} {}", roll.actor, faces(&roll.results)),
+                    })
+                    .collect();
+                format!("called a {:?} check: {}", stat, rolls.join("; "))
+            }
+            Event::BurdenInvoked { burden } => format!("invoked {}", burden),
+            Event::Note => String::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", format_time(self.time), self.actor)?;
+        if let Some(spirit) = self.spirit {
+            write!(f, " ({:?})", spirit)?;
+        }
+        match (self.result().as_str(), self.notes.as_str()) {
+            (result, "") => write!(f, " {}", result),
+            ("", notes) => write!(f, ": {}", notes),
+            (result, notes) => write!(f, " {}. {}", result, notes),
+        }
+    }
+}
+
+fn faces(results: &[u8]) -> String {
+    results
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A way to export a log.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Json,
+}
+
+/// Everything recorded for a campaign, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionLog {
+    pub entries: Vec<Entry>,
+}
+
+impl SessionLog {
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    pub fn from_json(contents: &str) -> eyre::Result<Self> {
+        serde_json::from_str(contents).wrap_err("parsing session log")
+    }
+
+    pub fn to_json(&self) -> eyre::Result<String> {
+        serde_json::to_string_pretty(self).wrap_err("serializing session log")
+    }
+
+    /// A recap headed `title` with a table row for each entry.
+    pub fn to_markdown(&self, title: &str) -> String {
+        let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+        let mut markdown = format!(
+            "# {}\n\n| Time (UTC) | Actor | Spirit | Result | Notes |\n| --- | --- | --- | --- | --- |\n",
+            cell(title)
+        );
+        for entry in &self.entries {
+            markdown += &format!(
+                "| {} | {} | {} | {} | {} |\n",
+                format_time(entry.time),
+                cell(&entry.actor),
+                entry
+                    .spirit
+                    .map_or_else(String::new, |s| format!("{:?}", s)),
+                cell(&entry.result()),
+                cell(&entry.notes)
+            );
+        }
+        markdown
+    }
+
+    /// Exports the log of `campaign` with [`storage::export`], returning where it went.
+    pub fn export(&self, campaign: &str, format: Format) -> eyre::Result<String> {
+        let (extension, contents) = match format {
+            Format::Markdown => ("md", self.to_markdown(campaign)),
+            Format::Json => ("json", self.to_json()?),
+        };
+        storage::export(
+            &format!("{}-log.{}", storage::file_stem(campaign), extension),
+            &contents,
+        )
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+/// `time` as a UTC date and time like `2024-03-01 18:30:05`.
+pub fn format_time(time: u64) -> String {
+    let (days, seconds) = (time / 86_400, time % 86_400);
+    // Howard Hinnant's days-to-civil, for days since 1970-01-01
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> SessionLog {
+        let mut log = SessionLog::default();
+        log.push(Entry {
+            time: 0,
+            ..Entry::new(
+                "Ada",
+                Event::Roll {
+                    results: vec![7, 12],
+                },
+            )
+            .with_spirit(Some(Spirit::Shadows))
+        });
+        log.push(Entry {
+            time: 1_709_317_805,
+            ..Entry::new(
+                "Bo",
+                Event::GroupCheck {
+                    stat: Stat::Knowledge,
+                    rolls: vec![CheckRoll {
+                        actor: "Bo".into(),
+                        results: vec![4],
+                        against: Some("3".into()),
+                    }],
+                },
+            )
+            .with_notes("the door holds")
+        });
+        log.push(Entry::new(
+            "Ada",
+            Event::BurdenInvoked {
+                burden: spirits_within::Burden::LIST[0].into(),
+            },
+        ));
+        log
+    }
+
+    #[test]
+    fn times_are_utc_dates() {
+        assert_eq!(format_time(0), "1970-01-01 00:00:00");
+        assert_eq!(format_time(951_782_400), "2000-02-29 00:00:00");
+        assert_eq!(format_time(1_709_317_805), "2024-03-01 18:30:05");
+    }
+
+    #[test]
+    fn logs_round_trip_through_json() {
+        let log = log();
+        assert_eq!(SessionLog::from_json(&log.to_json().unwrap()).unwrap(), log);
+    }
+
+    #[test]
+    fn markdown_has_a_row_per_entry() {
+        let markdown = log().to_markdown("Night | Market");
+        assert!(markdown.starts_with("# Night \\| Market\n"));
+        assert!(markdown.contains(
+            "| 1970-01-01 00:00:00 | Ada | Shadows | rolled 2d12: 7, 12 (total 19) |  |\n"
+        ));
+        assert!(
+            markdown.contains("| Bo |  | called a Knowledge check: Bo 4 vs 3 | the door holds |\n")
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{
+    config::{Config, SeedBehavior, WindowConfig},
+    theme::Theme,
+};
+use iced_winit::{
+    widget::{pick_list, slider, text_input, Checkbox, Column, PickList, Row, Slider, TextInput},
+    Element, Length,
+};
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    WindowSizeSelected(WindowSize),
+    FullscreenToggled(bool),
+    MsaaSelected(Msaa),
+    VsyncToggled(bool),
+    ThemeSelected(Theme),
+    UiScaleChanged(f32),
+    UiScaleReleased,
+    AnimationToggled(bool),
+    FixedSeedToggled(bool),
+    SeedChanged(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    window_size_picker: pick_list::State<WindowSize>,
+    msaa_picker: pick_list::State<Msaa>,
+    theme_picker: pick_list::State<Theme>,
+    ui_scale_slider: slider::State,
+    /// The scale being dragged to, applied when the slider is released so the UI doesn't resize
+    /// under the cursor.
+    pending_ui_scale: Option<f32>,
+    seed_input: text_input::State,
+    seed_text: String,
+}
+
+impl Settings {
+    /// Whether one of the screen's text inputs has keyboard focus.
+    pub fn is_editing_text(&self) -> bool {
+        self.seed_input.is_focused()
+    }
+
+    /// Applies `message` to `config`, returning whether it changed and should be saved.
+    ///
+    /// `seed` is the seed this session started with, which becomes the fixed seed when one is
+    /// first turned on.
+    pub fn update(&mut self, message: Message, config: &mut Config, seed: u64) -> bool {
+        let before = config.clone();
+        match message {
+            Message::WindowSizeSelected(WindowSize { width, height }) => {
+                config.window.width = width;
+                config.window.height = height;
+            }
+            Message::FullscreenToggled(fullscreen) => config.window.fullscreen = fullscreen,
+            Message::MsaaSelected(Msaa(samples)) => config.msaa = samples,
+            Message::VsyncToggled(vsync) => config.vsync = vsync,
+            Message::ThemeSelected(theme) => config.theme = theme,
+            Message::UiScaleChanged(scale) => self.pending_ui_scale = Some(scale),
+            Message::UiScaleReleased => {
+                if let Some(scale) = self.pending_ui_scale.take() {
+                    config.ui_scale = scale;
+                }
+            }
+            Message::AnimationToggled(animation) => config.animation = animation,
+            Message::FixedSeedToggled(true) => {
+                config.seed = SeedBehavior::Fixed { seed };
+                self.seed_text = seed.to_string();
+            }
+            Message::FixedSeedToggled(false) => config.seed = SeedBehavior::Random,
+            Message::SeedChanged(text) => {
+                if let Ok(seed) = text.parse() {
+                    config.seed = SeedBehavior::Fixed { seed };
+                }
+                self.seed_text = text;
+            }
+        }
+        *config != before
+    }
+
+    pub fn view(&mut self, config: &Config) -> Element<'_, Message, Renderer> {
+        let theme = config.theme;
+        let typography = theme.typography();
+        let checkbox = |checked: bool, on_toggle: fn(bool) -> Message| {
+            Checkbox::new(checked, "", on_toggle).size(typography.body_size)
+        };
+
+        let sizes: Vec<WindowSize> = WindowConfig::SIZES
+            .into_iter()
+            .map(|(width, height)| WindowSize { width, height })
+            .collect();
+        let msaa: Vec<Msaa> = Config::MSAA_OPTIONS.into_iter().map(Msaa).collect();
+        let ui_scale = self.pending_ui_scale.unwrap_or(config.ui_scale);
+        let fixed_seed = match config.seed {
+            SeedBehavior::Random => None,
+            SeedBehavior::Fixed { seed } => Some(seed),
+        };
+
+        let mut seed_row = labelled(
+            theme,
+            "Fixed seed",
+            checkbox(fixed_seed.is_some(), Message::FixedSeedToggled),
+        );
+        if let Some(seed) = fixed_seed {
+            if self.seed_text.is_empty() {
+                self.seed_text = seed.to_string();
+            }
+            seed_row = seed_row.push(
+                TextInput::new(
+                    &mut self.seed_input,
+                    "Seed",
+                    &self.seed_text,
+                    Message::SeedChanged,
+                )
+                .font(typography.body.into())
+                .size(typography.body_size)
+                .padding(2)
+                .width(Length::Units(240)),
+            );
+        }
+
+        Column::new()
+            .push(theme.heading("Settings"))
+            .push(theme.body("Window, fullscreen, MSAA and vsync changes apply after a restart."))
+            .push(labelled(
+                theme,
+                "Window size",
+                PickList::new(
+                    &mut self.window_size_picker,
+                    sizes,
+                    Some(WindowSize {
+                        width: config.window.width,
+                        height: config.window.height,
+                    }),
+                    Message::WindowSizeSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            ))
+            .push(labelled(
+                theme,
+                "Fullscreen",
+                checkbox(config.window.fullscreen, Message::FullscreenToggled),
+            ))
+            .push(labelled(
+                theme,
+                "MSAA",
+                PickList::new(
+                    &mut self.msaa_picker,
+                    msaa,
+                    Some(Msaa(config.msaa)),
+                    Message::MsaaSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            ))
+            .push(labelled(
+                theme,
+                "Vsync",
+                checkbox(config.vsync, Message::VsyncToggled),
+            ))
+            .push(labelled(
+                theme,
+                "Theme",
+                PickList::new(
+                    &mut self.theme_picker,
+                    &Theme::ALL[..],
+                    Some(theme),
+                    Message::ThemeSelected,
+                )
+                .font(typography.body.into())
+                .text_size(typography.body_size)
+                .style(theme.pick_list()),
+            ))
+            .push(
+                labelled(
+                    theme,
+                    "UI scale",
+                    Slider::new(
+                        &mut self.ui_scale_slider,
+                        Config::MIN_UI_SCALE..=Config::MAX_UI_SCALE,
+                        ui_scale,
+                        Message::UiScaleChanged,
+                    )
+                    .on_release(Message::UiScaleReleased)
+                    .step(0.05)
+                    .width(Length::Units(240)),
+                )
+                .push(theme.body(format!("{:.0}%", ui_scale * 100.))),
+            )
+            .push(labelled(
+                theme,
+                "Animation",
+                checkbox(config.animation, Message::AnimationToggled),
+            ))
+            .push(seed_row)
+            .spacing(5)
+            .padding(10)
+            .width(Length::Fill)
+            .into()
+    }
+}
+
+/// A row with `label` in a fixed-width column followed by `control`.
+pub(crate) fn labelled<'a, Message: 'a>(
+    theme: Theme,
+    label: &str,
+    control: impl Into<Element<'a, Message, Renderer>>,
+) -> Row<'a, Message, Renderer> {
+    Row::new()
+        .push(theme.body(label).width(Length::Units(200)))
+        .push(control)
+        .spacing(5)
+        .align_items(iced_winit::Alignment::Center)
+}
+
+/// A window size choice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct WindowSize {
+    width: u32,
+    height: u32,
+}
+
+impl std::fmt::Display for WindowSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} x {}", self.width, self.height)
+    }
+}
+
+/// A multisampling choice.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Msaa(u16);
+
+impl std::fmt::Display for Msaa {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "Off"),
+            samples => write!(f, "{}x", samples),
+        }
+    }
+}
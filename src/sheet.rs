@@ -0,0 +1,65 @@
+//! A read-only character sheet.
+
+use crate::{character::Character, rules, settings::labelled, stat_chart, theme::Theme};
+use iced_winit::{widget::Column, Element};
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+
+/// Shows the stats of `character` against its base stats, its spirits by connection, and its
+/// prerogatives and burdens.
+pub fn view<'a, Message: 'a>(character: &Character, theme: Theme) -> Column<'a, Message, Renderer> {
+    let stats: Element<'a, Message, Renderer> =
+        match rules::selection(character).and_then(|selection| {
+            let base = spirits_within::BaseStats::new(&selection);
+            rules::stats(character).map(|stats| (base, stats))
+        }) {
+            Ok((base, stats)) => stat_chart::view(stats.into(), base.into(), theme),
+            Err(err) => theme
+                .body(format!("Not a legal character: {:#}", err))
+                .into(),
+        };
+
+    let mut sheet = Column::new().spacing(5).push(stats);
+    for (connection, _) in rules::CONNECTION_COUNTS {
+        let spirits: Vec<String> = character
+            .spirits
+            .iter()
+            .filter(|(_, c)| *c == connection)
+            .map(|(spirit, _)| format!("{:?}", spirit))
+            .collect();
+        sheet = sheet.push(labelled(
+            theme,
+            &connection.to_string(),
+            theme.body(spirits.join(", ")),
+        ));
+    }
+
+    let prerogatives: Vec<String> = character
+        .prerogatives
+        .iter()
+        .map(|prerogative| {
+            prerogative
+                .as_ref()
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "-".into())
+        })
+        .collect();
+    let burdens = if character.burdens.is_empty() {
+        "None".to_owned()
+    } else {
+        character
+            .burdens
+            .iter()
+            .map(|(burden, prerogative)| format!("{} (for {})", burden, prerogative))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    sheet
+        .push(labelled(
+            theme,
+            "Prerogatives",
+            theme.body(prerogatives.join(", ")),
+        ))
+        .push(labelled(theme, "Burdens", theme.body(burdens)))
+}
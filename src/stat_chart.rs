@@ -0,0 +1,189 @@
+use crate::theme::{Palette, Theme};
+use iced_winit::{
+    layout, renderer,
+    widget::{Column, Row},
+    Color, Element, Layout, Length, Point, Rectangle, Size, Widget,
+};
+
+/// The three stats in a form the chart can draw, whatever they were derived from.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct StatValues {
+    pub proficiency: f32,
+    pub knowledge: f32,
+    pub discipline: f32,
+}
+
+impl StatValues {
+    pub fn max() -> Self {
+        use spirits_within::Stats;
+        Self {
+            proficiency: Stats::MAX_PROFICIENCY as f32,
+            knowledge: Stats::MAX_KNOWLEDGE as f32,
+            discipline: Stats::MAX_DISCIPLINE as f32,
+        }
+    }
+
+    fn rows(self) -> [(spirits_within::Stat, f32); 3] {
+        use spirits_within::Stat::*;
+        [
+            (Proficiency, self.proficiency),
+            (Knowledge, self.knowledge),
+            (Discipline, self.discipline),
+        ]
+    }
+}
+
+impl From<spirits_within::BaseStats> for StatValues {
+    fn from(stats: spirits_within::BaseStats) -> Self {
+        Self {
+            proficiency: stats.proficiency as f32,
+            knowledge: stats.knowledge as f32,
+            discipline: stats.discipline as f32,
+        }
+    }
+}
+
+impl From<spirits_within::Stats> for StatValues {
+    fn from(stats: spirits_within::Stats) -> Self {
+        Self {
+            proficiency: stats.proficiency as f32,
+            knowledge: stats.knowledge as f32,
+            discipline: stats.discipline as f32,
+        }
+    }
+}
+
+/// Draws `current` as bars against each stat's maximum, with `base` shown as a ghost beneath.
+pub fn view<'a, Message: 'a>(
+    current: StatValues,
+    base: StatValues,
+    theme: Theme,
+) -> Element<'a, Message, <crate::Application as iced_winit::Program>::Renderer> {
+    let palette = theme.palette();
+    let max = StatValues::max();
+    let rows = current
+        .rows()
+        .into_iter()
+        .zip(base.rows())
+        .zip(max.rows())
+        .map(|(((stat, value), (_, ghost)), (_, max))| {
+            Row::new()
+                .push(theme.body(format!("{:?}", stat)).width(Length::Units(120)))
+                .push(StatBar::new(value, ghost, max, palette))
+                .push(
+                    theme
+                        .body(format!("{} / {}", value, max))
+                        .width(Length::Units(80))
+                        .horizontal_alignment(iced_winit::alignment::Horizontal::Right),
+                )
+                .spacing(5)
+                .align_items(iced_winit::Alignment::Center)
+                .into()
+        })
+        .collect();
+    Column::with_children(rows)
+        .spacing(2)
+        .padding(5)
+        .width(Length::Fill)
+        .into()
+}
+
+/// A single horizontal bar.
+pub struct StatBar {
+    value: f32,
+    ghost: f32,
+    max: f32,
+    palette: Palette,
+}
+
+impl StatBar {
+    const HEIGHT: u16 = 20;
+
+    pub fn new(value: f32, ghost: f32, max: f32, palette: Palette) -> Self {
+        Self {
+            value,
+            ghost,
+            max,
+            palette,
+        }
+    }
+
+    fn fraction(&self, value: f32) -> f32 {
+        if self.max > 0. {
+            (value / self.max).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+}
+
+impl<Message, Renderer> Widget<Message, Renderer> for StatBar
+where
+    Renderer: renderer::Renderer,
+{
+    fn width(&self) -> Length {
+        Length::Fill
+    }
+
+    fn height(&self) -> Length {
+        Length::Units(Self::HEIGHT)
+    }
+
+    fn layout(&self, _renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits
+            .width(Length::Fill)
+            .height(Length::Units(Self::HEIGHT));
+        layout::Node::new(limits.resolve(Size::ZERO))
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) {
+        let palette = &self.palette;
+        let ghost = Color {
+            a: 0.35,
+            ..palette.text
+        };
+
+        let bounds = layout.bounds();
+        let quad = |width: f32| renderer::Quad {
+            bounds: Rectangle {
+                width: bounds.width * width,
+                ..bounds
+            },
+            border_radius: 2.,
+            border_width: 0.,
+            border_color: Color::TRANSPARENT,
+        };
+
+        renderer.fill_quad(
+            renderer::Quad {
+                border_width: 1.,
+                border_color: palette.border,
+                ..quad(1.)
+            },
+            palette.primary,
+        );
+        let color = if self.value >= self.max {
+            palette.danger
+        } else {
+            palette.selection
+        };
+        renderer.fill_quad(quad(self.fraction(self.value)), color);
+        renderer.fill_quad(quad(self.fraction(self.ghost)), ghost);
+    }
+}
+
+impl<'a, Message, Renderer> From<StatBar> for Element<'a, Message, Renderer>
+where
+    Renderer: renderer::Renderer,
+{
+    fn from(bar: StatBar) -> Self {
+        Element::new(bar)
+    }
+}
@@ -0,0 +1,140 @@
+//! Small key/value persistence for user preferences.
+//!
+//! Natively each key is a file in the user's data directory. On the web keys live in
+//! `localStorage`.
+
+use eyre::WrapErr;
+
+/// Where `key` is kept.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn path(key: &str) -> eyre::Result<std::path::PathBuf> {
+    let dir = dirs::data_dir()
+        .ok_or_else(|| eyre::eyre!("no data directory for this platform"))?
+        .join("spirits-within");
+    Ok(dir.join(key))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key).ok()?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(key: &str, value: &str) -> eyre::Result<()> {
+    let path = path(key)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("creating {}", parent.display()))?;
+    }
+    std::fs::write(&path, value).wrap_err_with(|| format!("writing {}", path.display()))
+}
+
+/// The keys saved under `folder`, sorted.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn keys(folder: &str) -> Vec<String> {
+    let entries = match path(folder).and_then(|dir| Ok(std::fs::read_dir(dir)?)) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let mut keys: Vec<String> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            if !entry.file_type().ok()?.is_file() {
+                return None;
+            }
+            Some(format!("{}/{}", folder, entry.file_name().to_str()?))
+        })
+        .collect();
+    keys.sort();
+    keys
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> eyre::Result<web_sys::Storage> {
+    web_sys::window()
+        .ok_or_else(|| eyre::eyre!("no window"))?
+        .local_storage()
+        .map_err(|err| eyre::eyre!("{:?}", err))?
+        .ok_or_else(|| eyre::eyre!("localStorage is unavailable"))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load(key: &str) -> Option<String> {
+    local_storage().ok()?.get_item(key).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(key: &str, value: &str) -> eyre::Result<()> {
+    local_storage()?
+        .set_item(key, value)
+        .map_err(|err| eyre::eyre!("{:?}", err))
+        .wrap_err_with(|| format!("saving {}", key))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn keys(folder: &str) -> Vec<String> {
+    let storage = match local_storage() {
+        Ok(storage) => storage,
+        Err(_) => return vec![],
+    };
+    let prefix = format!("{}/", folder);
+    let mut keys: Vec<String> = (0..storage.length().unwrap_or(0))
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .filter(|key| key.starts_with(&prefix))
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// `name` as something safe to use in a key or file name.
+pub fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.is_empty() {
+        "default".into()
+    } else {
+        stem
+    }
+}
+
+/// Hands `contents` to the user as a file called `name`, returning where it went.
+///
+/// Natively it's written to the `exports` folder of the data directory. On the web it's
+/// downloaded.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export(name: &str, contents: &str) -> eyre::Result<String> {
+    let key = format!("exports/{}", name);
+    save(&key, contents)?;
+    Ok(path(&key)?.display().to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn export(name: &str, contents: &str) -> eyre::Result<String> {
+    use wasm_bindgen::JsCast;
+
+    let js = |err: wasm_bindgen::JsValue| eyre::eyre!("{:?}", err);
+    let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(contents));
+    let blob = web_sys::Blob::new_with_str_sequence(&parts).map_err(js)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js)?;
+    let anchor: web_sys::HtmlAnchorElement = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| eyre::eyre!("no document"))?
+        .create_element("a")
+        .map_err(js)?
+        .dyn_into()
+        .map_err(|_| eyre::eyre!("created an element that isn't a link"))?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url).map_err(js)?;
+    Ok(format!("your downloads as {}", name))
+}
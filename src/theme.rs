@@ -0,0 +1,293 @@
+use crate::fonts::{FontChoice, Typography};
+use iced_winit::{
+    overlay::menu,
+    widget::{button, container, pick_list, tooltip, Tooltip},
+    Background, Color, Element,
+};
+use serde::{Deserialize, Serialize};
+
+type Renderer = <crate::Application as iced_winit::Program>::Renderer;
+type Text = iced_winit::widget::Text<Renderer>;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::Light
+    }
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Light, Theme::Dark, Theme::HighContrast];
+
+    pub fn palette(self) -> Palette {
+        match self {
+            Theme::Light => Palette {
+                background: Color::WHITE,
+                surface: Color::from_rgb(0.7, 0.7, 0.7),
+                text: Color::BLACK,
+                primary: Color::from_rgb(0.87, 0.87, 0.87),
+                primary_text: Color::BLACK,
+                danger: Color::from_rgb(0.9, 0.2, 0.3),
+                border: Color::from_rgb(0.5, 0.5, 0.5),
+                selection: Color::from_rgb(0.4, 0.4, 1.),
+                weak: Color::from_rgb(0.35, 0.4, 0.65),
+                strong: Color::from_rgb(1., 0.75, 0.1),
+                accent: Color::from_rgb(1., 0., 0.),
+            },
+            Theme::Dark => Palette {
+                background: Color::from_rgb(0.08, 0.08, 0.1),
+                surface: Color::from_rgb(0.18, 0.18, 0.22),
+                text: Color::from_rgb(0.9, 0.9, 0.9),
+                primary: Color::from_rgb(0.28, 0.28, 0.34),
+                primary_text: Color::from_rgb(0.9, 0.9, 0.9),
+                danger: Color::from_rgb(0.75, 0.15, 0.25),
+                border: Color::from_rgb(0.4, 0.4, 0.45),
+                selection: Color::from_rgb(0.3, 0.35, 0.8),
+                weak: Color::from_rgb(0.3, 0.45, 0.8),
+                strong: Color::from_rgb(1., 0.8, 0.3),
+                accent: Color::from_rgb(1., 0.3, 0.3),
+            },
+            Theme::HighContrast => Palette {
+                background: Color::BLACK,
+                surface: Color::BLACK,
+                text: Color::WHITE,
+                primary: Color::BLACK,
+                primary_text: Color::from_rgb(1., 1., 0.),
+                danger: Color::from_rgb(1., 0., 0.),
+                border: Color::WHITE,
+                selection: Color::from_rgb(1., 1., 0.),
+                weak: Color::from_rgb(0., 1., 1.),
+                strong: Color::from_rgb(1., 1., 0.),
+                accent: Color::from_rgb(1., 0., 1.),
+            },
+        }
+    }
+
+    pub fn typography(self) -> Typography {
+        match self {
+            Theme::Light => Typography::default(),
+            Theme::Dark => Typography {
+                heading: FontChoice::Pixel,
+                ..Default::default()
+            },
+            Theme::HighContrast => Typography {
+                body_size: 24,
+                heading_size: 40,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Text in this theme's body font and size.
+    pub fn body(self, label: impl Into<String>) -> Text {
+        let typography = self.typography();
+        Text::new(label)
+            .font(typography.body)
+            .size(typography.body_size)
+    }
+
+    /// Text in this theme's heading font and size.
+    pub fn heading(self, label: impl Into<String>) -> Text {
+        let typography = self.typography();
+        Text::new(label)
+            .font(typography.heading)
+            .size(typography.heading_size)
+    }
+
+    pub fn root(self) -> Container {
+        let palette = self.palette();
+        Container {
+            background: None,
+            text: palette.text,
+            border: None,
+        }
+    }
+
+    pub fn toolbar(self) -> Container {
+        let palette = self.palette();
+        Container {
+            background: Some(palette.surface),
+            text: palette.text,
+            border: None,
+        }
+    }
+
+    /// `content` with `text` shown by the cursor while it hovers.
+    pub fn tooltip<'a, Message: 'a>(
+        self,
+        content: impl Into<Element<'a, Message, Renderer>>,
+        text: impl ToString,
+    ) -> Tooltip<'a, Message, Renderer> {
+        let palette = self.palette();
+        let typography = self.typography();
+        Tooltip::new(content, text, tooltip::Position::FollowCursor)
+            .font(typography.body)
+            .size(typography.body_size)
+            .padding(5)
+            .style(Container {
+                background: Some(palette.surface),
+                text: palette.text,
+                border: Some(palette.border),
+            })
+    }
+
+    pub fn button(self) -> Button {
+        let palette = self.palette();
+        Button {
+            background: palette.primary,
+            text: palette.primary_text,
+            border: palette.border,
+        }
+    }
+
+    pub fn danger_button(self) -> Button {
+        let palette = self.palette();
+        Button {
+            background: palette.danger,
+            text: Color::WHITE,
+            border: palette.border,
+        }
+    }
+
+    pub fn pick_list(self) -> PickList {
+        PickList(self.palette())
+    }
+
+    /// The colors the 3D scene is drawn with.
+    pub fn scene(self) -> renderer::Palette {
+        let palette = self.palette();
+        let rgb = |color: Color| [color.r, color.g, color.b];
+        renderer::Palette {
+            clear: [
+                palette.background.r,
+                palette.background.g,
+                palette.background.b,
+                palette.background.a,
+            ],
+            weak: rgb(palette.weak),
+            strong: rgb(palette.strong),
+            die: rgb(palette.accent),
+        }
+    }
+}
+
+impl std::fmt::Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Theme::Light => write!(f, "Light"),
+            Theme::Dark => write!(f, "Dark"),
+            Theme::HighContrast => write!(f, "High Contrast"),
+        }
+    }
+}
+
+/// The colors of a theme.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Palette {
+    pub background: Color,
+    pub surface: Color,
+    pub text: Color,
+    pub primary: Color,
+    pub primary_text: Color,
+    pub danger: Color,
+    pub border: Color,
+    pub selection: Color,
+    /// The weakest spirit connection.
+    pub weak: Color,
+    /// The strongest spirit connection.
+    pub strong: Color,
+    pub accent: Color,
+}
+
+pub struct Container {
+    background: Option<Color>,
+    text: Color,
+    border: Option<Color>,
+}
+
+impl container::StyleSheet for Container {
+    fn style(&self) -> container::Style {
+        container::Style {
+            text_color: Some(self.text),
+            background: self.background.map(Background::Color),
+            border_width: if self.border.is_some() { 1. } else { 0. },
+            border_color: self.border.unwrap_or(Color::TRANSPARENT),
+            ..Default::default()
+        }
+    }
+}
+
+pub struct Button {
+    background: Color,
+    text: Color,
+    border: Color,
+}
+
+impl button::StyleSheet for Button {
+    fn active(&self) -> button::Style {
+        button::Style {
+            shadow_offset: Default::default(),
+            background: Some(Background::Color(self.background)),
+            border_radius: 2.,
+            border_width: 1.,
+            border_color: self.border,
+            text_color: self.text,
+        }
+    }
+
+    fn hovered(&self) -> button::Style {
+        button::Style {
+            border_color: self.text,
+            ..self.active()
+        }
+    }
+
+    fn disabled(&self) -> button::Style {
+        let active = self.active();
+        button::Style {
+            text_color: Color {
+                a: 0.5,
+                ..active.text_color
+            },
+            ..active
+        }
+    }
+}
+
+pub struct PickList(Palette);
+
+impl pick_list::StyleSheet for PickList {
+    fn menu(&self) -> menu::Style {
+        menu::Style {
+            text_color: self.0.primary_text,
+            background: Background::Color(self.0.primary),
+            border_width: 1.,
+            border_color: self.0.border,
+            selected_text_color: self.0.background,
+            selected_background: Background::Color(self.0.selection),
+        }
+    }
+
+    fn active(&self) -> pick_list::Style {
+        pick_list::Style {
+            text_color: self.0.primary_text,
+            background: Background::Color(self.0.primary),
+            border_color: self.0.border,
+            ..Default::default()
+        }
+    }
+
+    fn hovered(&self) -> pick_list::Style {
+        pick_list::Style {
+            border_color: self.0.text,
+            ..self.active()
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use iced_solstice::{Backend, Renderer, Settings, Viewport};
+use iced_solstice::{Backend, Renderer, Viewport};
 use iced_winit::{conversion, program, winit::dpi::PhysicalPosition, Clipboard, Debug, Size};
 use wasm_bindgen::prelude::*;
 
@@ -8,10 +8,19 @@ pub fn start() {
     wasm_logger::init(wasm_logger::Config::new(log::Level::Debug));
 }
 
+/// The configured canvas size as `[width, height]`, for sizing the canvas before creating the
+/// `Application`.
+#[wasm_bindgen]
+pub fn window_size() -> Vec<u32> {
+    let window = crate::config::Config::load().window;
+    vec![window.width, window.height]
+}
+
 const MOUSE_DOWN_EVENT: &'static str = "mousedown";
 const MOUSE_MOVE_EVENT: &'static str = "mousemove";
 const MOUSE_UP_EVENT: &'static str = "mouseup";
 const MOUSE_WHEEL_EVENT: &'static str = "wheel";
+const KEY_DOWN_EVENT: &'static str = "keydown";
 
 #[allow(unused)]
 struct Closures {
@@ -20,6 +29,7 @@ struct Closures {
     mouse_move: Closure<dyn FnMut(web_sys::MouseEvent)>,
     mouse_up: Closure<dyn FnMut(web_sys::MouseEvent)>,
     mouse_wheel: Closure<dyn FnMut(web_sys::WheelEvent)>,
+    key_down: Closure<dyn FnMut(web_sys::KeyboardEvent)>,
 }
 
 impl Drop for Closures {
@@ -43,6 +53,18 @@ impl Drop for Closures {
                 self.mouse_up.as_ref().unchecked_ref(),
             )
             .unwrap();
+        self.canvas
+            .remove_event_listener_with_callback(
+                MOUSE_WHEEL_EVENT,
+                self.mouse_wheel.as_ref().unchecked_ref(),
+            )
+            .unwrap();
+        self.canvas
+            .remove_event_listener_with_callback(
+                KEY_DOWN_EVENT,
+                self.key_down.as_ref().unchecked_ref(),
+            )
+            .unwrap();
     }
 }
 
@@ -60,6 +82,9 @@ pub struct Application {
     renderer: Renderer,
     clipboard: Clipboard,
     cursor_position: PhysicalPosition<f64>,
+    ui_scale: f32,
+    clock: crate::clock::Clock,
+    camera: crate::camera_controller::CameraController,
 }
 
 #[wasm_bindgen]
@@ -74,32 +99,41 @@ impl Application {
         let ctx = solstice::glow::Context::from_webgl1_context(ctx);
         let mut ctx = solstice::Context::new(ctx);
 
+        let config = crate::config::Config::load();
+        let ui_scale = config.ui_scale;
         let physical_size =
             iced_winit::winit::dpi::PhysicalSize::new(canvas.width(), canvas.height());
-        let viewport =
-            Viewport::with_physical_size(Size::new(physical_size.width, physical_size.height), 1.);
-        let d2 = renderer::Renderer {
-            d2: renderer::Graphics::new(
+        let viewport = Viewport::with_physical_size(
+            Size::new(physical_size.width, physical_size.height),
+            ui_scale as f64,
+        );
+        let mut d2 = renderer::Renderer::new(
+            renderer::Graphics::new(
                 &mut ctx,
                 physical_size.width as _,
                 physical_size.height as _,
             )
             .unwrap(),
-        };
+        );
+        d2.set_viewport(physical_size.width as _, physical_size.height as _);
 
         let mut debug = Debug::new();
-        let mut renderer = Renderer::new(Backend::new(&mut ctx, Settings::default()));
+        let mut renderer = Renderer::new(Backend::new(&mut ctx, crate::fonts::settings()));
         let mut clipboard = Clipboard::unconnected();
 
         let cursor_position = PhysicalPosition::new(-1.0, -1.0);
         // let modifiers = ModifiersState::default();
 
-        let controls = crate::Application::new({
-            let rand: web_sys::Crypto = web_sys::window().unwrap().crypto()?;
-            let mut seed = [0; 8];
-            rand.get_random_values_with_u8_array(&mut seed)?;
-            u64::from_ne_bytes(seed)
-        });
+        let seed = match config.seed {
+            crate::config::SeedBehavior::Fixed { seed } => seed,
+            crate::config::SeedBehavior::Random => {
+                let rand: web_sys::Crypto = web_sys::window().unwrap().crypto()?;
+                let mut seed = [0; 8];
+                rand.get_random_values_with_u8_array(&mut seed)?;
+                u64::from_ne_bytes(seed)
+            }
+        };
+        let controls = crate::Application::new(seed, config);
 
         let mut state =
             program::State::new(controls, viewport.logical_size(), &mut renderer, &mut debug);
@@ -198,12 +232,50 @@ impl Application {
                 closure
             };
 
+            let key_down = {
+                let events_sx = events_sx.clone();
+                let closure = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+                    log::trace!("key down");
+                    use iced_winit::keyboard;
+                    let modifiers = keyboard::Modifiers {
+                        shift: event.shift_key(),
+                        control: event.ctrl_key(),
+                        alt: event.alt_key(),
+                        logo: event.meta_key(),
+                    };
+                    let key = event.key();
+                    if let Some(key_code) = key_code(&key) {
+                        events_sx
+                            .send(Event::Keyboard(keyboard::Event::KeyPressed {
+                                key_code,
+                                modifiers,
+                            }))
+                            .unwrap();
+                    }
+                    // named keys such as "Enter" are longer than one character and type nothing
+                    let mut chars = key.chars();
+                    if let (Some(c), None) = (chars.next(), chars.next()) {
+                        if !modifiers.control && !modifiers.logo {
+                            events_sx
+                                .send(Event::Keyboard(keyboard::Event::CharacterReceived(c)))
+                                .unwrap();
+                        }
+                    }
+                }) as Box<dyn FnMut(_)>);
+                canvas.add_event_listener_with_callback(
+                    KEY_DOWN_EVENT,
+                    closure.as_ref().unchecked_ref(),
+                )?;
+                closure
+            };
+
             Closures {
                 canvas,
                 mouse_down,
                 mouse_move,
                 mouse_up,
                 mouse_wheel,
+                key_down,
             }
         };
 
@@ -218,21 +290,68 @@ impl Application {
             renderer,
             clipboard,
             cursor_position,
+            ui_scale,
+            clock: Default::default(),
+            camera: Default::default(),
         })
     }
 
-    pub fn update(&mut self, t: f32) {
-        for event in self.events.try_iter() {
-            match &event {
+    /// Replaces the current campaign with the contents of a campaign file.
+    pub fn import_campaign(&mut self, contents: &str) -> Result<(), JsValue> {
+        let campaign = crate::campaign::Campaign::from_json(contents)
+            .map_err(|err| JsValue::from_str(&format!("{:#}", err)))?;
+        self.state
+            .queue_message(crate::Message::CampaignOpened(campaign));
+        Ok(())
+    }
+
+    /// Runs a frame. `t` is a timestamp in milliseconds, as from `performance.now()`.
+    pub fn update(&mut self, t: f64) {
+        for mut event in self.events.try_iter() {
+            match &mut event {
                 iced_winit::Event::Mouse(iced_winit::mouse::Event::CursorMoved { position }) => {
                     self.cursor_position = iced_winit::winit::dpi::PhysicalPosition::new(
                         position.x.into(),
                         position.y.into(),
                     );
+                    // the UI works in logical pixels
+                    *position = conversion::cursor_position(
+                        self.cursor_position,
+                        self.viewport.scale_factor(),
+                    );
                 }
                 _ => {}
             }
-            self.state.queue_event(event);
+            // keys typed into a text input are the UI's alone
+            let editing_text = self.state.program().is_editing_text();
+            if !editing_text {
+                self.clock.handle_event(&event);
+            }
+            let status = self.camera.handle_event(
+                &event,
+                self.state.mouse_interaction(),
+                editing_text,
+                self.d2.camera_mut(),
+            );
+            if status == iced_winit::event::Status::Ignored {
+                self.state.queue_event(event);
+            }
+        }
+
+        // Anything under the cursor that the UI isn't using is hovered in the scene
+        let hovered = if self.state.mouse_interaction() == iced_winit::mouse::Interaction::Idle {
+            self.d2
+                .pick([self.cursor_position.x as f32, self.cursor_position.y as f32])
+        } else {
+            None
+        };
+        self.d2.set_hovered(hovered);
+        if let (true, Some(pick)) = (self.camera.take_click(), hovered) {
+            self.state.queue_message(crate::Message::ScenePicked(pick));
+        }
+
+        for message in self.state.program().session_messages() {
+            self.state.queue_message(message);
         }
 
         if !self.state.is_queue_empty() {
@@ -244,27 +363,27 @@ impl Application {
                 &mut self.clipboard,
                 &mut self.debug,
             );
+
+            let ui_scale = self.state.program().config().ui_scale;
+            if ui_scale != self.ui_scale {
+                self.ui_scale = ui_scale;
+                self.viewport =
+                    Viewport::with_physical_size(self.viewport.physical_size(), ui_scale as f64);
+            }
         }
 
-        solstice::Renderer::clear(
-            &mut self.ctx,
-            solstice::ClearSettings {
-                color: Some(
-                    solstice::Color {
-                        red: 1f32,
-                        blue: 1.,
-                        green: 1.,
-                        alpha: 1.,
-                    }
-                    .into(),
-                ),
-                ..Default::default()
-            },
-        );
+        self.d2.set_palette(self.state.program().theme().scene());
+        self.d2
+            .set_animated(self.state.program().config().animation);
+        self.d2.clear(&mut self.ctx);
 
-        const DURATION: f32 = 10.;
-        let t = (t / 1000.) % DURATION / DURATION;
-        self.d2.draw(&mut self.ctx, t);
+        self.clock.tick(t / 1000.);
+        self.d2
+            .set_constellation(&self.state.program().constellation());
+        for _ in 0..self.clock.fixed_steps() {
+            self.d2.update(self.clock.fixed_step());
+        }
+        self.d2.draw(&mut self.ctx);
 
         self.renderer.with_primitives(|backend, primitives| {
             backend.present(
@@ -276,3 +395,67 @@ impl Application {
         });
     }
 }
+
+/// Maps a DOM `KeyboardEvent.key` to the key code iced expects, for the keys the scene's controls
+/// and text inputs react to.
+fn key_code(key: &str) -> Option<iced_winit::keyboard::KeyCode> {
+    use iced_winit::keyboard::KeyCode;
+    let key_code = match key {
+        " " => KeyCode::Space,
+        "." => KeyCode::Period,
+        "," => KeyCode::Comma,
+        "+" => KeyCode::Plus,
+        "=" => KeyCode::Equals,
+        "-" => KeyCode::Minus,
+        "/" => KeyCode::Slash,
+        "0" => KeyCode::Key0,
+        "1" => KeyCode::Key1,
+        "2" => KeyCode::Key2,
+        "3" => KeyCode::Key3,
+        "4" => KeyCode::Key4,
+        "5" => KeyCode::Key5,
+        "6" => KeyCode::Key6,
+        "7" => KeyCode::Key7,
+        "8" => KeyCode::Key8,
+        "9" => KeyCode::Key9,
+        "a" | "A" => KeyCode::A,
+        "b" | "B" => KeyCode::B,
+        "c" | "C" => KeyCode::C,
+        "d" | "D" => KeyCode::D,
+        "e" | "E" => KeyCode::E,
+        "f" | "F" => KeyCode::F,
+        "g" | "G" => KeyCode::G,
+        "h" | "H" => KeyCode::H,
+        "i" | "I" => KeyCode::I,
+        "j" | "J" => KeyCode::J,
+        "k" | "K" => KeyCode::K,
+        "l" | "L" => KeyCode::L,
+        "m" | "M" => KeyCode::M,
+        "n" | "N" => KeyCode::N,
+        "o" | "O" => KeyCode::O,
+        "p" | "P" => KeyCode::P,
+        "q" | "Q" => KeyCode::Q,
+        "r" | "R" => KeyCode::R,
+        "s" | "S" => KeyCode::S,
+        "t" | "T" => KeyCode::T,
+        "u" | "U" => KeyCode::U,
+        "v" | "V" => KeyCode::V,
+        "w" | "W" => KeyCode::W,
+        "x" | "X" => KeyCode::X,
+        "y" | "Y" => KeyCode::Y,
+        "z" | "Z" => KeyCode::Z,
+        "Backspace" => KeyCode::Back,
+        "Delete" => KeyCode::Delete,
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "Escape" => KeyCode::Escape,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "ArrowLeft" => KeyCode::Left,
+        "ArrowRight" => KeyCode::Right,
+        "ArrowUp" => KeyCode::Up,
+        "ArrowDown" => KeyCode::Down,
+        _ => return None,
+    };
+    Some(key_code)
+}
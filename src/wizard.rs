@@ -0,0 +1,176 @@
+//! The steps of the character creator's wizard, which fills in the same `CharacterBuild` as the
+//! grid one part at a time: each connection's spirits, strongest first, then the base
+//! prerogatives, the optional burdens and a review.
+
+use crate::{
+    build::CharacterBuild,
+    compendium::Compendium,
+    rules::{BASE_PREROGATIVE_COUNT, CONNECTION_COUNTS, MAX_OPTIONAL_BURDENS},
+};
+use spirits_within::Connection;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Choosing the spirits with the connection at this index of `CONNECTION_COUNTS`.
+    Spirits(usize),
+    Prerogatives,
+    Burdens,
+    Review,
+}
+
+impl Default for Step {
+    fn default() -> Self {
+        Step::Spirits(0)
+    }
+}
+
+impl Step {
+    pub const COUNT: usize = CONNECTION_COUNTS.len() + 3;
+
+    /// The step's position, counting from 1.
+    pub fn number(self) -> usize {
+        match self {
+            Step::Spirits(index) => index + 1,
+            Step::Prerogatives => CONNECTION_COUNTS.len() + 1,
+            Step::Burdens => CONNECTION_COUNTS.len() + 2,
+            Step::Review => CONNECTION_COUNTS.len() + 3,
+        }
+    }
+
+    pub fn next(self) -> Option<Self> {
+        match self {
+            Step::Spirits(index) if index + 1 < CONNECTION_COUNTS.len() => {
+                Some(Step::Spirits(index + 1))
+            }
+            Step::Spirits(_) => Some(Step::Prerogatives),
+            Step::Prerogatives => Some(Step::Burdens),
+            Step::Burdens => Some(Step::Review),
+            Step::Review => None,
+        }
+    }
+
+    pub fn previous(self) -> Option<Self> {
+        match self {
+            Step::Spirits(0) => None,
+            Step::Spirits(index) => Some(Step::Spirits(index - 1)),
+            Step::Prerogatives => Some(Step::Spirits(CONNECTION_COUNTS.len() - 1)),
+            Step::Burdens => Some(Step::Prerogatives),
+            Step::Review => Some(Step::Burdens),
+        }
+    }
+
+    /// The connection whose spirits are chosen in this step, if any.
+    pub fn connection(self) -> Option<Connection> {
+        match self {
+            Step::Spirits(index) => Some(CONNECTION_COUNTS[index].0),
+            _ => None,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Step::Spirits(index) => match CONNECTION_COUNTS[index].0 {
+                Connection::Mastery => "Masteries",
+                Connection::Expertise => "Expertises",
+                Connection::Competence => "Competences",
+                Connection::Ineptitude => "Ineptitudes",
+            },
+            Step::Prerogatives => "Base prerogatives",
+            Step::Burdens => "Optional burdens",
+            Step::Review => "Review",
+        }
+    }
+
+    /// What the step asks for and why, for a player new to the rules.
+    pub fn explanation(self, compendium: &Compendium) -> String {
+        match self {
+            Step::Spirits(index) => {
+                let (connection, count) = CONNECTION_COUNTS[index];
+                format!(
+                    "Choose the {} spirits your character has {} with. {} Spirits chosen in an \
+                     earlier step keep their connection. Each connection adds to the stat of \
+                     its spirit.",
+                    count,
+                    connection,
+                    compendium.connection(connection)
+                )
+            }
+            Step::Prerogatives => format!(
+                "Choose {} base prerogatives. They are what your character has going for them \
+                 beyond the spirits, and most raise a stat, which can't go past its maximum. \
+                 The same prerogative can be chosen more than once unless it says otherwise.",
+                BASE_PREROGATIVE_COUNT
+            ),
+            Step::Burdens => format!(
+                "Optionally take on up to {} burdens, each in exchange for another \
+                 prerogative. A burden is a complication the game master can bring into play; \
+                 leave this step empty to play without any.",
+                MAX_OPTIONAL_BURDENS
+            ),
+            Step::Review => "Check the finished character. Go back to change anything, or \
+                             finalize it to lock it in."
+                .into(),
+        }
+    }
+
+    /// Why `build` isn't ready to move on from this step, if it isn't.
+    pub fn unfinished(self, build: &CharacterBuild) -> Option<String> {
+        match self {
+            Step::Spirits(index) => {
+                let (connection, _) = CONNECTION_COUNTS[index];
+                match build.remaining(connection) {
+                    0 => None,
+                    remaining => Some(format!("Choose {} more for {}", remaining, connection)),
+                }
+            }
+            Step::Prerogatives | Step::Burdens => {
+                match build.prerogatives().iter().filter(|p| p.is_none()).count() {
+                    0 => build.stats().err().map(|err| format!("{:#}", err)),
+                    missing => Some(format!("Choose {} more base prerogatives", missing)),
+                }
+            }
+            Step::Review => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spirits_within::Spirit;
+
+    #[test]
+    fn steps_go_through_every_connection_in_order() {
+        let mut steps = vec![Step::default()];
+        while let Some(next) = steps.last().unwrap().next() {
+            steps.push(next);
+        }
+        assert_eq!(steps.len(), Step::COUNT);
+        assert_eq!(steps.last(), Some(&Step::Review));
+        for (number, step) in steps.iter().enumerate() {
+            assert_eq!(step.number(), number + 1);
+        }
+        let connections: Vec<Connection> = steps.iter().filter_map(|s| s.connection()).collect();
+        let expected: Vec<Connection> = CONNECTION_COUNTS.iter().map(|&(c, _)| c).collect();
+        assert_eq!(connections, expected);
+
+        for pair in steps.windows(2) {
+            assert_eq!(pair[1].previous(), Some(pair[0]));
+        }
+        assert_eq!(Step::default().previous(), None);
+    }
+
+    #[test]
+    fn spirit_steps_are_finished_when_the_connection_is_used_up() {
+        let mut build = CharacterBuild::new();
+        let (mastery, count) = CONNECTION_COUNTS[0];
+        assert!(Step::Spirits(0).unfinished(&build).is_some());
+        for spirit in Spirit::LIST.into_iter().take(count) {
+            build.set_connection(spirit, Some(mastery));
+        }
+        assert_eq!(Step::Spirits(0).unfinished(&build), None);
+        assert!(Step::Spirits(1).unfinished(&build).is_some());
+        assert!(Step::Prerogatives.unfinished(&build).is_some());
+        assert!(Step::Burdens.unfinished(&build).is_some());
+    }
+}
@@ -0,0 +1,289 @@
+//! Runs a session server and clients in-process over real localhost WebSockets.
+
+use spirits_within_app::{
+    client::{Client, Event},
+    protocol::{
+        ClientMessage, PlayerId, PlayerRoll, ServerMessage, DIE_SIDES, MAX_DICE, MAX_NAME_CHARS,
+        VERSION,
+    },
+    server::Server,
+};
+use std::time::{Duration, Instant};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Starts a server on a free local port, returning its URL.
+fn start_server() -> String {
+    serve(Server::bind("127.0.0.1:0").unwrap().with_seed(0))
+}
+
+fn serve(server: Server) -> String {
+    let url = format!("ws://{}", server.local_addr().unwrap());
+    std::thread::spawn(move || server.run());
+    url
+}
+
+/// The client's next event, failing the test if none comes in time.
+fn next(client: &Client) -> Event {
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        if let Some(event) = client.events().next() {
+            return event;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for an event");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn next_message(client: &Client) -> ServerMessage {
+    match next(client) {
+        Event::Message(message) => message,
+        Event::Closed(reason) => panic!("connection closed: {}", reason),
+    }
+}
+
+/// Connects as `name`, returning the client once it is welcomed, along with its id.
+fn join(url: &str, name: &str) -> (Client, PlayerId) {
+    let client = Client::connect(url, name);
+    match next_message(&client) {
+        ServerMessage::Welcome { you, .. } => (client, you),
+        other => panic!("expected a welcome, got {:?}", other),
+    }
+}
+
+/// Says hello over a bare socket, returning the server's reply.
+fn hello(url: &str, version: u32, name: &str) -> ServerMessage {
+    let (mut socket, _) = tungstenite::connect(url).unwrap();
+    let hello = ClientMessage::Hello {
+        version,
+        name: name.into(),
+    };
+    socket
+        .send(tungstenite::Message::Text(hello.to_json().unwrap()))
+        .unwrap();
+    let reply = match socket.read().unwrap() {
+        tungstenite::Message::Text(text) => ServerMessage::from_json(&text).unwrap(),
+        other => panic!("expected a text frame, got {:?}", other),
+    };
+    assert!(matches!(
+        socket.read(),
+        Ok(tungstenite::Message::Close(_)) | Err(_)
+    ));
+    reply
+}
+
+#[test]
+fn players_see_each_other_join_and_leave() {
+    let url = start_server();
+
+    let ada = Client::connect(&url, "Ada");
+    let ada_id = match next_message(&ada) {
+        ServerMessage::Welcome {
+            version,
+            you,
+            players,
+        } => {
+            assert_eq!(version, VERSION);
+            assert_eq!(players.len(), 1);
+            assert_eq!(players[0].name, "Ada");
+            you
+        }
+        other => panic!("expected a welcome, got {:?}", other),
+    };
+
+    // the welcome lists everyone seated, newcomer last
+    let bo = Client::connect(&url, "Bo");
+    let bo_id = match next_message(&bo) {
+        ServerMessage::Welcome { you, players, .. } => {
+            let names: Vec<&str> = players.iter().map(|p| p.name.as_str()).collect();
+            assert_eq!(names, ["Ada", "Bo"]);
+            you
+        }
+        other => panic!("expected a welcome, got {:?}", other),
+    };
+    assert_ne!(ada_id, bo_id);
+    match next_message(&ada) {
+        ServerMessage::Joined { player } => {
+            assert_eq!((player.id, player.name.as_str()), (bo_id, "Bo"))
+        }
+        other => panic!("expected Bo to join, got {:?}", other),
+    }
+
+    drop(bo);
+    assert_eq!(next_message(&ada), ServerMessage::Left { player: bo_id });
+}
+
+#[test]
+fn shared_characters_and_rolls_reach_everyone() {
+    let url = start_server();
+    let (ada, ada_id) = join(&url, "Ada");
+    let (bo, _) = join(&url, "Bo");
+    next_message(&ada);
+
+    let mut rng: rand::rngs::SmallRng = rand::SeedableRng::seed_from_u64(9);
+    let character = spirits_within_app::rules::random(&mut rng);
+    ada.send(ClientMessage::ShareCharacter {
+        character: character.clone(),
+    });
+    let shared = ServerMessage::CharacterShared {
+        player: ada_id,
+        character,
+    };
+    assert_eq!(next_message(&ada), shared);
+    assert_eq!(next_message(&bo), shared);
+
+    ada.send(ClientMessage::Roll {
+        dice: 3,
+        spirit: Some(spirits_within::Spirit::Shadows),
+    });
+    let rolled = next_message(&bo);
+    match &rolled {
+        ServerMessage::Rolled {
+            player,
+            spirit,
+            results,
+        } => {
+            assert_eq!(*player, ada_id);
+            assert_eq!(*spirit, Some(spirits_within::Spirit::Shadows));
+            assert_eq!(results.len(), 3);
+            assert!(results.iter().all(|r| (1..=DIE_SIDES).contains(r)));
+        }
+        other => panic!("expected a roll, got {:?}", other),
+    }
+    // everyone sees the same dice
+    assert_eq!(next_message(&ada), rolled);
+}
+
+#[test]
+fn group_checks_roll_for_everyone() {
+    let url = start_server();
+    let (gm, gm_id) = join(&url, "GM");
+    let (bo, bo_id) = join(&url, "Bo");
+    next_message(&gm);
+
+    gm.send(ClientMessage::GroupCheck {
+        stat: spirits_within::Stat::Knowledge,
+        dice: 2,
+    });
+    let check = next_message(&bo);
+    match &check {
+        ServerMessage::GroupCheck {
+            player,
+            stat,
+            rolls,
+        } => {
+            assert_eq!(*player, gm_id);
+            assert_eq!(*stat, spirits_within::Stat::Knowledge);
+            let players: Vec<_> = rolls.iter().map(|roll| roll.player).collect();
+            assert_eq!(players, [gm_id, bo_id]);
+            assert!(rolls
+                .iter()
+                .all(|PlayerRoll { results, .. }| results.len() == 2));
+        }
+        other => panic!("expected a group check, got {:?}", other),
+    }
+    assert_eq!(next_message(&gm), check);
+}
+
+#[test]
+fn bad_rolls_are_refused_to_the_roller_only() {
+    let url = start_server();
+    let (ada, _) = join(&url, "Ada");
+    let (bo, _) = join(&url, "Bo");
+    next_message(&ada);
+
+    ada.send(ClientMessage::Roll {
+        dice: MAX_DICE + 1,
+        spirit: None,
+    });
+    assert!(matches!(next_message(&ada), ServerMessage::Error { .. }));
+
+    // Bo hears about their own roll, not Ada's error
+    bo.send(ClientMessage::Roll {
+        dice: 1,
+        spirit: None,
+    });
+    assert!(matches!(next_message(&bo), ServerMessage::Rolled { .. }));
+}
+
+#[test]
+fn bad_group_checks_are_refused_once_and_roll_nothing() {
+    let url = start_server();
+    let (gm, _) = join(&url, "GM");
+    let (bo, _) = join(&url, "Bo");
+    next_message(&gm);
+
+    gm.send(ClientMessage::GroupCheck {
+        stat: spirits_within::Stat::Discipline,
+        dice: MAX_DICE + 1,
+    });
+    assert!(matches!(next_message(&gm), ServerMessage::Error { .. }));
+
+    // one error for the caller, however many are seated, and nothing for anyone else
+    gm.send(ClientMessage::Roll {
+        dice: 1,
+        spirit: None,
+    });
+    assert!(matches!(next_message(&gm), ServerMessage::Rolled { .. }));
+    assert!(matches!(next_message(&bo), ServerMessage::Rolled { .. }));
+}
+
+#[test]
+fn other_versions_are_rejected() {
+    let url = start_server();
+    assert!(matches!(
+        hello(&url, VERSION + 1, "Future"),
+        ServerMessage::Rejected { .. }
+    ));
+}
+
+#[test]
+fn names_are_trimmed_and_must_fit() {
+    let url = start_server();
+    for name in ["", "   ", &"x".repeat(MAX_NAME_CHARS + 1)] {
+        assert!(
+            matches!(hello(&url, VERSION, name), ServerMessage::Rejected { .. }),
+            "{:?} was let in",
+            name
+        );
+    }
+
+    let padded = format!("  {}  ", "x".repeat(MAX_NAME_CHARS));
+    let ada = Client::connect(&url, &padded);
+    match next_message(&ada) {
+        ServerMessage::Welcome { players, .. } => {
+            assert_eq!(players[0].name, "x".repeat(MAX_NAME_CHARS))
+        }
+        other => panic!("expected a welcome, got {:?}", other),
+    }
+}
+
+#[test]
+fn silent_clients_are_dropped() {
+    let url = serve(
+        Server::bind("127.0.0.1:0")
+            .unwrap()
+            .with_hello_timeout(Duration::from_millis(100)),
+    );
+    let (mut socket, _) = tungstenite::connect(&url).unwrap();
+    let started = Instant::now();
+    loop {
+        match socket.read() {
+            Ok(tungstenite::Message::Close(_)) | Err(_) => break,
+            Ok(_) => assert!(started.elapsed() < TIMEOUT, "still connected"),
+        }
+    }
+    assert!(started.elapsed() < TIMEOUT);
+}
+
+#[test]
+fn unreachable_servers_close_the_client() {
+    // nothing listens on a port we just freed
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let client = Client::connect(&format!("ws://127.0.0.1:{}", port), "Ada");
+    assert!(matches!(next(&client), Event::Closed(_)));
+}